@@ -0,0 +1,856 @@
+// `angler_bot_v2 admin` - edit bot data from a terminal
+// works directly against the data files, so it doesn't need a display or a running bot
+
+use std::fs;
+use std::num::NonZeroU64;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serenity::all::{GuildId, UserId};
+
+use crate::cli::{find_item, generate_bait, parse_number};
+use crate::data_management::config::Config;
+use crate::data_management::data_path;
+use crate::data_management::guildfile::{GuildFile, GuildSettings};
+use crate::data_management::monetary::MonetaryAmount;
+use crate::data_management::qte_stats::QteStats;
+use crate::data_management::userfile::{UserFile, UserValues};
+use crate::fishing::Attribute;
+use crate::fishing::fish_data::fish::Pond;
+use crate::fishing::inventory::GearKind;
+use crate::fishing::rod_data::lines::Line;
+use crate::fishing::rod_data::reels::Reel;
+use crate::fishing::rod_data::rods::RodBase;
+use crate::fishing::rod_data::sinkers::Sinker;
+use crate::fishing::shop::{Shop, ShopState};
+use crate::logging::{find_error_code, logs_dir};
+use crate::{hey, yay};
+
+
+pub enum MoneyAction {
+    Give,
+    Take,
+    Set,
+}
+
+pub enum ChannelAction {
+    List,
+    Add(u64),
+    Remove(u64),
+}
+
+/// The per-user commands take `--guild <GUILD_ID>` to work on a guild's profile instead of the global one
+pub enum AdminCommand {
+    Users,
+    User {
+        user: UserId,
+        guild: Option<GuildId>,
+    },
+    Money {
+        user: UserId,
+        guild: Option<GuildId>,
+        action: MoneyAction,
+        amount: f32,
+    },
+    AddItem {
+        user: UserId,
+        guild: Option<GuildId>,
+        kind: GearKind,
+        name: String,
+    },
+    RemoveItem {
+        user: UserId,
+        guild: Option<GuildId>,
+        kind: GearKind,
+        index: usize,
+    },
+    Reset {
+        user: UserId,
+        guild: Option<GuildId>,
+    },
+    Channels {
+        guild: GuildId,
+        action: ChannelAction,
+    },
+    ShopShow,
+    ShopRestock,
+    Validate,
+    Export(PathBuf),
+    Import {
+        path: PathBuf,
+        overwrite: bool,
+    },
+    ErrorCode(String),
+    Flagged,
+    Unflag(UserId),
+    Migrate {
+        guild: GuildId,
+        // None copies every user with a global profile
+        user: Option<UserId>,
+        overwrite: bool,
+    },
+}
+
+/// Everything `export` writes and `import` reads
+#[derive(Serialize, Deserialize)]
+struct DataBundle {
+    users: Vec<(u64, UserValues)>,
+    /// per-guild profiles as `(guild id, user id, profile)`, exports from before them have none
+    #[serde(default)]
+    guild_users: Vec<(u64, u64, UserValues)>,
+    guilds: Vec<(u64, GuildFile)>,
+    shop: ShopState,
+}
+
+pub fn parse(args: impl Iterator<Item = String>) -> Result<AdminCommand, String> {
+    let args: Vec<String> = args.collect();
+    let mut args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let guild = take_guild_flag(&mut args)?;
+
+    let command = match args.as_slice() {
+        ["users"] => AdminCommand::Users,
+        ["user", id] => AdminCommand::User { user: parse_user(id)?, guild },
+        ["money", id, action, amount] => {
+            let action = match *action {
+                "give" => MoneyAction::Give,
+                "take" => MoneyAction::Take,
+                "set" => MoneyAction::Set,
+                other => return Err(format!("Unknown money action: {}", other)),
+            };
+            let amount: f32 = parse_number("money", amount)?;
+            if !amount.is_finite() || amount < 0.0 {
+                return Err("Amount must be a positive number".to_string());
+            }
+            AdminCommand::Money {
+                user: parse_user(id)?,
+                guild,
+                action,
+                amount,
+            }
+        }
+        ["item", id, "add", kind, name @ ..] if !name.is_empty() => AdminCommand::AddItem {
+            user: parse_user(id)?,
+            guild,
+            kind: parse_kind(kind)?,
+            name: name.join(" "),
+        },
+        ["item", id, "remove", kind, index] => AdminCommand::RemoveItem {
+            user: parse_user(id)?,
+            guild,
+            kind: parse_kind(kind)?,
+            index: parse_number("index", index)?,
+        },
+        ["reset", id] => AdminCommand::Reset { user: parse_user(id)?, guild },
+        ["channels", id, rest @ ..] => {
+            let guild = parse_guild(id)?;
+            let action = match rest {
+                ["list"] => ChannelAction::List,
+                ["add", channel] => ChannelAction::Add(parse_number("channel id", channel)?),
+                ["remove", channel] => ChannelAction::Remove(parse_number("channel id", channel)?),
+                _ => return Err("Usage: admin channels <GUILD_ID> list|add|remove [ID]".to_string()),
+            };
+            AdminCommand::Channels { guild, action }
+        }
+        ["shop", "show"] => AdminCommand::ShopShow,
+        ["shop", "restock"] => AdminCommand::ShopRestock,
+        ["validate"] => AdminCommand::Validate,
+        ["export", path] => AdminCommand::Export(PathBuf::from(path)),
+        ["import", path] => AdminCommand::Import {
+            path: PathBuf::from(path),
+            overwrite: false,
+        },
+        ["import", path, "--overwrite"] => AdminCommand::Import {
+            path: PathBuf::from(path),
+            overwrite: true,
+        },
+        ["errorcode", code] => AdminCommand::ErrorCode(code.to_string()),
+        ["flagged"] => AdminCommand::Flagged,
+        ["unflag", id] => AdminCommand::Unflag(parse_user(id)?),
+        ["migrate", guild, user, rest @ ..] => {
+            let overwrite = match rest {
+                [] => false,
+                ["--overwrite"] => true,
+                _ => return Err("Usage: admin migrate <GUILD_ID> <USER_ID|all> [--overwrite]".to_string()),
+            };
+            AdminCommand::Migrate {
+                guild: parse_guild(guild)?,
+                user: if *user == "all" { None } else { Some(parse_user(user)?) },
+                overwrite,
+            }
+        }
+        [] => return Err("Missing admin command".to_string()),
+        _ => return Err(format!("Unknown admin command: {}", args.join(" "))),
+    };
+
+    let per_user = matches!(
+        command,
+        AdminCommand::User { .. } | AdminCommand::Money { .. } | AdminCommand::AddItem { .. }
+            | AdminCommand::RemoveItem { .. } | AdminCommand::Reset { .. }
+    );
+    if guild.is_some() && !per_user {
+        return Err("--guild only works with user, money, item and reset".to_string());
+    }
+
+    Ok(command)
+}
+
+/// Take `--guild <GUILD_ID>` out of the arguments, wherever it is
+fn take_guild_flag(args: &mut Vec<&str>) -> Result<Option<GuildId>, String> {
+    let Some(index) = args.iter().position(|a| *a == "--guild") else {
+        return Ok(None);
+    };
+    let guild = parse_guild(args.get(index + 1).ok_or("Missing guild id after --guild")?)?;
+    args.drain(index..=index + 1);
+    Ok(Some(guild))
+}
+
+fn parse_guild(id: &str) -> Result<GuildId, String> {
+    let id: u64 = parse_number("guild id", id)?;
+    let id = NonZeroU64::new(id).ok_or("Guild ids can't be 0")?;
+    Ok(GuildId::from(id))
+}
+
+fn parse_user(id: &str) -> Result<UserId, String> {
+    let id: u64 = parse_number("user id", id)?;
+    if id == 0 {
+        return Err("User ids can't be 0".to_string());
+    }
+    Ok(UserId::new(id))
+}
+
+fn parse_kind(kind: &str) -> Result<GearKind, String> {
+    GearKind::from_name(kind).ok_or(format!("Unknown item kind: {}", kind))
+}
+
+/// Read an existing profile (global with None), without generating a file for unknown ids
+fn existing_user(id: UserId, guild: Option<GuildId>) -> Result<UserFile, String> {
+    if !UserFile::exists_in(&id, guild) {
+        return Err(match guild {
+            Some(guild) => format!("No data file for user {} in guild {}", id, guild),
+            None => format!("No data file for user {}", id),
+        });
+    }
+    UserFile::read_in(&id, guild).map_err(|e| e.to_string())
+}
+
+/// Describe a profile for messages, e.g. `user 42 in guild 7`
+fn profile_name(id: UserId, guild: Option<GuildId>) -> String {
+    match guild {
+        Some(guild) => format!("user {} in guild {}", id, guild),
+        None => format!("user {}", id),
+    }
+}
+
+fn read_ron<T: for<'a> Deserialize<'a>>(path: &Path) -> Result<T, String> {
+    let data = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    ron::from_str(&data).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Every `<id>.ron` file in a directory
+fn ron_files(dir: &Path) -> Vec<(u64, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+        .filter_map(|path| Some((path.file_stem()?.to_str()?.parse::<u64>().ok()?, path)))
+        .collect::<Vec<_>>();
+    files.sort_by_key(|(id, _)| *id);
+    files
+}
+
+/// Every per-guild profile, as `(guild id, user id, path)`
+fn guild_profile_files() -> Vec<(u64, u64, PathBuf)> {
+    let Ok(entries) = fs::read_dir(data_path("guilds")) else {
+        return Vec::new();
+    };
+
+    let mut guilds = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| Some((path.file_name()?.to_str()?.parse::<u64>().ok()?, path)))
+        .collect::<Vec<_>>();
+    guilds.sort_by_key(|(id, _)| *id);
+
+    guilds
+        .into_iter()
+        .flat_map(|(guild, dir)| {
+            ron_files(&dir.join("users")).into_iter().map(move |(user, path)| (guild, user, path))
+        })
+        .collect()
+}
+
+/// Entry point for `angler_bot_v2 admin`
+pub fn run(command: AdminCommand) -> Result<(), String> {
+    fs::create_dir_all(data_path("users")).map_err(|e| format!("Failed to create users directory: {}", e))?;
+    fs::create_dir_all(data_path("guilds")).map_err(|e| format!("Failed to create guilds directory: {}", e))?;
+
+    match command {
+        AdminCommand::Users => list_users(),
+        AdminCommand::User { user, guild } => show_user(user, guild),
+        AdminCommand::Money { user, guild, action, amount } => change_money(user, guild, action, amount),
+        AdminCommand::AddItem { user, guild, kind, name } => add_item(user, guild, kind, &name),
+        AdminCommand::RemoveItem { user, guild, kind, index } => remove_item(user, guild, kind, index),
+        AdminCommand::Reset { user, guild } => {
+            existing_user(user, guild)?;
+            UserFile { guild, ..UserFile::new(&user) }.update();
+            yay!("Reset {} to a fresh profile", profile_name(user, guild));
+            Ok(())
+        }
+        AdminCommand::Channels { guild, action } => edit_channels(guild, action),
+        AdminCommand::ShopShow => {
+            print_shop(&Shop::load_state());
+            Ok(())
+        }
+        AdminCommand::ShopRestock => {
+            let mut shop = Shop::load_catalog();
+            shop.restock();
+            yay!("Restocked the shop");
+            print_shop(&shop.state);
+            Ok(())
+        }
+        AdminCommand::Validate => validate(),
+        AdminCommand::Export(path) => export(&path),
+        AdminCommand::Import { path, overwrite } => import(&path, overwrite),
+        AdminCommand::ErrorCode(code) => lookup_error_code(&code),
+        AdminCommand::Migrate { guild, user, overwrite } => migrate(guild, user, overwrite),
+        AdminCommand::Flagged => {
+            list_flagged();
+            Ok(())
+        }
+        AdminCommand::Unflag(id) => {
+            if !QteStats::clear_flag(&id) {
+                return Err(format!("User {} isn't flagged", id));
+            }
+            yay!("Cleared the flag on user {}", id);
+            Ok(())
+        }
+    }
+}
+
+fn list_flagged() {
+    let flagged = QteStats::flagged();
+    for record in &flagged {
+        let when = record
+            .flagged_at
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .map_or("unknown".to_string(), |t| t.format("%Y-%m-%d %H:%M").to_string());
+        let times: Vec<String> = record.reply_times.iter().map(|t| format!("{:.2}", t)).collect();
+
+        println!("{:<22}{}  {}", record.user_id, when, record.flag_reason.as_deref().unwrap_or_default());
+        println!("{:<22}recent replies (s): {}", "", times.join(", "));
+    }
+    println!("{} flagged players", flagged.len());
+}
+
+fn migrate(guild: GuildId, user: Option<UserId>, overwrite: bool) -> Result<(), String> {
+    let ids = match user {
+        Some(id) => vec![id],
+        None => UserFile::list_ids(),
+    };
+
+    let mut copied = 0;
+    let mut skipped = 0;
+    for id in ids {
+        match UserFile::copy_to_guild(&id, guild, overwrite) {
+            Ok(true) => copied += 1,
+            Ok(false) => skipped += 1,
+            Err(e) => hey!("Failed to copy user {}: {}", id, e),
+        }
+    }
+
+    yay!("Copied {} profiles into guild {}", copied, guild);
+    if skipped > 0 {
+        hey!("Skipped {} users that already have a profile there, use --overwrite to replace them", skipped);
+    }
+    Ok(())
+}
+
+/// How many earlier lines from the same user to show with an error code
+const ERROR_CONTEXT_LINES: usize = 10;
+
+fn lookup_error_code(code: &str) -> Result<(), String> {
+    let matches = find_error_code(&logs_dir(), code, None, ERROR_CONTEXT_LINES);
+    if matches.is_empty() {
+        return Err(format!("No log entry found for error code {}", code));
+    }
+
+    for found in matches {
+        for line in &found.context {
+            println!("  {}", line);
+        }
+        println!("> {}\n", found.line);
+    }
+    Ok(())
+}
+
+fn list_users() -> Result<(), String> {
+    let ids = UserFile::list_ids();
+    for id in &ids {
+        match existing_user(*id, None) {
+            Ok(user) => println!(
+                "{:<22}{:>16}{:>10} catches",
+                id, user.file.balance, user.file.total_catches
+            ),
+            Err(e) => println!("{:<22}unreadable: {}", id, e),
+        }
+    }
+    println!("{} users", ids.len());
+
+    let guild_profiles = guild_profile_files();
+    if !guild_profiles.is_empty() {
+        println!("\nGuild profiles:");
+        for (guild, id, path) in &guild_profiles {
+            match read_ron::<UserValues>(path) {
+                Ok(values) => println!(
+                    "{:<22}guild {:<22}{:>16}{:>10} catches",
+                    id, guild, values.balance, values.total_catches
+                ),
+                Err(e) => println!("{:<22}guild {:<22}unreadable: {}", id, guild, e),
+            }
+        }
+        println!("{} guild profiles", guild_profiles.len());
+    }
+    Ok(())
+}
+
+fn show_user(id: UserId, guild: Option<GuildId>) -> Result<(), String> {
+    let user = existing_user(id, guild)?;
+    let values = &user.file;
+    let inventory = &values.inventory;
+
+    match guild {
+        Some(guild) => println!("User {} (guild {})", id, guild),
+        None => println!("User {}", id),
+    }
+    println!("Balance:        {}", values.balance);
+    println!("Total catches:  {}", values.total_catches);
+    println!("Unique fish:    {}", values.caught_fish.len());
+    println!("Autobait:       {}", values.autobait);
+    println!("Boat:           {}", inventory.boat.as_ref().map_or("None", |b| b.name.as_str()));
+    println!("Gadgets:        {}", if inventory.gadgets.is_empty() { "None".to_string() } else { inventory.gadgets.join(", ") });
+
+    let marker = |selected: bool| if selected { "*" } else { " " };
+
+    println!("\nRods:");
+    for (i, rod) in inventory.rods.iter().enumerate() {
+        println!(" {}{:>3}. {} ({:.0}%)", marker(i == inventory.selected_rod), i, rod.name, rod.durability * 100.0);
+    }
+    println!("Reels:");
+    for (i, reel) in inventory.reels.iter().enumerate() {
+        println!(" {}{:>3}. {} ({:.0}%)", marker(i == inventory.selected_reel), i, reel.name, reel.durability * 100.0);
+    }
+    println!("Lines:");
+    for (i, line) in inventory.lines.iter().enumerate() {
+        println!(" {}{:>3}. {} ({:.0}%)", marker(i == inventory.selected_line), i, line.name, line.durability * 100.0);
+    }
+    println!("Sinkers:");
+    for (i, sinker) in inventory.sinkers.iter().enumerate() {
+        println!(" {}{:>3}. {}", marker(i == inventory.selected_sinker), i, sinker.name);
+    }
+    println!("Bait:");
+    for (i, stack) in inventory.bait_bucket.stacks.iter().enumerate() {
+        println!(" {}{:>3}. {} x{}", marker(inventory.selected_bait == Some(i)), i, stack.bait.name, stack.count);
+    }
+
+    for problem in inventory.find_problems() {
+        hey!("Inventory problem: {}", problem);
+    }
+
+    Ok(())
+}
+
+fn change_money(id: UserId, guild: Option<GuildId>, action: MoneyAction, amount: f32) -> Result<(), String> {
+    let mut user = existing_user(id, guild)?;
+    let amount = MonetaryAmount::new(amount);
+
+    user.file.balance = match action {
+        MoneyAction::Give => user.file.balance.clone() + amount,
+        MoneyAction::Take => user.file.balance.checked_sub(&amount).ok_or(format!(
+            "{} only has {}, can't take {}",
+            profile_name(id, guild), user.file.balance, amount
+        ))?,
+        MoneyAction::Set => amount,
+    };
+    user.update();
+
+    yay!("{} now has {}", profile_name(id, guild), user.file.balance);
+    Ok(())
+}
+
+fn add_item(id: UserId, guild: Option<GuildId>, kind: GearKind, name: &str) -> Result<(), String> {
+    let mut user = existing_user(id, guild)?;
+    let inventory = &mut user.file.inventory;
+
+    let added = match kind {
+        GearKind::Bait => {
+            let bait = generate_bait(name, &mut rand::rng())?;
+            let added = bait.name.clone();
+            inventory.bait_bucket.add(bait);
+            added
+        }
+        _ => {
+            let shop = Shop::load_catalog();
+            match kind {
+                GearKind::Rod => {
+                    let rod = find_item(&shop.rods, name, |i| &i.name)?;
+                    let added = rod.name.clone();
+                    inventory.rods.push(rod);
+                    added
+                }
+                GearKind::Reel => {
+                    let reel = find_item(&shop.reels, name, |i| &i.name)?;
+                    let added = reel.name.clone();
+                    inventory.reels.push(reel);
+                    added
+                }
+                GearKind::Line => {
+                    let line = find_item(&shop.lines, name, |i| &i.name)?;
+                    let added = line.name.clone();
+                    inventory.lines.push(line);
+                    added
+                }
+                GearKind::Sinker => {
+                    let sinker = find_item(&shop.sinkers, name, |i| &i.name)?;
+                    let added = sinker.name.clone();
+                    inventory.sinkers.push(sinker);
+                    added
+                }
+                GearKind::Bait => unreachable!(),
+            }
+        }
+    };
+    user.update();
+
+    yay!("Gave {} to {}", added, profile_name(id, guild));
+    Ok(())
+}
+
+fn remove_item(id: UserId, guild: Option<GuildId>, kind: GearKind, index: usize) -> Result<(), String> {
+    let mut user = existing_user(id, guild)?;
+    let removed = user.file.inventory.remove_gear(kind, index)?;
+    user.update();
+
+    yay!("Removed {} from {}", removed, profile_name(id, guild));
+    Ok(())
+}
+
+fn edit_channels(guild: GuildId, action: ChannelAction) -> Result<(), String> {
+    let mut settings = GuildSettings::get(&guild).map_err(|e| e.to_string())?;
+
+    match action {
+        ChannelAction::List => {}
+        ChannelAction::Add(channel) => {
+            if settings.file.fishing_channels.contains(&channel) {
+                return Err(format!("Channel {} is already a fishing channel", channel));
+            }
+            settings.add_channel(channel).map_err(|e| e.to_string())?;
+            yay!("Added fishing channel {}", channel);
+        }
+        ChannelAction::Remove(channel) => {
+            if !settings.file.fishing_channels.contains(&channel) {
+                return Err(format!("Channel {} is not a fishing channel", channel));
+            }
+            settings.remove_channel(channel).map_err(|e| e.to_string())?;
+            yay!("Removed fishing channel {}", channel);
+        }
+    }
+
+    let channels = settings.get_channels().map_err(|e| e.to_string())?;
+    if channels.is_empty() {
+        println!("Guild {} allows fishing in every channel", guild);
+    } else {
+        println!("Guild {} fishing channels:", guild);
+        for channel in channels {
+            println!("  {}", channel);
+        }
+    }
+    Ok(())
+}
+
+fn print_shop(state: &ShopState) {
+    println!("Last restocked: {}", state.last_refresh);
+    for (i, bait) in state.daily_baits.iter().enumerate() {
+        println!("{:>3}. {:<40}${:>10.2}", i, bait.name, bait.price);
+    }
+}
+
+fn check_attribute(name: &str, attribute: &Attribute) -> Option<String> {
+    if attribute.min <= attribute.average && attribute.average <= attribute.max {
+        None
+    } else {
+        Some(format!(
+            "{} should be min <= average <= max (got {} / {} / {})",
+            name, attribute.min, attribute.average, attribute.max
+        ))
+    }
+}
+
+fn validate() -> Result<(), String> {
+    let mut problems: Vec<String> = Vec::new();
+
+    let config = data_path("config.toml");
+    let config = config.as_path();
+    if config.exists()
+        && let Err(e) = Config::load_from(config)
+    {
+        problems.push(format!("config.toml: {}", e));
+    }
+
+    let users = ron_files(&data_path("users"));
+    for (id, path) in &users {
+        match read_ron::<UserValues>(path) {
+            Ok(values) => problems.extend(
+                values
+                    .inventory
+                    .find_problems()
+                    .into_iter()
+                    .map(|p| format!("user {}: {}", id, p)),
+            ),
+            Err(e) => problems.push(e),
+        }
+    }
+
+    let guild_profiles = guild_profile_files();
+    for (guild, id, path) in &guild_profiles {
+        match read_ron::<UserValues>(path) {
+            Ok(values) => problems.extend(
+                values
+                    .inventory
+                    .find_problems()
+                    .into_iter()
+                    .map(|p| format!("user {} in guild {}: {}", id, guild, p)),
+            ),
+            Err(e) => problems.push(e),
+        }
+    }
+
+    let guilds = ron_files(&data_path("guilds"));
+    for (_, path) in &guilds {
+        if let Err(e) = read_ron::<GuildFile>(path) {
+            problems.push(e);
+        }
+    }
+
+    let shop_state = data_path("gamedata/shop.ron");
+    let shop_state = shop_state.as_path();
+    if shop_state.exists()
+        && let Err(e) = read_ron::<ShopState>(shop_state)
+    {
+        problems.push(e);
+    }
+
+    let catalogs = [
+        read_ron::<Vec<RodBase>>(&data_path("gamedata/rods.ron")).map(|_| ()),
+        read_ron::<Vec<Reel>>(&data_path("gamedata/reels.ron")).map(|_| ()),
+        read_ron::<Vec<Line>>(&data_path("gamedata/lines.ron")).map(|_| ()),
+        read_ron::<Vec<Sinker>>(&data_path("gamedata/sinkers.ron")).map(|_| ()),
+    ];
+    problems.extend(catalogs.into_iter().filter_map(Result::err));
+
+    match read_ron::<Pond>(&data_path("gamedata/fish_types.ron")) {
+        Ok(pond) => {
+            for fish in &pond.fish_types {
+                let checks = [
+                    check_attribute("size_range", &fish.size_range),
+                    check_attribute("weight_range", &fish.weight_range),
+                    (fish.depth_range.0 > fish.depth_range.1).then(|| {
+                        format!(
+                            "depth_range min is above max ({} > {})",
+                            fish.depth_range.0, fish.depth_range.1
+                        )
+                    }),
+                    (fish.base_value <= 0.0).then(|| "base_value should be above 0".to_string()),
+                ];
+                problems.extend(checks.into_iter().flatten().map(|p| format!("fish {}: {}", fish.name, p)));
+            }
+        }
+        Err(e) => problems.push(e),
+    }
+
+    println!(
+        "Checked {} users, {} guild profiles and {} guilds",
+        users.len(),
+        guild_profiles.len(),
+        guilds.len()
+    );
+
+    if problems.is_empty() {
+        yay!("No problems found");
+        return Ok(());
+    }
+
+    for problem in &problems {
+        hey!("{}", problem);
+    }
+    Err(format!("Found {} problems", problems.len()))
+}
+
+fn export(path: &Path) -> Result<(), String> {
+    let users = ron_files(&data_path("users"))
+        .into_iter()
+        .map(|(id, path)| Ok((id, read_ron::<UserValues>(&path)?)))
+        .collect::<Result<Vec<_>, String>>()?;
+    let guild_users = guild_profile_files()
+        .into_iter()
+        .map(|(guild, id, path)| Ok((guild, id, read_ron::<UserValues>(&path)?)))
+        .collect::<Result<Vec<_>, String>>()?;
+    let guilds = ron_files(&data_path("guilds"))
+        .into_iter()
+        .map(|(id, path)| Ok((id, read_ron::<GuildFile>(&path)?)))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let bundle = DataBundle {
+        users,
+        guild_users,
+        guilds,
+        shop: Shop::load_state(),
+    };
+
+    let data = ron::ser::to_string_pretty(&bundle, ron::ser::PrettyConfig::default())
+        .map_err(|e| format!("Failed to serialize export: {}", e))?;
+    fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    yay!(
+        "Exported {} users, {} guild profiles and {} guilds to {}",
+        bundle.users.len(),
+        bundle.guild_users.len(),
+        bundle.guilds.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// An id read from an export, which may have been edited by hand, so 0 is refused instead of panicking
+fn bundle_id(kind: &str, id: u64) -> Result<NonZeroU64, String> {
+    NonZeroU64::new(id).ok_or(format!("{} id 0 in the export is not a valid id", kind))
+}
+
+fn import(path: &Path, overwrite: bool) -> Result<(), String> {
+    let bundle = read_ron::<DataBundle>(path)?;
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    let mut invalid = Vec::new();
+
+    for (id, file) in bundle.users {
+        let user_id = match bundle_id("user", id) {
+            Ok(id) => UserId::from(id),
+            Err(e) => {
+                invalid.push(e);
+                continue;
+            }
+        };
+        if UserFile::exists(&user_id) && !overwrite {
+            skipped += 1;
+            continue;
+        }
+        UserFile { user_id, guild: None, file }.update();
+        imported += 1;
+    }
+
+    for (guild, id, file) in bundle.guild_users {
+        let (guild, user_id) = match (bundle_id("guild", guild), bundle_id("user", id)) {
+            (Ok(guild), Ok(id)) => (GuildId::from(guild), UserId::from(id)),
+            (Err(e), _) | (_, Err(e)) => {
+                invalid.push(e);
+                continue;
+            }
+        };
+        if UserFile::exists_in(&user_id, Some(guild)) && !overwrite {
+            skipped += 1;
+            continue;
+        }
+        UserFile { user_id, guild: Some(guild), file }.update();
+        imported += 1;
+    }
+
+    for (id, file) in bundle.guilds {
+        let guild_id = match bundle_id("guild", id) {
+            Ok(id) => GuildId::from(id),
+            Err(e) => {
+                invalid.push(e);
+                continue;
+            }
+        };
+        if data_path("guilds").join(format!("{}.ron", id)).exists() && !overwrite {
+            skipped += 1;
+            continue;
+        }
+        GuildSettings { id: guild_id, file }.update();
+        imported += 1;
+    }
+
+    // the shop state is shared by everyone, so only replace it when asked to
+    if overwrite {
+        Shop::save_state(&bundle.shop);
+    }
+
+    yay!("Imported {} files from {}", imported, path.display());
+    if skipped > 0 {
+        hey!("Skipped {} existing files, use --overwrite to replace them", skipped);
+    }
+    if !invalid.is_empty() {
+        for problem in &invalid {
+            hey!("{}", problem);
+        }
+        return Err(format!("Skipped {} entries with invalid ids", invalid.len()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestDataDir;
+
+    #[test]
+    fn import_reports_zero_ids_instead_of_panicking() {
+        let dir = TestDataDir::new();
+        let bundle = DataBundle {
+            users: vec![(0, UserValues::default()), (5, UserValues::default())],
+            guild_users: vec![(7, 0, UserValues::default())],
+            guilds: vec![(0, GuildFile::default())],
+            shop: ShopState::default(),
+        };
+        let path = dir.path.join("export.ron");
+        fs::write(&path, ron::to_string(&bundle).unwrap()).unwrap();
+
+        assert!(import(&path, false).is_err());
+        assert!(UserFile::exists(&UserId::new(5)));
+    }
+
+    #[test]
+    fn zero_guild_ids_are_refused_instead_of_panicking() {
+        for args in [&["channels", "0", "list"][..], &["migrate", "0", "all"], &["reset", "5", "--guild", "0"]] {
+            assert!(parse(args.iter().map(|a| a.to_string())).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn guild_profiles_survive_an_export_and_import() {
+        let dir = TestDataDir::new();
+        let (user, guild) = (UserId::new(42), GuildId::new(7));
+        let mut profile = UserFile::read_in(&user, Some(guild)).unwrap();
+        profile.file.total_catches = 9;
+        profile.update();
+
+        let path = dir.path.join("export.ron");
+        export(&path).unwrap();
+        fs::remove_dir_all(data_path("guilds/7")).unwrap();
+        import(&path, false).unwrap();
+        assert_eq!(UserFile::read_in(&user, Some(guild)).unwrap().file.total_catches, 9);
+
+        // the per-user commands reach the guild profile with --guild
+        let args = ["money", "42", "--guild", "7", "set", "5"].map(String::from);
+        let command = parse(args.into_iter()).unwrap();
+        assert!(matches!(command, AdminCommand::Money { guild: Some(g), .. } if g == guild));
+        run(command).unwrap();
+        assert_eq!(UserFile::read_in(&user, Some(guild)).unwrap().file.balance.get(), 5.0);
+        assert!(!UserFile::exists(&user));
+    }
+}
@@ -0,0 +1,112 @@
+// command line handling for the bot binary
+// running with no arguments starts the bot as usual
+
+use rand::Rng;
+
+use crate::fishing::rod_data::bait::{Bait, BaitPotency};
+
+pub mod admin;
+pub mod simulate;
+
+pub const USAGE: &str = "\
+Usage:
+  angler_bot_v2 [--headless]         Start the bot (--headless skips the admin window)
+  angler_bot_v2 simulate [OPTIONS]   Run a headless balance simulation
+  angler_bot_v2 admin <COMMAND>      Inspect and edit bot data without the admin window
+
+Simulate options:
+  --casts <N>             Number of casts to simulate (default 10000)
+  --rod <NAME>            Rod from the shop catalog (default: starter gear)
+  --reel <NAME>           Reel from the shop catalog
+  --line <NAME>           Line from the shop catalog
+  --sinker <NAME>         Sinker from the shop catalog
+  --boat <NAME>           Boat from the shop catalog (default: fishing from the shore)
+  --bait <POTENCY>        none, low, medium, high or lure (default none)
+  --depth <FT>            Always cast to this depth instead of rolling the sinker
+  --config <PATH>         Config file to simulate with (default ./data/config.toml)
+  --seed <N>              Seed the run so the report can be reproduced exactly
+  --qte-success <0..1>    Chance the player completes a QTE in time (default 0.8)
+  --qte-reaction <SECS>   Seconds the player needs to type a QTE code (default 4)
+  --csv <PATH>            Also write the report as CSV
+  --json <PATH>           Also write the report as JSON
+
+Admin commands:
+  users                                   List every user with a data file
+  user <USER_ID>                          Show a user's balance and inventory
+  money <USER_ID> give|take|set <AMOUNT>  Change a user's balance
+  item <USER_ID> add <KIND> <NAME>        Give a user an item from the shop catalog
+                                          (KIND is rod, reel, line or sinker; for
+                                          bait, NAME is low, medium, high or lure)
+  item <USER_ID> remove <KIND> <INDEX>    Remove an item by its index in `user`
+  reset <USER_ID>                         Reset a user to a fresh profile
+                                          (user, money, item and reset take
+                                          --guild <GUILD_ID> to edit a guild profile)
+  channels <GUILD_ID> list                List a guild's fishing channels
+  channels <GUILD_ID> add|remove <ID>     Edit a guild's fishing channels
+  migrate <GUILD_ID> <USER_ID|all>        Copy global profiles into a guild's own profiles,
+          [--overwrite]                   replacing ones it already has only with --overwrite
+  shop show                               Show today's bait stock
+  shop restock                            Regenerate today's bait stock
+  validate                                Check every data file for problems
+  export <PATH>                           Write users, guild profiles, guilds and shop
+                                          state to one file
+  import <PATH> [--overwrite]             Load an export, replacing existing data only
+                                          with --overwrite
+  errorcode <CODE>                        Find a reported error code in ./data/logs
+  flagged                                 List players flagged for possible QTE macros
+  unflag <USER_ID>                        Clear a player's macro flag
+";
+
+pub enum CliCommand {
+    RunBot(BotArgs),
+    // boxed, the simulate options are much bigger than the other commands
+    Simulate(Box<simulate::SimulateArgs>),
+    Admin(admin::AdminCommand),
+    Help,
+}
+
+#[derive(Default)]
+pub struct BotArgs {
+    /// run without the egui admin window
+    pub headless: bool,
+}
+
+/// Parse the program arguments (without the binary name)
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<CliCommand, String> {
+    let mut args = args.into_iter();
+
+    match args.next().as_deref() {
+        None => Ok(CliCommand::RunBot(BotArgs::default())),
+        Some("--headless") => Ok(CliCommand::RunBot(BotArgs { headless: true })),
+        Some("simulate") => simulate::parse(args).map(|args| CliCommand::Simulate(Box::new(args))),
+        Some("admin") => admin::parse(args).map(CliCommand::Admin),
+        Some("help" | "--help" | "-h") => Ok(CliCommand::Help),
+        Some(other) => Err(format!("Unknown argument: {}", other)),
+    }
+}
+
+pub(crate) fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid number for {}: {}", flag, value))
+}
+
+/// Find an item in a catalog by name, ignoring case
+pub(crate) fn find_item<T: Clone>(items: &[T], name: &str, get_name: impl Fn(&T) -> &str) -> Result<T, String> {
+    items
+        .iter()
+        .find(|i| get_name(i).eq_ignore_ascii_case(name))
+        .cloned()
+        .ok_or(format!("No item named '{}' in the shop catalog", name))
+}
+
+/// Generate a random bait from a potency name (low, medium, high or lure)
+pub(crate) fn generate_bait(potency: &str, rng: &mut impl Rng) -> Result<Bait, String> {
+    match potency.to_lowercase().as_str() {
+        "low" => Ok(Bait::generate(BaitPotency::Low, false, rng)),
+        "medium" => Ok(Bait::generate(BaitPotency::Medium, false, rng)),
+        "high" => Ok(Bait::generate(BaitPotency::High, false, rng)),
+        "lure" => Ok(Bait::generate(BaitPotency::High, true, rng)),
+        other => Err(format!("Unknown bait potency: {}", other)),
+    }
+}
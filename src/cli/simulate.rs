@@ -0,0 +1,142 @@
+// `angler_bot_v2 simulate` - headless balance simulation
+
+use std::path::PathBuf;
+
+use crate::cli::{find_item, generate_bait, parse_number};
+use crate::data_management::config::Config;
+use crate::fishing::fish_data::fish::Pond;
+use crate::fishing::rod_data::RodLoadout;
+use crate::fishing::shop::Shop;
+use crate::fishing::simulation::{SimulationSettings, seeded_rng, simulate};
+use crate::{nay, yay};
+
+#[derive(Default)]
+pub struct SimulateArgs {
+    pub settings: SimulationSettings,
+    pub rod: Option<String>,
+    pub reel: Option<String>,
+    pub line: Option<String>,
+    pub sinker: Option<String>,
+    pub boat: Option<String>,
+    pub bait: Option<String>,
+    pub config: Option<PathBuf>,
+    pub csv: Option<PathBuf>,
+    pub json: Option<PathBuf>,
+}
+
+pub fn parse(mut args: impl Iterator<Item = String>) -> Result<SimulateArgs, String> {
+    let mut parsed = SimulateArgs::default();
+
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", flag));
+
+        match flag.as_str() {
+            "--casts" => parsed.settings.casts = parse_number(&flag, &value()?)?,
+            "--rod" => parsed.rod = Some(value()?),
+            "--reel" => parsed.reel = Some(value()?),
+            "--line" => parsed.line = Some(value()?),
+            "--sinker" => parsed.sinker = Some(value()?),
+            "--boat" => parsed.boat = Some(value()?),
+            "--bait" => parsed.bait = Some(value()?),
+            "--depth" => parsed.settings.fixed_depth = Some(parse_number(&flag, &value()?)?),
+            "--config" => parsed.config = Some(PathBuf::from(value()?)),
+            "--qte-success" => parsed.settings.qte_success_rate = parse_number(&flag, &value()?)?,
+            "--qte-reaction" => parsed.settings.qte_reaction_time = parse_number(&flag, &value()?)?,
+            "--seed" => parsed.settings.seed = Some(parse_number(&flag, &value()?)?),
+            "--csv" => parsed.csv = Some(PathBuf::from(value()?)),
+            "--json" => parsed.json = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("Unknown simulate option: {}", flag)),
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn build_loadout(args: &SimulateArgs, shop: &Shop) -> Result<RodLoadout, String> {
+    let mut loadout = RodLoadout::default();
+
+    if let Some(name) = &args.rod {
+        loadout.rod = find_item(&shop.rods, name, |i| &i.name)?;
+    }
+    if let Some(name) = &args.reel {
+        loadout.reel = find_item(&shop.reels, name, |i| &i.name)?;
+    }
+    if let Some(name) = &args.line {
+        loadout.line = find_item(&shop.lines, name, |i| &i.name)?;
+    }
+    if let Some(name) = &args.sinker {
+        loadout.sinker = find_item(&shop.sinkers, name, |i| &i.name)?;
+    }
+    if let Some(name) = &args.boat {
+        loadout.boat = Some(find_item(&shop.boats, name, |i| &i.name)?);
+    }
+
+    loadout.bait = match args.bait.as_deref() {
+        None | Some("none") => None,
+        Some(potency) => Some(generate_bait(potency, &mut seeded_rng(args.settings.seed))?),
+    };
+
+    Ok(loadout)
+}
+
+/// Entry point for `angler_bot_v2 simulate`
+pub fn run_simulation(args: SimulateArgs) {
+    let config = match &args.config {
+        Some(path) => match Config::load_from(path) {
+            Ok(config) => config,
+            Err(e) => {
+                nay!("Failed to load config: {}", e);
+                return;
+            }
+        },
+        None => Config::load(),
+    };
+
+    let pond = match Pond::load() {
+        Ok(pond) => pond,
+        Err(e) => {
+            nay!("Failed to load the pond: {}", e);
+            return;
+        }
+    };
+
+    let shop = Shop::load_catalog();
+
+    let loadout = match build_loadout(&args, &shop) {
+        Ok(loadout) => loadout,
+        Err(e) => {
+            nay!("{}", e);
+            return;
+        }
+    };
+
+    if let Some(bait) = &loadout.bait {
+        println!("Simulating with bait: {} - {}", bait.name, bait.description);
+        println!("Net effect: {}\n", bait.effect(&config.bait).summary());
+    }
+
+    let report = match simulate(&loadout, &pond, &shop, &config, &args.settings) {
+        Ok(report) => report,
+        Err(e) => {
+            nay!("Simulation failed: {}", e);
+            return;
+        }
+    };
+
+    println!("{}", report.to_table());
+
+    if let Some(path) = &args.csv {
+        match std::fs::write(path, report.to_csv()) {
+            Ok(_) => yay!("Wrote CSV report to {}", path.display()),
+            Err(e) => nay!("Failed to write CSV report: {}", e),
+        }
+    }
+
+    if let Some(path) = &args.json {
+        let written = report.to_json().and_then(|json| Ok(std::fs::write(path, json)?));
+        match written {
+            Ok(_) => yay!("Wrote JSON report to {}", path.display()),
+            Err(e) => nay!("Failed to write JSON report: {}", e),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{
+    command,
+    commands::command_response_ephemeral,
+    logging::{find_error_code, logs_dir},
+};
+
+// discord messages are capped at 2000 characters
+const MAX_RESPONSE_LEN: usize = 1900;
+const CONTEXT_LINES: usize = 5;
+
+command! {
+    struct: ErrorCodeCommand,
+    name: "errorcode",
+    desc: "Look up an error code a player reported in this server.",
+    requires_guild: true,
+    is_admin_command: true,
+    cooldown: User(3),
+
+    run: async |data, code("The error code the player was given"): String|
+    WITH [ ADMINISTRATOR, MANAGE_GUILD ] {
+        let guild = data.log_guild();
+
+        // only show what happened in this server, the logs are shared by every server the bot is in
+        let matches = find_error_code(&logs_dir(), code.trim(), Some(&guild), CONTEXT_LINES);
+
+        if matches.is_empty() {
+            command_response_ephemeral(data.ctx, data.command,
+                format!("No log entry found for `{}` in this server.", code.trim())).await;
+            return Ok(());
+        }
+
+        let mut response = format!("🔎 **Error Code `{}`**\n```\n", code.trim());
+        for found in matches {
+            for line in &found.context {
+                response.push_str(&format!("  {}\n", line));
+            }
+            response.push_str(&format!("> {}\n\n", found.line));
+        }
+
+        if response.len() > MAX_RESPONSE_LEN {
+            let mut end = MAX_RESPONSE_LEN;
+            while !response.is_char_boundary(end) {
+                end -= 1;
+            }
+            response.truncate(end);
+            response.push_str("\n...");
+        }
+        response.push_str("```");
+
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+}
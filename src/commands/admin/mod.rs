@@ -1 +1,4 @@
+pub mod error_code;
+pub mod permissions;
 pub mod register_channel;
+pub mod settings;
@@ -0,0 +1,161 @@
+use serenity::all::{PartialChannel, Role};
+
+use crate::{
+    command,
+    commands::{command_response_ephemeral, get_all_cmds},
+    data_management::guildfile::GuildSettings,
+};
+
+/// The name of a command the guild's rules can apply to
+fn rule_target(name: &str) -> Result<String, String> {
+    let name = name.trim().trim_start_matches('/').to_lowercase();
+    match get_all_cmds().iter().find(|c| c.name() == name) {
+        Some(cmd) if cmd.is_admin() => Err(format!("`/{}` is an admin command and can't be restricted.", name)),
+        Some(_) => Ok(name),
+        None => Err(format!("There is no `/{}` command.", name)),
+    }
+}
+
+command! {
+    struct: PermissionsCommand,
+    name: "permissions",
+    desc: "Manage who can use Angler Bot's commands in this server, and where.",
+    requires_guild: true,
+    is_admin_command: true,
+    cooldown: Guild(2),
+
+    run: async |data|
+
+    // SUBCOMMANDS:
+    sub: view("Show this server's command permissions") => async |data| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let guild = GuildSettings::get(guild_id)?;
+
+        let mut response = String::from("🔐 **Command Permissions**\n\n**Bot Manager Role:** ");
+        match guild.file.manager_role {
+            Some(role) => response.push_str(&format!("<@&{}>\n", role)),
+            None => response.push_str("None\n"),
+        }
+
+        let mut commands: Vec<_> = guild.file.commands.iter().collect();
+        commands.sort_by_key(|(name, _)| name.as_str());
+
+        if commands.is_empty() {
+            response.push_str("\nEvery command is open to everyone.\n");
+        }
+        for (name, rules) in commands {
+            response.push_str(&format!("\n**/{}**{}\n", name, if rules.enabled { "" } else { " (turned off)" }));
+            if !rules.roles.is_empty() {
+                let roles: Vec<String> = rules.roles.iter().map(|r| format!("<@&{}>", r)).collect();
+                response.push_str(&format!("- Roles: {}\n", roles.join(", ")));
+            }
+            if !rules.channels.is_empty() {
+                let channels: Vec<String> = rules.channels.iter().map(|c| format!("<#{}>", c)).collect();
+                response.push_str(&format!("- Channels: {}\n", channels.join(", ")));
+            }
+        }
+
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+
+    sub: manager("Set the role that can use admin commands") => async |data,
+        role("The Bot Manager role, leave empty to remove it"): Option<&Role>| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild = GuildSettings::get(guild_id)?;
+
+        let role_id = role.map(|r| r.id.get());
+        guild.edit(|file| file.manager_role = role_id)?;
+
+        let response = match role_id {
+            Some(role_id) => format!("✅ <@&{}> can now use Angler Bot's admin commands.", role_id),
+            None => "🗑️ The Bot Manager role was removed.".to_string(),
+        };
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+
+    sub: disable("Turn a command off in this server") => async |data,
+        command("The command to turn off"): String| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild = GuildSettings::get(guild_id)?;
+        let name = rule_target(&command)?;
+
+        guild.edit(|file| file.edit_rules(&name, |rules| rules.enabled = false))?;
+
+        command_response_ephemeral(data.ctx, data.command, format!("🚫 `/{}` is now turned off.", name)).await;
+        Ok(())
+    }
+
+    sub: enable("Turn a command back on in this server") => async |data,
+        command("The command to turn on"): String| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild = GuildSettings::get(guild_id)?;
+        let name = rule_target(&command)?;
+
+        guild.edit(|file| file.edit_rules(&name, |rules| rules.enabled = true))?;
+
+        command_response_ephemeral(data.ctx, data.command, format!("✅ `/{}` is now turned on.", name)).await;
+        Ok(())
+    }
+
+    sub: role("Only let members with a role use a command (run again to add more roles)") => async |data,
+        command("The command to restrict"): String,
+        role("The role that can use it"): &Role| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild = GuildSettings::get(guild_id)?;
+        let name = rule_target(&command)?;
+
+        let role_id = role.id.get();
+        guild.edit(|file| file.edit_rules(&name, |rules| {
+            if !rules.roles.contains(&role_id) {
+                rules.roles.push(role_id);
+            }
+        }))?;
+
+        command_response_ephemeral(data.ctx, data.command,
+            format!("✅ <@&{}> can now use `/{}`.", role_id, name)).await;
+        Ok(())
+    }
+
+    sub: channel("Only let a command be used in a channel (run again to add more channels)") => async |data,
+        command("The command to restrict"): String,
+        channel("The channel it can be used in"): &PartialChannel| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild = GuildSettings::get(guild_id)?;
+        let name = rule_target(&command)?;
+
+        let channel_id = channel.id.get();
+        guild.edit(|file| file.edit_rules(&name, |rules| {
+            if !rules.channels.contains(&channel_id) {
+                rules.channels.push(channel_id);
+            }
+        }))?;
+
+        command_response_ephemeral(data.ctx, data.command,
+            format!("✅ `/{}` can now be used in <#{}>.", name, channel_id)).await;
+        Ok(())
+    }
+
+    sub: clear("Remove every rule for a command") => async |data,
+        command("The command to open up to everyone"): String| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild = GuildSettings::get(guild_id)?;
+        let name = rule_target(&command)?;
+
+        guild.edit(|file| file.commands.remove(&name))?;
+
+        command_response_ephemeral(data.ctx, data.command,
+            format!("🗑️ `/{}` is open to everyone again.", name)).await;
+        Ok(())
+    }
+
+    WITH [ ADMINISTRATOR, MANAGE_GUILD ] {
+        command_response_ephemeral(
+            data.ctx,
+            data.command,
+            "Please select a subcommand: `view`, `manager`, `disable`, `enable`, `role`, `channel`, or `clear`."
+        ).await;
+        Ok(())
+    }
+}
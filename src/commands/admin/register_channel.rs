@@ -15,10 +15,10 @@ command! {
 
     // SUBCOMMANDS:
     sub: add("Add a channel to the allowed channels") => async |data, channel("The channel you wish Angler Bot commands to be allowed in"): &PartialChannel| {
-        let guild_id = data.guild_id.unwrap(); // Safe because requires_guild: true
-        let mut guild_file = GuildSettings::get(&guild_id);
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild_file = GuildSettings::get(guild_id)?;
 
-        guild_file.add_channel(channel.id.get());
+        guild_file.add_channel(channel.id.get())?;
 
         command_response_ephemeral(
             &data.ctx,
@@ -30,10 +30,10 @@ command! {
     }
 
     sub: remove("Remove a channel from the allowed channels") => async |data, channel("The channel to remove from Angler Bot's allowed operating channels"): &PartialChannel| {
-        let guild_id = data.guild_id.unwrap();
-        let mut guild_file = GuildSettings::get(&guild_id);
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild_file = GuildSettings::get(guild_id)?;
 
-        guild_file.remove_channel(channel.id.get());
+        guild_file.remove_channel(channel.id.get())?;
 
         command_response_ephemeral(
             &data.ctx,
@@ -45,9 +45,9 @@ command! {
     }
 
     sub: list("List all allowed channels") => async |data| {
-        let guild_id = data.guild_id.unwrap();
-        let mut guild_file = GuildSettings::get(&guild_id);
-        let channels = guild_file.get_channels();
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild_file = GuildSettings::get(guild_id)?;
+        let channels = guild_file.get_channels()?;
 
         if channels.is_empty() {
             command_response_ephemeral(
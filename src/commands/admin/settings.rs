@@ -0,0 +1,200 @@
+use serenity::all::{PartialChannel, User};
+
+use crate::{
+    command,
+    commands::command_response_ephemeral,
+    data_management::{
+        config::{Config, ValueCalculationType},
+        guildfile::{ConfigOverrides, GuildFeatures, GuildSettings, ProfileMode},
+        userfile::UserFile,
+    },
+};
+
+command! {
+    struct: SettingsCommand,
+    name: "settings",
+    desc: "Change how Angler Bot works in this server.",
+    requires_guild: true,
+    is_admin_command: true,
+    cooldown: Guild(2),
+
+    run: async |data|
+
+    // SUBCOMMANDS:
+    sub: view("Show this server's settings") => async |data| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let guild = GuildSettings::get(guild_id)?;
+
+        let mut response = String::from("⚙️ **Server Settings**\n\n**Features:**\n");
+        for feature in GuildFeatures::NAMES {
+            let enabled = guild.file.features.get(feature).unwrap_or_default();
+            response.push_str(&format!("- `{}`: {}\n", feature, if enabled { "✅ on" } else { "❌ off" }));
+        }
+
+        response.push_str(&format!("\n**Player Profiles:** {:?}\n", guild.file.profile_mode));
+
+        response.push_str("\n**Hall of Fame:** ");
+        match guild.file.announcement_channel {
+            Some(channel) => response.push_str(&format!("<#{}>\n", channel)),
+            None => response.push_str("None\n"),
+        }
+
+        response.push_str("\n**Config Overrides:**\n");
+        let overrides = guild.file.overrides.list();
+        if overrides.is_empty() {
+            response.push_str("None - this server uses the global config.\n");
+        }
+        for (setting, value) in overrides {
+            response.push_str(&format!("- `{}` = {}\n", setting, value));
+        }
+
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+
+    sub: feature("Turn a feature on or off") => async |data,
+        feature("The feature to change" | ["QTEs": "qte", "Public catch results": "public_results", "Darryl's messages": "darryl_messages", "Fights": "fights"]): String,
+        enabled("Whether the feature is on"): bool| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild = GuildSettings::get(guild_id)?;
+
+        guild.edit(|file| file.features.set(&feature, enabled))??;
+
+        command_response_ephemeral(
+            data.ctx,
+            data.command,
+            format!("✅ `{}` is now {}.", feature, if enabled { "on" } else { "off" })
+        ).await;
+        Ok(())
+    }
+
+    sub: announcements("Set the hall of fame channel, where rare and record-breaking catches are announced") => async |data,
+        channel("The channel to announce in, leave empty to stop announcing"): Option<&PartialChannel>| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild = GuildSettings::get(guild_id)?;
+
+        let channel_id = channel.map(|c| c.id.get());
+        guild.edit(|file| file.announcement_channel = channel_id)?;
+
+        let response = match channel_id {
+            Some(channel_id) => format!("📣 Rare and record-breaking catches will be announced in <#{}>.", channel_id),
+            None => "🔇 Catches will no longer be announced.".to_string(),
+        };
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+
+    sub: set("Override a config value for this server") => async |data,
+        setting("The value to override" | [
+            "Base catch chance (0-1)": "fishing.base_catch_chance",
+            "Base cast wait (seconds)": "fishing.base_cast_wait",
+            "Min cast wait (seconds)": "fishing.min_cast_wait",
+            "Max cast wait (seconds)": "fishing.max_cast_wait",
+            "Base QTE time (seconds)": "fishing.base_qte_time",
+            "Min QTE time (seconds)": "fishing.min_qte_time",
+            "Low bait weight": "bait.low_bait_weight",
+            "Medium bait weight": "bait.medium_bait_weight",
+            "High bait weight": "bait.high_bait_weight"
+        ]): String,
+        value("The new value"): f64| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild = GuildSettings::get(guild_id)?;
+
+        let global = Config::load();
+        guild.edit(|file| file.overrides.set(&setting, Some(value as f32), &global))??;
+
+        command_response_ephemeral(
+            data.ctx,
+            data.command,
+            format!("✅ `{}` is now {} in this server.", setting, value)
+        ).await;
+        Ok(())
+    }
+
+    sub: value_calculation("Override how fish values are calculated for this server") => async |data,
+        calculation("How fish values are calculated" | ["Averaged": "Averaged", "Multiplicative": "Multiplicative"]): String| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild = GuildSettings::get(guild_id)?;
+
+        let calculation = match calculation.as_str() {
+            "Averaged" => ValueCalculationType::Averaged,
+            "Multiplicative" => ValueCalculationType::Multiplicative,
+            _ => return Err(format!("Unknown value calculation `{}`.", calculation).into()),
+        };
+
+        let response = format!("✅ Fish values are now calculated with `{:?}` in this server.", calculation);
+        guild.edit(|file| file.overrides.fishing.fish_value_calculation = Some(calculation))?;
+
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+
+    sub: reset("Go back to the global config value") => async |data,
+        setting("The value to reset, leave empty to reset every override"): Option<String>| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild = GuildSettings::get(guild_id)?;
+
+        let response = match setting.as_deref() {
+            None => {
+                guild.edit(|file| file.overrides = ConfigOverrides::default())?;
+                "🗑️ Every override was reset, this server now uses the global config.".to_string()
+            }
+            Some("fishing.fish_value_calculation") => {
+                guild.edit(|file| file.overrides.fishing.fish_value_calculation = None)?;
+                "🗑️ `fishing.fish_value_calculation` now uses the global config.".to_string()
+            }
+            Some(setting) => {
+                let global = Config::load();
+                guild.edit(|file| file.overrides.set(setting, None, &global))??;
+                format!("🗑️ `{}` now uses the global config.", setting)
+            }
+        };
+
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+
+    sub: profiles("Choose whether players use their global profile here or a separate one") => async |data,
+        mode("Where players' progress is kept" | ["Shared with every server": "Global", "Separate for this server": "PerGuild"]): String| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+        let mut guild = GuildSettings::get(guild_id)?;
+
+        let (mode, response) = match mode.as_str() {
+            "Global" => (ProfileMode::Global, "🌍 Players now use their global profile in this server."),
+            "PerGuild" => (ProfileMode::PerGuild, "🏠 Players now have a separate profile in this server. Use `/settings migrate` to copy a player's global profile here."),
+            _ => return Err(format!("Unknown profile mode `{}`.", mode).into()),
+        };
+        guild.edit(|file| file.profile_mode = mode)?;
+
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+
+    sub: migrate("Copy a player's global profile into this server") => async |data,
+        player("The player whose profile to copy"): &User,
+        overwrite("Replace the profile they already have in this server"): Option<bool>| {
+        let guild_id = data.guild_id.ok_or("You must be in a server to use that command!")?;
+
+        if !UserFile::exists(&player.id) {
+            return Err(format!("{} doesn't have a global profile to copy.", player.display_name()).into());
+        }
+
+        let response = if UserFile::copy_to_guild(&player.id, *guild_id, overwrite.unwrap_or(false))? {
+            format!("✅ Copied {}'s global profile into this server.", player.display_name())
+        } else {
+            format!("{} already has a profile in this server, set `overwrite` to replace it.", player.display_name())
+        };
+
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+
+    WITH [ ADMINISTRATOR, MANAGE_GUILD ] {
+        command_response_ephemeral(
+            data.ctx,
+            data.command,
+            "Please select a subcommand: `view`, `feature`, `announcements`, `set`, `value_calculation`, `reset`, `profiles`, or `migrate`."
+        ).await;
+        Ok(())
+    }
+}
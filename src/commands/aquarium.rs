@@ -0,0 +1,252 @@
+use chrono::Utc;
+use serenity::all::{
+    ButtonStyle, Color, CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter,
+    CreateInteractionResponse, CreateInteractionResponseMessage, Mentionable, User, UserId,
+};
+use serenity::async_trait;
+
+use crate::command;
+use crate::commands::command_response_ephemeral;
+use crate::commands::components::{ComponentData, ComponentHandler, component_id};
+use crate::data_management::config::AquariumConfig;
+use crate::data_management::userfile::UserFile;
+use crate::error::CommandError;
+use crate::fishing::aquarium::{Aquarium, Trophy};
+
+/// how many fish are shown on each page of an aquarium
+const PAGE_SIZE: usize = 5;
+
+command! {
+    struct: AquariumCommand,
+    name: "aquarium",
+    desc: "Show off the fish in your aquarium, or look at someone else's.",
+    requires_guild: false,
+    cooldown: User(2),
+
+    run: async |data, user("Whose aquarium to look at"): Option<&User>| {
+        let profile_guild = data.settings.profile_guild;
+        let config = &data.settings.config.aquarium;
+        let owner = user.unwrap_or(data.sender);
+        let own = owner.id == data.sender.id;
+
+        if !own && !UserFile::exists_in(&owner.id, profile_guild) {
+            return Err(format!("{} hasn't been fishing yet.", owner.name).into());
+        }
+        let mut user_file = UserFile::read_in(&owner.id, profile_guild)?;
+        if !own && user_file.file.private_catches {
+            return Err(format!("{} keeps their aquarium private.", owner.name).into());
+        }
+
+        // the owner collects what the tank earned while they were away
+        let mut note = None;
+        if own {
+            let earned = user_file.file.aquarium.collect(Utc::now().timestamp_millis(), config);
+            if earned.get() > 0.0 {
+                note = Some(format!("💰 Your aquarium earned **{}** since your last visit.", earned));
+                user_file.file.balance += earned;
+            }
+            user_file.update();
+        }
+
+        let embed = build_aquarium_embed(owner.id, &user_file.file.aquarium, config, 0, note)
+            .thumbnail(owner.face());
+        let components = build_aquarium_components(owner.id, &user_file.file.aquarium, 0);
+
+        // private players still see their own tank, just not in front of everyone
+        let response = CreateInteractionResponseMessage::new()
+            .embed(embed)
+            .components(components)
+            .ephemeral(user_file.file.private_catches);
+        data.command.create_response(&data.ctx.http, CreateInteractionResponse::Message(response)).await?;
+        Ok(())
+    }
+}
+
+command! {
+    struct: TrophyCommand,
+    name: "trophy",
+    desc: "Mount your catches in your aquarium.",
+    requires_guild: false,
+    cooldown: User(2),
+
+    run: async |data|
+
+    // SUBCOMMANDS:
+    sub: catches("List your latest catches that can be mounted") => async |data| {
+        let user_file = data.user_file()?;
+        let recent = &user_file.file.aquarium.recent;
+
+        if recent.is_empty() {
+            command_response_ephemeral(data.ctx, data.command,
+                "You haven't caught anything to mount yet. Go `/cast` a line!").await;
+            return Ok(());
+        }
+
+        let mut response = String::from("🎣 **Recent Catches**\n");
+        for (i, trophy) in recent.iter().enumerate() {
+            response.push_str(&format!("\n`{}.` {}", i + 1, describe_trophy(trophy)));
+        }
+        response.push_str("\n\nMount one with `/trophy mount <number>`.");
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+
+    sub: mount("Put one of your recent catches on display") => async |data,
+        number("The catch's number in `/trophy catches`"): i64| {
+        let config = &data.settings.config.aquarium;
+        let mut user_file = data.user_file()?;
+        let aquarium = &mut user_file.file.aquarium;
+
+        let earned = aquarium.collect(Utc::now().timestamp_millis(), config);
+        let index = (number.max(1) - 1) as usize;
+        let mut response = match aquarium.mount(index, config.capacity) {
+            Ok(trophy) => format!("🐠 Mounted your **{}** in your aquarium! See it with `/aquarium`.", trophy.name),
+            Err(msg) => return Err(msg.into()),
+        };
+        if earned.get() > 0.0 {
+            response.push_str(&format!("\n💰 Your aquarium earned **{}** since your last visit.", earned));
+        }
+        user_file.file.balance += earned;
+        user_file.update();
+
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+
+    sub: release("Take a fish out of your aquarium for good") => async |data,
+        number("The fish's number in `/aquarium`"): i64| {
+        let config = &data.settings.config.aquarium;
+        let mut user_file = data.user_file()?;
+        let aquarium = &mut user_file.file.aquarium;
+
+        let earned = aquarium.collect(Utc::now().timestamp_millis(), config);
+        let released = aquarium.release((number.max(1) - 1) as usize)?;
+        user_file.file.balance += earned;
+        user_file.update();
+
+        command_response_ephemeral(data.ctx, data.command,
+            format!("🌊 Released your **{}** back into the water.", released.name)).await;
+        Ok(())
+    }
+
+    {
+        command_response_ephemeral(
+            data.ctx,
+            data.command,
+            "Please select a subcommand: `catches`, `mount`, or `release`."
+        ).await;
+        Ok(())
+    }
+}
+
+/// Turns the pages of an aquarium, ids look like `aquarium:<owner id>:<page>`
+pub struct AquariumComponent;
+
+#[async_trait]
+impl ComponentHandler for AquariumComponent {
+    fn prefix(&self) -> &'static str {
+        "aquarium"
+    }
+
+    fn command(&self) -> &'static str {
+        "aquarium"
+    }
+
+    async fn handle(&self, data: &ComponentData<'_>) -> Result<(), CommandError> {
+        // parsing the id rejects 0, which UserId::new would panic on
+        let owner: UserId = data.arg(0)?;
+        let page: usize = data.arg(1)?;
+
+        // anyone looking at the message can turn the pages, so the owner's file is only read
+        let user_file = UserFile::read_in(&owner, data.settings.profile_guild)?;
+        let aquarium = &user_file.file.aquarium;
+
+        let mut embed = build_aquarium_embed(owner, aquarium, &data.settings.config.aquarium, page, None);
+        // the owner's avatar from the first page is kept, so it doesn't have to be looked up again
+        if let Some(thumbnail) = data.interaction.message.embeds.first().and_then(|e| e.thumbnail.as_ref()) {
+            embed = embed.thumbnail(&thumbnail.url);
+        }
+        let components = build_aquarium_components(owner, aquarium, page);
+        data.update_message(CreateInteractionResponseMessage::new().embed(embed).components(components)).await
+    }
+}
+
+/// One line about a fish, e.g. for the list of recent catches
+fn describe_trophy(trophy: &Trophy) -> String {
+    format!(
+        "**{}** ({}) · 📏 {:.2} in · ⚖️ {:.2} lbs · 🌊 {:.0} ft · 📅 {}",
+        trophy.name, trophy.rarity, trophy.size, trophy.weight, trophy.depth, trophy.caught_on()
+    )
+}
+
+fn page_count(aquarium: &Aquarium) -> usize {
+    aquarium.trophies.len().div_ceil(PAGE_SIZE).max(1)
+}
+
+fn build_aquarium_embed(
+    owner: UserId,
+    aquarium: &Aquarium,
+    config: &AquariumConfig,
+    page: usize,
+    note: Option<String>,
+) -> CreateEmbed {
+    let page = page.min(page_count(aquarium) - 1);
+    let mut description = String::new();
+
+    if let Some(note) = note {
+        description.push_str(&format!("### {}\n\n", note));
+    }
+    description.push_str(&format!(
+        "{}'s tank holds **{}/{}** fish and earns **${:.2}** an hour.",
+        owner.mention(),
+        aquarium.trophies.len(),
+        config.capacity,
+        aquarium.hourly_income(config)
+    ));
+    if aquarium.trophies.is_empty() {
+        description.push_str("\n\nThe tank is empty! Mount a catch with `/trophy mount`.");
+    }
+
+    let fields = aquarium
+        .trophies
+        .iter()
+        .enumerate()
+        .skip(page * PAGE_SIZE)
+        .take(PAGE_SIZE)
+        .map(|(i, trophy)| {
+            (
+                format!("#{} {} ({})", i + 1, trophy.name, trophy.rarity),
+                format!(
+                    "📏 {:.2} in · ⚖️ {:.2} lbs · 🌊 {:.0} ft\n💲 {} · 📅 Caught {}",
+                    trophy.size, trophy.weight, trophy.depth, trophy.value, trophy.caught_on()
+                ),
+                false,
+            )
+        });
+
+    CreateEmbed::new()
+        .title("🐠 Aquarium")
+        .description(description)
+        .fields(fields)
+        .color(Color::BLUE)
+        .footer(CreateEmbedFooter::new(format!("Page {}/{}", page + 1, page_count(aquarium))))
+}
+
+fn build_aquarium_components(owner: UserId, aquarium: &Aquarium, page: usize) -> Vec<CreateActionRow> {
+    let pages = page_count(aquarium);
+    if pages == 1 {
+        return vec![];
+    }
+    let page = page.min(pages - 1);
+
+    let prev = CreateButton::new(component_id("aquarium", &[&owner, &page.saturating_sub(1)]))
+        .label("◀ Prev")
+        .style(ButtonStyle::Secondary)
+        .disabled(page == 0);
+    let next = CreateButton::new(component_id("aquarium", &[&owner, &(page + 1)]))
+        .label("Next ▶")
+        .style(ButtonStyle::Secondary)
+        .disabled(page + 1 >= pages);
+
+    vec![CreateActionRow::Buttons(vec![prev, next])]
+}
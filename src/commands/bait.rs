@@ -1,26 +1,26 @@
 use crate::command;
+use crate::commands::components::{ComponentData, ComponentHandler, component_id};
+use crate::data_management::config::BaitConfig;
+use crate::data_management::monetary::MonetaryAmount;
 use crate::data_management::userfile::UserFile;
+use crate::error::CommandError;
+use crate::fishing::inventory::GearKind;
 use serenity::all::{
-    ButtonStyle, ComponentInteractionDataKind, CreateActionRow, CreateButton, CreateEmbed,
-    CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
-    EditMessage,
+    ButtonStyle, CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter,
+    CreateInteractionResponse, CreateInteractionResponseMessage,
 };
-use serenity::futures::StreamExt;
-use std::time::Duration;
+use serenity::async_trait;
 
 command! {
     struct: BaitCommand,
     name: "bait",
     desc: "Open your bait bucket to view and equip bait.",
+    cooldown: User(2),
     run: async |data| {
-        // Start at 0 (The "No Bait" option)
-        let mut index = 0;
-        let mut feedback: Option<String> = None;
-
         // --- PREVENT EXPLOIT: Check if fishing ---
         {
             let fishing_set = data.handler.users_fishing.lock().await;
-            if fishing_set.contains(&data.sender.id) {
+            if fishing_set.contains_key(&data.sender.id) {
                 let embed = CreateEmbed::new()
                     .title("🪣 Bait Bucket")
                     .description("You can't change your bait while your rod is cast!")
@@ -28,14 +28,12 @@ command! {
 
                 data.command.create_response(&data.ctx.http, CreateInteractionResponse::Message(
                     CreateInteractionResponseMessage::new().embed(embed).ephemeral(true)
-                )).await.map_err(|e| e.to_string())?;
+                )).await?;
                 return Ok(());
             }
         }
 
-        let load_file = || UserFile::read(&data.sender.id);
-
-        let mut user_file = load_file();
+        let user_file = data.user_file()?;
 
         // Empty bucket and no equipped bait check
         if user_file.file.inventory.bait_bucket.is_empty() && user_file.file.inventory.selected_bait.is_none() {
@@ -46,120 +44,158 @@ command! {
 
             data.command.create_response(&data.ctx.http, CreateInteractionResponse::Message(
                 CreateInteractionResponseMessage::new().embed(embed).ephemeral(true)
-            )).await.map_err(|e| e.to_string())?;
+            )).await?;
             return Ok(());
         }
 
-        let embed = build_bait_embed(&user_file, index, &feedback);
-        let components = build_bait_components(&user_file);
+        let embed = build_bait_embed(&user_file, 0, &None, &None, &data.settings.config.bait);
+        let components = build_bait_components(&user_file, 0, &None);
 
         let response = CreateInteractionResponseMessage::new()
             .embed(embed)
             .components(components)
             .ephemeral(true);
 
-        data.command.create_response(&data.ctx.http, CreateInteractionResponse::Message(response)).await.map_err(|e| e.to_string())?;
+        data.command.create_response(&data.ctx.http, CreateInteractionResponse::Message(response)).await?;
+
+        Ok(())
+    }
+}
 
-        let mut message = data.command.get_response(&data.ctx.http).await.map_err(|e| e.to_string())?;
+/// Handles the bait bucket's buttons, ids look like `bait:<action>:<cursor index>`
+pub struct BaitComponent;
 
-        let mut collector = message.await_component_interactions(&data.ctx.shard)
-            .timeout(Duration::from_secs(120)) // handled manually in the loop
-            .stream();
+#[async_trait]
+impl ComponentHandler for BaitComponent {
+    fn prefix(&self) -> &'static str {
+        "bait"
+    }
 
-        while let Some(interaction) = collector.next().await {
-            // Reload file to ensure fresh state
-            user_file = load_file();
+    fn command(&self) -> &'static str {
+        "bait"
+    }
 
-            // Max index is the count of items.
-            // If we have 5 items, indices are 0 (No Bait), 1, 2, 3, 4, 5.
-            let max_index = user_file.file.inventory.bait_bucket.len();
+    async fn handle(&self, data: &ComponentData<'_>) -> Result<(), CommandError> {
+        let action: String = data.arg(0)?;
+        let mut index: usize = data.arg(1)?;
+        let mut feedback: Option<String> = None;
+        // The action waiting on the player to confirm it, and the question to ask
+        let mut pending: Option<(&'static str, String)> = None;
+
+        // Reload file to ensure fresh state
+        let mut user_file = data.user_file()?;
+
+        // Max index is the count of items.
+        // If we have 5 items, indices are 0 (No Bait), 1, 2, 3, 4, 5.
+        let max_index = user_file.file.inventory.bait_bucket.len();
+        index = index.min(max_index);
+
+        match action.as_str() {
+            "up" => {
+                if index > 0 { index -= 1; }
+                else { index = max_index; }
+            },
+            "down" => {
+                if index < max_index { index += 1; }
+                else { index = 0; }
+            },
+            "equip" => {
+                // --- PREVENT EXPLOIT: the view can outlive the cast check in /bait ---
+                if data.handler.users_fishing.lock().await.contains_key(&data.sender.id) {
+                    feedback = Some("Failed to equip, you can't change your bait while your rod is cast!".to_string());
+                } else if index == 0 {
+                    // === UNEQUIP LOGIC (No Bait) ===
+                    if user_file.file.inventory.selected_bait.is_some() {
+                        user_file.file.inventory.selected_bait = None;
+                        user_file.update();
+                        feedback = Some("Unequipped bait.".to_string());
+                    } else {
+                        feedback = Some("You aren't using any bait.".to_string());
+                    }
+                } else {
+                    // === EQUIP LOGIC (Specific Bait) ===
+                    // Adjust index because visual index 1 is actually array index 0
+                    let real_index = index - 1;
 
-            let custom_id = match &interaction.data.kind {
-                ComponentInteractionDataKind::Button => interaction.data.custom_id.clone(),
-                _ => continue,
-            };
+                    // Verify the item exists
+                    if let Some(bait) = user_file.file.inventory.bait_bucket.get(real_index) {
+                        let name = bait.name.clone();
 
-            // Reset feedback unless it's an action that produces feedback (equip or toggle)
-            if custom_id != "bait_equip" && custom_id != "bait_toggle" {
-                feedback = None;
-            }
+                        // Set the selected index
+                        user_file.file.inventory.selected_bait = Some(real_index);
+                        user_file.update();
 
-            match custom_id.as_str() {
-                "bait_up" => {
-                    if index > 0 { index -= 1; }
-                    else { index = max_index; }
-                },
-                "bait_down" => {
-                    if index < max_index { index += 1; }
-                    else { index = 0; }
-                },
-                "bait_equip" => {
-                    if index == 0 {
-                        // === UNEQUIP LOGIC (No Bait) ===
-                        if user_file.file.inventory.selected_bait.is_some() {
-                            user_file.file.inventory.selected_bait = None;
+                        feedback = Some(format!("Equipped **{}**!", name));
+                    } else {
+                        feedback = Some("Failed to find that bait.".to_string());
+                        index = 0;
+                    }
+                }
+            },
+            "toggle" => {
+                user_file.file.autobait = !user_file.file.autobait;
+                user_file.update();
+                let status = if user_file.file.autobait { "ON" } else { "OFF" };
+                feedback = Some(format!("AutoBait is now **{}**.", status));
+            },
+            "sell" | "discard" => {
+                let fraction = data.settings.config.shop.sell_back_fraction;
+                let quote = index
+                    .checked_sub(1)
+                    .ok_or("pick a bait first".to_string())
+                    .and_then(|real_index| user_file.file.inventory.sale_quote(GearKind::Bait, real_index, fraction));
+                pending = match (quote, action.as_str()) {
+                    (Ok((name, value)), "sell") => Some(("confirm_sell", format!("Sell one **{}** for ${:.2}?", name, value))),
+                    (Ok((name, _)), _) => Some(("confirm_discard", format!("Throw away one **{}**?", name))),
+                    (Err(msg), _) => {
+                        feedback = Some(format!("Failed to {}, {}.", action, msg));
+                        None
+                    }
+                };
+            },
+            "confirm_sell" | "confirm_discard" => {
+                if data.handler.users_fishing.lock().await.contains_key(&data.sender.id) {
+                    feedback = Some("Failed to remove that bait, you can't change your bait while your rod is cast!".to_string());
+                } else if index == 0 {
+                    feedback = Some("Failed to find that bait.".to_string());
+                } else {
+                    // discarding is selling for nothing
+                    let discard = action == "confirm_discard";
+                    let fraction = if discard { 0.0 } else { data.settings.config.shop.sell_back_fraction };
+                    feedback = Some(match user_file.file.inventory.sell(GearKind::Bait, index - 1, fraction) {
+                        Ok((name, _)) if discard => {
                             user_file.update();
-                            feedback = Some("Unequipped bait.".to_string());
-                        } else {
-                            feedback = Some("You aren't using any bait.".to_string());
+                            format!("Threw away one **{}**.", name)
                         }
-                    } else {
-                        // === EQUIP LOGIC (Specific Bait) ===
-                        // Adjust index because visual index 1 is actually array index 0
-                        let real_index = index - 1;
-
-                        // Verify the item exists
-                        if let Some(bait) = user_file.file.inventory.bait_bucket.get(real_index) {
-                            let name = bait.name.clone();
-
-                            // Set the selected index
-                            user_file.file.inventory.selected_bait = Some(real_index);
+                        Ok((name, value)) => {
+                            user_file.file.balance += MonetaryAmount::new(value);
                             user_file.update();
-
-                            feedback = Some(format!("Equipped **{}**!", name));
-                        } else {
-                            feedback = Some("Failed to find that bait.".to_string());
-                            // Reset index if out of bounds
-                            if index > user_file.file.inventory.bait_bucket.len() {
-                                index = 0;
-                            }
+                            format!("Sold **{}** for ${:.2}!", name, value)
                         }
-                    }
-                },
-                "bait_toggle" => {
-                    user_file.file.autobait = !user_file.file.autobait;
-                    user_file.update();
-                    let status = if user_file.file.autobait { "ON" } else { "OFF" };
-                    feedback = Some(format!("AutoBait is now **{}**.", status));
-                },
-                _ => {}
-            }
-
-            let embed = build_bait_embed(&user_file, index, &feedback);
-            let components = build_bait_components(&user_file);
-
-            let _ = interaction.create_response(&data.ctx.http, CreateInteractionResponse::UpdateMessage(
-                CreateInteractionResponseMessage::new().embed(embed).components(components)
-            )).await;
+                        Err(msg) => format!("Failed to remove that bait, {}.", msg),
+                    });
+                    index = index.min(user_file.file.inventory.bait_bucket.len());
+                }
+            },
+            _ => {}
         }
 
-        // --- Timeout Handling ---
-        // Uses message.edit directly to ensure it actually closes
-        let closed_embed = CreateEmbed::new()
-            .title("🪣 Bait Bucket - Closed")
-            .description("Session timed out.")
-            .color(0x2B2D31);
+        let embed = build_bait_embed(&user_file, index, &feedback, &pending, &data.settings.config.bait);
+        let components = build_bait_components(&user_file, index, &pending);
 
-        let _ = message.edit(&data.ctx.http, EditMessage::new()
-            .embed(closed_embed)
-            .components(vec![])
-        ).await;
-
-        Ok(())
+        data.update_message(
+            CreateInteractionResponseMessage::new().embed(embed).components(components)
+        ).await
     }
 }
 
-fn build_bait_embed(user_file: &UserFile, selected_index: usize, feedback: &Option<String>) -> CreateEmbed {
+fn build_bait_embed(
+    user_file: &UserFile,
+    selected_index: usize,
+    feedback: &Option<String>,
+    pending: &Option<(&'static str, String)>,
+    config: &BaitConfig,
+) -> CreateEmbed {
     let mut description = String::new();
 
     if let Some(msg) = feedback {
@@ -167,6 +203,10 @@ fn build_bait_embed(user_file: &UserFile, selected_index: usize, feedback: &Opti
         description.push_str(&format!("### {} {}\n\n", icon, msg));
     }
 
+    if let Some((_, question)) = pending {
+        description.push_str(&format!("### ❓ {}\n\n", question));
+    }
+
     // AutoBait Status Display
     // let autobait_status = if user_file.file.autobait { "Enabled (auto-equips duplicate baits on use)" } else { "Disabled" };
     // description.push_str(&format!("🤖 **AutoBait:** {}\n", autobait_status));
@@ -189,15 +229,19 @@ fn build_bait_embed(user_file: &UserFile, selected_index: usize, feedback: &Opti
     // We check which index is actually equipped in the inventory data
     let actual_equipped_index = user_file.file.inventory.selected_bait;
 
-    for (i, bait) in user_file.file.inventory.bait_bucket.baits.iter().enumerate() {
+    for (i, stack) in user_file.file.inventory.bait_bucket.stacks.iter().enumerate() {
         let display_index = i + 1;
         let is_equipped = actual_equipped_index == Some(i);
         let equipped_mark = if is_equipped { " *(Equipped)*" } else { "" };
+        let bait = &stack.bait;
 
         if display_index == selected_index {
-            description.push_str(&format!("🔷 **{}{}**\n╰ *{}*\n", bait.name, equipped_mark, bait.description));
+            description.push_str(&format!(
+                "🔷 **{} x{}{}**\n╰ *{}*\n╰ 📊 {}\n",
+                bait.name, stack.count, equipped_mark, bait.description, bait.effect(config).summary()
+            ));
         } else {
-            description.push_str(&format!("▪️ {}{}\n", bait.name, equipped_mark));
+            description.push_str(&format!("▪️ {} x{}{}\n", bait.name, stack.count, equipped_mark));
         }
     }
 
@@ -209,13 +253,30 @@ fn build_bait_embed(user_file: &UserFile, selected_index: usize, feedback: &Opti
         .title("🪣 Bait Bucket")
         .description(description)
         .color(0x2B2D31)
-        .footer(CreateEmbedFooter::new(format!("AutoBait will equip the same bait on use if you have multiple.\nItems: {} | This closes automatically after 2min of inactivity", user_file.file.inventory.bait_bucket.len())))
+        .footer(CreateEmbedFooter::new(format!(
+            "AutoBait keeps the rest of a stack equipped when one is used up.\nBaits: {} in {} stacks",
+            user_file.file.inventory.bait_bucket.total(),
+            user_file.file.inventory.bait_bucket.len()
+        )))
 }
 
-fn build_bait_components(user_file: &UserFile) -> Vec<CreateActionRow> {
-    let up = CreateButton::new("bait_up").label("▲ Up").style(ButtonStyle::Primary);
-    let down = CreateButton::new("bait_down").label("▼ Down").style(ButtonStyle::Primary);
-    let equip = CreateButton::new("bait_equip").label("🎣 Select").style(ButtonStyle::Success);
+fn build_bait_components(
+    user_file: &UserFile,
+    index: usize,
+    pending: &Option<(&'static str, String)>,
+) -> Vec<CreateActionRow> {
+    let id = |action: &str| component_id("bait", &[&action, &index]);
+
+    // Only the confirmation is offered while a sale or discard is pending
+    if let Some((confirm_action, _)) = pending {
+        let confirm = CreateButton::new(id(confirm_action)).label("✅ Confirm").style(ButtonStyle::Danger);
+        let cancel = CreateButton::new(id("cancel")).label("✖ Cancel").style(ButtonStyle::Secondary);
+        return vec![CreateActionRow::Buttons(vec![confirm, cancel])];
+    }
+
+    let up = CreateButton::new(id("up")).label("▲ Up").style(ButtonStyle::Primary);
+    let down = CreateButton::new(id("down")).label("▼ Down").style(ButtonStyle::Primary);
+    let equip = CreateButton::new(id("equip")).label("🎣 Select").style(ButtonStyle::Success);
 
     // Dynamic Toggle Button
     let (label, style) = if user_file.file.autobait {
@@ -223,10 +284,14 @@ fn build_bait_components(user_file: &UserFile) -> Vec<CreateActionRow> {
     } else {
         ("🤖 AutoBait: OFF", ButtonStyle::Secondary)
     };
-    let toggle = CreateButton::new("bait_toggle").label(label).style(style);
+    let toggle = CreateButton::new(id("toggle")).label(label).style(style);
+
+    let sell = CreateButton::new(id("sell")).label("💰 Sell").style(ButtonStyle::Danger);
+    let discard = CreateButton::new(id("discard")).label("🗑️ Discard").style(ButtonStyle::Danger);
 
     vec![
         CreateActionRow::Buttons(vec![up, down]),
         CreateActionRow::Buttons(vec![equip, toggle]),
+        CreateActionRow::Buttons(vec![sell, discard]),
     ]
 }
\ No newline at end of file
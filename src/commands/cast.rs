@@ -1,20 +1,36 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::Mutex;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use rand::Rng;
-use crate::{command, nay, say, wow};
-use serenity::all::{ButtonStyle, ChannelId, Color, CommandInteraction, Context, CreateActionRow, CreateAttachment, CreateButton, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditInteractionResponse, Mentionable, UserId};
-use serenity::builder::CreateEmbedFooter;
-use crate::commands::{command_response_ephemeral, error_command_response};
+use crate::error::{CommandError, ReelError};
+use crate::{command, hey, nay, say, wow};
+use serenity::all::{ButtonStyle, ChannelId, Color, Context, CreateActionRow, CreateAttachment, CreateButton, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseFollowup, CreateInteractionResponseMessage, CreateMessage, EditInteractionResponse, EditMessage, GuildId, InteractionId, Mentionable, MessageId, Timestamp, UserId};
+use serenity::builder::{CreateEmbedAuthor, CreateEmbedFooter};
+use chrono::Utc;
+use crate::data_management::cast_store::{CastStore, PendingCast};
+use serenity::async_trait;
+use crate::commands::error_message;
+use crate::commands::components::{ComponentData, ComponentHandler, ID_SEPARATOR, component_id};
 use crate::commands::game_tips::random_tip;
 use crate::data_management::config::Config;
+use crate::data_management::guildfile::{CatchRecord, EffectiveSettings, GuildSettings};
+use crate::data_management::qte_stats::QteStats;
 use crate::data_management::userfile::UserFile;
+use crate::fishing::aquarium::Trophy;
+use crate::fishing::cast_rules::{is_long_cast, keeps_bait, long_cast_hooks};
 use crate::fishing::fish_data::fish::{Fish, Pond};
 use crate::fishing::fish_data::rarity::FishRarity;
-use crate::fishing::rod_data::bait::Bait;
+use crate::fishing::depth::Depth;
+use crate::fishing::gadgets::{GadgetCatalog, GadgetEffects};
+use crate::fishing::fight::{Fight, FightAction, FightOutcome, MAX_STAMINA, MAX_TENSION, meter};
+use crate::fishing::qte::{Qte, QteInput, QteMiss, QteStage, choose_qte};
+use crate::fishing::rod_data::RodLoadout;
 use crate::helpers::generate_error_code;
+use crate::messenger::{Messenger, SerenityMessenger};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 
 const MYSTERIOUS_MESSAGES: &[&str] = &[
     // Mysterious Comments
@@ -127,31 +143,155 @@ const MISSED_FISH_LINES: &[&str] = &[
     "A swing and a miss!",
 ];
 
-fn missed_fish(fish: &Fish, user_file: &UserFile) -> Vec<(String, String, bool)> {
-    let lost_message = MISSED_FISH_LINES[rand::rng().random_range(0..MISSED_FISH_LINES.len())];
-    // Check inventory flags instead of loadout
-    let lost = if user_file.file.inventory.underwater_cam {
-        vec![
-            ("📹 Underwater Camera".to_string(), format!("You lost a {:.2}' {} weighing {:.2} lbs.", fish.size, fish.fish_type.name, fish.weight), false),
-            ("🧙 Strange Angler Darryl".to_string(), format!("That would've been {}!", fish.value), false)
-        ]
-    } else {
-        vec![
-            ("🧙 Strange Angler Darryl".to_string(), format!("{}", lost_message), false)
-        ]
-    };
+fn missed_fish(fish: &Fish, underwater_cam: bool, darryl: bool, rng: &mut impl Rng) -> Vec<(String, String, bool)> {
+    let lost_message = MISSED_FISH_LINES[rng.random_range(0..MISSED_FISH_LINES.len())];
+    let mut lost = Vec::new();
+
+    if underwater_cam {
+        lost.push(("📹 Underwater Camera".to_string(), format!("You lost a {:.2}' {} weighing {:.2} lbs.", fish.size, fish.fish_type.name, fish.weight), false));
+    }
+
+    // guilds can turn Darryl off
+    if darryl {
+        let comment = if underwater_cam {
+            format!("That would've been {}!", fish.value)
+        } else {
+            lost_message.to_string()
+        };
+        lost.push(("🧙 Strange Angler Darryl".to_string(), comment, false));
+    }
 
     lost
 }
 
+/// The depth a cast aims for, from a depth in feet or a zone.
+/// Zones aim for the middle of the part of them the loadout can reach.
+fn aim_target(loadout: &RodLoadout, depth: Option<f32>, zone: Option<&str>) -> Result<Option<f32>, String> {
+    let (shallowest, deepest) = loadout.depth_reach();
+    let reach = format!("your line reaches {:.0}-{:.0} ft", shallowest, deepest);
+
+    match (depth, zone) {
+        (None, None) => Ok(None),
+        (Some(_), Some(_)) => Err("Aim for a depth or a zone, not both!".to_string()),
+        (Some(depth), None) => {
+            if depth < shallowest || depth > deepest {
+                return Err(format!("You can't reach {:.0} ft, {}.", depth, reach));
+            }
+            Ok(Some(depth))
+        }
+        (None, Some(zone)) => {
+            let (min, max) = Depth::from_name(zone).ok_or("Unknown depth zone")?.get_range();
+            let (min, max) = (min.max(shallowest), max.min(deepest));
+            if min > max {
+                return Err(format!("You can't reach {} water, {}.", zone, reach));
+            }
+            Ok(Some((min + max) / 2.0))
+        }
+    }
+}
+
+/// What the sonar shows about a hooked fish, None without one
+fn sonar_reading(fish: &Fish, gadgets: &GadgetEffects) -> Option<String> {
+    gadgets.sonar.then(|| format!("Rarity: **{}**\nCategory: **{:?}**", fish.fish_type.rarity, fish.fish_type.category))
+}
+
+/// The species that live at a depth, for the fish finder
+fn fish_at_depth(pond: &Pond, depth: f32) -> String {
+    const SHOWN: usize = 10;
+
+    let names: Vec<&str> = pond
+        .fish_types
+        .iter()
+        .filter(|f| f.depth_range.0 <= depth && depth <= f.depth_range.1)
+        .map(|f| f.name.as_str())
+        .collect();
+
+    match names.len() {
+        0 => "Nothing lives this deep...".to_string(),
+        n if n > SHOWN => format!("{}, and {} more", names[..SHOWN].join(", "), n - SHOWN),
+        _ => names.join(", "),
+    }
+}
+
+/// Everything that is decided the moment the line hits the water
+pub struct RolledCast {
+    pub depth: f32,
+    pub fish: Option<Fish>,
+    /// seconds until the catch
+    pub catch_time: f32,
+}
+
+pub enum CastRollError {
+    Sinker(ReelError),
+    Fish(ReelError),
+}
+
+impl CastRollError {
+    /// the error code prefix and the error
+    fn into_parts(self) -> (&'static str, ReelError) {
+        match self {
+            // Sinker / Fish Failure To Generate
+            CastRollError::Sinker(e) => ("SINKER_FTG", e),
+            CastRollError::Fish(e) => ("FISH_FTG", e),
+        }
+    }
+}
+
+/// the extra chance for a fish that got away to bite again, when a cast lands right on target
+const PRECISE_CAST_LUCK: f32 = 0.1;
+
+/// rng stream for everything rolled when the line is cast
+pub const ROLL_STREAM: u64 = 0;
+/// rng stream for everything rolled when the fish bites
+pub const CATCH_STREAM: u64 = 1;
+
+/// Every roll a cast makes comes from its seed, so a disputed catch can be replayed exactly.
+/// The cast and the catch use separate streams, so a cast resumed after a restart
+/// still rolls the same catch as one that never stopped.
+pub fn cast_rng(seed: u64, stream: u64) -> StdRng {
+    let mut bytes = [0u8; 32];
+    bytes[..8].copy_from_slice(&seed.to_le_bytes());
+    bytes[8..16].copy_from_slice(&stream.to_le_bytes());
+    StdRng::from_seed(bytes)
+}
+
+/// Roll the depth, the fish and the wait for a cast, near the `target` depth if the player aimed
+pub fn roll_cast(
+    loadout: &RodLoadout,
+    target: Option<f32>,
+    pond: &Pond,
+    config: &Config,
+    rng: &mut impl Rng,
+) -> Result<RolledCast, CastRollError> {
+    let depth = loadout.generate_depth(target, rng).map_err(CastRollError::Sinker)?;
+
+    // generate the fish from the pond
+    let fish = pond
+        .generate_fish(depth, loadout.near_bottom(depth), loadout.bait.as_ref(), config, rng)
+        .map_err(CastRollError::Fish)?;
+
+    // calculate the catch time
+    let catch_time = loadout.calculate_cast_time(fish.as_ref(), config);
+
+    Ok(RolledCast { depth, fish, catch_time })
+}
+
 pub struct CastHandler {
-    ctx: Context,
+    messenger: Arc<dyn Messenger>,
     user: UserId,
     channel: ChannelId,
     fish: Option<Fish>,
-    users_fishing: Arc<Mutex<HashSet<UserId>>>,
+    users_fishing: Arc<Mutex<HashMap<UserId, Arc<AtomicBool>>>>,
     canceled: Arc<AtomicBool>,
-    interaction: CommandInteraction,
+    // token of the /cast interaction, so the cancel button can be removed even after a restart
+    interaction_token: String,
+    user_name: String,
+    long_cast: bool,
+    // the guild the cast was made in, for its settings
+    guild: Option<GuildId>,
+    precise_cast: bool,
+    // shown with the catch in the hall of fame
+    avatar_url: Option<String>,
 }
 
 command! {
@@ -159,301 +299,849 @@ command! {
     name: "cast",
     desc: "Cast your line into the pond",
     requires_guild: false,
+    cooldown: User(5),
+
+    run: async |data,
+        preset("A saved loadout to switch to before casting, see /loadout"): Option<String>,
+        depth("A depth in feet to aim for, within your sinker's range"): Option<f64>,
+        zone("A depth zone to aim for": [
+            "Shallow (0-60ft)": "Shallow",
+            "Mid Water (60-200ft)": "MidWater",
+            "Deep (200-1000ft)": "Deep",
+            "Abyssal (1000-4000ft)": "Abyssal",
+            "Hadal (4000ft+)": "Hadal"
+        ]): Option<String>| {
+
+        let request = CastRequest {
+            user: data.sender.id,
+            user_name: data.sender.display_name().to_string(),
+            avatar_url: Some(data.sender.face()),
+            channel: data.channel,
+            guild: data.guild_id.copied(),
+            interaction: data.command.id,
+            interaction_token: data.command.token.clone(),
+            settings: data.settings.clone(),
+            users_fishing: data.handler.users_fishing.clone(),
+        };
+        let messenger = Arc::new(SerenityMessenger::new(data.ctx.clone()));
+        let seed: u64 = rand::rng().random();
 
-    run: async |data| {
-
-        // ensure the user is not already casting
-        let user_id = data.sender.id;
-        {
-            let fishing_set = data.handler.users_fishing.lock().await;
-            if fishing_set.contains(&user_id) {
-                command_response_ephemeral(&data.ctx, &data.command,
-                    "You are already fishing!").await;
-                return Ok(());
-            }
-        }
+        cast_line(messenger, request, preset, depth.map(|d| d as f32), zone, seed).await
+    }
+}
 
-        // add the user to the set of users currently fishing
-        let users_fishing = data.handler.users_fishing.clone();
-        {
-            let mut fishing_set = users_fishing.lock().await;
-            fishing_set.insert(user_id);
-        }
+/// Everything a cast needs from the /cast interaction
+struct CastRequest {
+    user: UserId,
+    user_name: String,
+    avatar_url: Option<String>,
+    channel: ChannelId,
+    guild: Option<GuildId>,
+    interaction: InteractionId,
+    interaction_token: String,
+    settings: EffectiveSettings,
+    users_fishing: Arc<Mutex<HashMap<UserId, Arc<AtomicBool>>>>,
+}
 
+impl CastRequest {
+    /// the guild for log lines, so `/errorcode` can find them
+    fn log_guild(&self) -> String {
+        self.guild.map_or("dm".to_string(), |g| g.to_string())
+    }
+}
 
-        // get the user file
-        let user_file = UserFile::read(&data.sender.id);
+/// Answer the /cast interaction with a message only the player sees
+async fn respond_ephemeral(messenger: &dyn Messenger, request: &CastRequest, msg: impl Into<String>) {
+    let response = CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new().content(msg.into()).ephemeral(true),
+    );
+    if let Err(e) = messenger.create_response(request.interaction, &request.interaction_token, response).await {
+        nay!({user: request.user, guild: request.log_guild()}, "Failed to respond to command: {}", e);
+    }
+}
 
-        // load the pond
-        let Ok(pond) = Pond::load() else {
-            command_response_ephemeral(&data.ctx, &data.command,
-                "Pond is closed! We are having some technical issues, please stand by!").await;
+/// Cast the player's line and schedule the catch, everything /cast does once it has the interaction
+async fn cast_line(
+    messenger: Arc<dyn Messenger>,
+    request: CastRequest,
+    preset: Option<String>,
+    depth: Option<f32>,
+    zone: Option<String>,
+    seed: u64,
+) -> Result<(), CommandError> {
+    let user_id = request.user;
+
+    // get the user file
+    let mut user_file = UserFile::read_in(&user_id, request.settings.profile_guild)?;
+
+    // switch gear first, unless the player is already fishing with the gear they have on
+    let mut preset_note = None;
+    if let Some(preset) = &preset {
+        if request.users_fishing.lock().await.contains_key(&user_id) {
+            respond_ephemeral(&*messenger, &request, "You are already fishing!").await;
             return Ok(());
-        };
+        }
 
-        // Construct a loadout snapshot from the inventory
-        let loadout = user_file.file.inventory.get_loadout();
-
-        let Ok(generated_depth) = loadout.sinker.generate_depth() else {
-            // Sinker Failure To Generate Error
-            let error_code = format!("SINKER_FTG-{}", generate_error_code());
-                    nay!(
-                        "Sinker Fail To Generate Error: {} CODE: {}",
-                        data.command_name,
-                        error_code.clone()
-                    );
-                    error_command_response(&data.ctx, &data.command, error_code).await;
-            return Ok(());
-        };
+        let missing = user_file.file.inventory.load_preset(preset)?;
+        user_file.update();
 
-        let bait: Option<&Bait> = if let Some(bait) = &loadout.bait {
-            Some(bait)
-        } else {
-            None
-        };
-
-        // generate the fish from the pond
-        let Ok(fish) = pond.generate_fish(generated_depth, bait) else {
-            // Fish Failure To Generate Error
-            let error_code = format!("FISH_FTG-{}", generate_error_code());
-                    nay!(
-                        "Fish Fail To Generate Error: {} CODE: {}",
-                        data.command_name,
-                        error_code.clone()
-                    );
-                    error_command_response(&data.ctx, &data.command, error_code).await;
-            return Ok(());
-        };
+        let mut note = format!("Switched to **{}**.", preset.trim());
+        if !missing.is_empty() {
+            note.push_str(&format!(" Missing: {}", missing.join(", ")));
+        }
+        preset_note = Some(note);
+    }
 
-        let config = Config::load();
+    // load the pond
+    let Ok(pond) = Pond::load() else {
+        respond_ephemeral(&*messenger, &request, "Pond is closed! We are having some technical issues, please stand by!").await;
+        return Ok(());
+    };
 
-        // calculate the catch time
-        let mut catch_time = loadout.generate_catch_time();
-        if let Some(f) = &fish {
-            let weight_catch_time = (f.weight - f.fish_type.weight_range.average) * config.fishing.fish_weight_time_multiplier;
-            catch_time += weight_catch_time;
+    // Construct a loadout snapshot from the inventory
+    let loadout = user_file.file.inventory.get_loadout();
+    let target = aim_target(&loadout, depth, zone.as_deref())?;
+    let gadgets = GadgetCatalog::load().effects(&user_file.file.inventory.gadgets);
+
+    let config = &request.settings.config;
+
+    let mut rng = cast_rng(seed, ROLL_STREAM);
+
+    let RolledCast { depth: generated_depth, fish, catch_time } = match roll_cast(&loadout, target, &pond, config, &mut rng) {
+        Ok(rolled) => rolled,
+        Err(e) => {
+            // logged here instead of by `respond_with_error` so the seed is kept, and the cast can be replayed
+            let (code, error) = e.into_parts();
+            let error_code = format!("{}-{}", code, generate_error_code());
+            nay!(
+                {user: user_id, guild: request.log_guild(), command: "cast", code: error_code, seed: seed},
+                "Command /cast failed: {}",
+                error
+            );
+            respond_ephemeral(&*messenger, &request, error_message(&error_code)).await;
+            return Ok(());
         }
-        catch_time = catch_time.max(config.fishing.min_cast_wait).min(config.fishing.max_cast_wait);
+    };
+    // only a depth finder can tell when a cast landed right on target
+    let precise_cast = gadgets.depth_finder && target.is_some_and(|t| loadout.is_precise(t, generated_depth));
+
+    // ensure the user is not already casting, then add them to the set of users currently fishing
+    // nothing above can leave them stuck as "fishing" if it fails
+    // the flag is shared with the cancel button so it can stop the cast
+    let canceled = Arc::new(AtomicBool::new(false));
+    let users_fishing = request.users_fishing.clone();
+    let already_fishing = {
+        let mut fishing_set = users_fishing.lock().await;
+        fishing_set.contains_key(&user_id) || fishing_set.insert(user_id, canceled.clone()).is_some()
+    };
+    if already_fishing {
+        respond_ephemeral(&*messenger, &request, "You are already fishing!").await;
+        return Ok(());
+    }
 
-        // log cast information
-        if config.general.log_cast_data {
-            match &fish {
-                Some(f) => {
-                    match f.fish_type.rarity {
-                        FishRarity::Legendary
-                        | FishRarity::Mythical => wow!("{} is attempting to catch a {} in {}seconds!", data.sender.display_name(), f, catch_time),
-                        _ => say!("{} is attempting to catch a {} in {}seconds!", data.sender.display_name(), f, catch_time),
-                    }
-                }
-                None => {
-                    say!("{} is attempting to catch nothing in {}seconds!", data.sender.display_name(), catch_time);
+    // log cast information
+    if config.general.log_cast_data {
+        match &fish {
+            Some(f) => {
+                match f.fish_type.rarity {
+                    FishRarity::Legendary
+                    | FishRarity::Mythical => wow!({user: user_id, guild: request.log_guild(), seed: seed}, "{} is attempting to catch a {} in {}seconds!", request.user_name, f, catch_time),
+                    _ => say!({user: user_id, guild: request.log_guild(), seed: seed}, "{} is attempting to catch a {} in {}seconds!", request.user_name, f, catch_time),
                 }
             }
+            None => {
+                say!({user: user_id, guild: request.log_guild(), seed: seed}, "{} is attempting to catch nothing in {}seconds!", request.user_name, catch_time);
+            }
         }
+    }
 
-        let canceled = Arc::new(AtomicBool::new(false));
+    // store the cast so it can be finished if the bot restarts before the catch
+    CastStore::add(PendingCast {
+        user_id: user_id.get(),
+        user_name: request.user_name.clone(),
+        channel_id: request.channel.get(),
+        fish: fish.clone(),
+        ends_at: Utc::now().timestamp_millis() + (catch_time * 1000.0) as i64,
+        long_cast: is_long_cast(catch_time),
+        interaction_token: request.interaction_token.clone(),
+        seed,
+        guild_id: request.guild.map(|g| g.get()),
+        precise_cast,
+        avatar_url: request.avatar_url.clone(),
+    });
+
+    let cast = CastHandler {
+        messenger: messenger.clone(),
+        user: user_id,
+        channel: request.channel,
+        fish,
+        users_fishing: users_fishing.clone(),
+        canceled: canceled.clone(),
+        interaction_token: request.interaction_token.clone(),
+        user_name: request.user_name.clone(),
+        long_cast: is_long_cast(catch_time),
+        guild: request.guild,
+        precise_cast,
+        avatar_url: request.avatar_url.clone(),
+    };
 
-        let cast = CastHandler {
-            ctx: data.ctx.clone(),
-            user: data.sender.id.clone(),
-            channel: data.channel.clone(),
-            fish,
-            users_fishing: users_fishing.clone(),
-            canceled: canceled.clone(),
-            interaction: data.command.clone(),
-        };
+    if catch_time > 40.0 && request.settings.features.darryl_messages {
+        let messenger = messenger.clone();
+        let channel = request.channel;
+        let canceled_clone = canceled.clone();
 
-        if catch_time > 40.0 {
-            let ctx = data.ctx.clone();
-            let channel = data.channel.clone();
-            let user_id = data.sender.id;
-            let canceled_clone = canceled.clone();
+        tokio::spawn(async move {
+            // Wait for 25% of the duration
+            let first_wait = (catch_time * 0.25) as u64;
+            tokio::time::sleep(Duration::from_secs(first_wait)).await;
+
+            // Check if user canceled
+            if canceled_clone.load(Ordering::Relaxed) { return; }
+
+            // Determine message based on duration
+            let message_content = if catch_time >= 180.0 {
+                "I hope you brought a lunch, because we aren't leaving anytime soon!\nKeep holding that line tight!"
+            } else if catch_time > 120.0 {
+                "This will take a while!"
+            } else if catch_time > 60.0 {
+                "This could take a minute!"
+            } else {
+                "That's a big one! Hold on tight!"
+            };
 
-            tokio::spawn(async move {
-                // Wait for 25% of the duration
-                let first_wait = (catch_time * 0.25) as u64;
-                tokio::time::sleep(Duration::from_secs(first_wait)).await;
+            // Send first warning
+            let embed = CreateEmbed::new()
+                .title("🧙 Strange Angler Darryl")
+                .description(format!("*{}*", message_content))
+                .thumbnail("attachment://darryl.png")
+                .color(0x3498db); // Match water color
 
-                // Check if user canceled
-                if canceled_clone.load(Ordering::Relaxed) { return; }
+            let mut msg = CreateMessage::new()
+                .content(user_id.mention().to_string())
+                .embed(embed);
 
-                // Determine message based on duration
-                let message_content = if catch_time >= 180.0 {
-                    "I hope you brought a lunch, because we aren't leaving anytime soon!\nKeep holding that line tight!"
-                } else if catch_time > 120.0 {
-                    "This will take a while!"
-                } else if catch_time > 60.0 {
-                    "This could take a minute!"
-                } else {
-                    "That's a big one! Hold on tight!"
-                };
+            if let Ok(file) = CreateAttachment::path("./assets/darryl.png").await {
+                msg = msg.add_file(file);
+            }
 
-                // Send first warning
-                let embed = CreateEmbed::new()
+            let _ = messenger.send_message(channel, msg).await;
+
+            // Wait until 10 seconds remaining
+            // We have already slept for `first_wait` seconds.
+            // We need to sleep until `catch_time - 10`.
+            let target_time = catch_time - 10.0;
+
+            // Ensure we don't try to sleep negative time or if the interval is too small
+            if target_time > (first_wait as f32) {
+                let remaining_wait = target_time - (first_wait as f32);
+                tokio::time::sleep(Duration::from_secs(remaining_wait as u64)).await;
+
+                // Check if user canceled again
+                if canceled_clone.load(Ordering::Relaxed) { return; }
+
+                // Send final warning
+                let embed_final = CreateEmbed::new()
                     .title("🧙 Strange Angler Darryl")
-                    .description(format!("*{}*", message_content))
+                    .description("*It's almost here! You better be ready!*")
                     .thumbnail("attachment://darryl.png")
-                    .color(0x3498db); // Match water color
+                    .color(0x3498db);
 
-                let mut msg = CreateMessage::new()
+                let mut msg_final = CreateMessage::new()
                     .content(user_id.mention().to_string())
-                    .embed(embed);
+                    .embed(embed_final);
 
                 if let Ok(file) = CreateAttachment::path("./assets/darryl.png").await {
-                    msg = msg.add_file(file);
+                    msg_final = msg_final.add_file(file);
                 }
 
-                let _ = channel.send_message(&ctx, msg).await;
+                let _ = messenger.send_message(channel, msg_final).await;
+            }
+        });
+    }
 
-                // Wait until 10 seconds remaining
-                // We have already slept for `first_wait` seconds.
-                // We need to sleep until `catch_time - 10`.
-                let target_time = catch_time - 10.0;
+    // schedule the catch
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(catch_time as u64)).await;
+        catch(cast, &mut cast_rng(seed, CATCH_STREAM)).await;
+    });
 
-                // Ensure we don't try to sleep negative time or if the interval is too small
-                if target_time > (first_wait as f32) {
-                    let remaining_wait = target_time - (first_wait as f32);
-                    tokio::time::sleep(Duration::from_secs(remaining_wait as u64)).await;
+    // let the user know they have cast their line
+    let random_mysterious_message = MYSTERIOUS_MESSAGES[rng.random_range(0..MYSTERIOUS_MESSAGES.len())];
 
-                    // Check if user canceled again
-                    if canceled_clone.load(Ordering::Relaxed) { return; }
+    // create the embed
 
-                    // Send final warning
-                    let embed_final = CreateEmbed::new()
-                        .title("🧙 Strange Angler Darryl")
-                        .description("*It's almost here! You better be ready!*")
-                        .thumbnail("attachment://darryl.png")
-                        .color(0x3498db);
+    // Check inventory for depth finder support
+    let mut depth_display = if gadgets.depth_finder {
+        format!("{:.2} ft", generated_depth)
+    } else {
+        "??? ft".to_string()
+    };
+    if let Some(target) = target {
+        depth_display.push_str(&format!(" (aimed for {:.0} ft)", target));
+    }
+    if precise_cast {
+        depth_display.push_str("\n🎯 Right on target! Fish are more likely to bite.");
+    }
+    // sinkers that go deeper than the boat (or the shore) allows are stopped short
+    if loadout.cut_short(generated_depth) {
+        let reach = match &loadout.boat {
+            Some(boat) => format!("Your {} can only take you over {:.0}ft of water.", boat.name, loadout.max_depth()),
+            None => format!("From the shore your line only reaches {:.0}ft. Buy a boat at the `/shop` to go deeper.", loadout.max_depth()),
+        };
+        depth_display.push_str(&format!("\n⛵ Your sinker could have gone deeper! {}", reach));
+    }
 
-                    let mut msg_final = CreateMessage::new()
-                        .content(user_id.mention().to_string())
-                        .embed(embed_final);
+    let mut fields = vec![("🌊 Cast Depth", depth_display, false)];
+    if gadgets.fish_finder {
+        fields.push(("📟 Fish Finder", fish_at_depth(&pond, generated_depth), false));
+    }
+    if let Some(note) = preset_note {
+        fields.push(("🎒 Loadout", note, false));
+    }
+    if request.settings.features.darryl_messages {
+        fields.push(("🧙 Strange Angler Darryl", format!("*{}*", random_mysterious_message), false));
+    }
 
-                    if let Ok(file) = CreateAttachment::path("./assets/darryl.png").await {
-                        msg_final = msg_final.add_file(file);
-                    }
+    let embed = CreateEmbed::new()
+    .title(format!("🎣 You cast your {} into the pond!", loadout.rod.name))
+    //.description(format!("\n**Strange Angler Darryl:** *{}*\n\nCast to {}. Waiting for a bite...", random_mysterious_message, depth_display))
+    .description("Waiting for a bite...".to_string())
+    .fields(fields)
+    .thumbnail("attachment://FishingRod.png")
+    .color(0x3498db)
+    .footer(CreateEmbedFooter::new(random_tip(&mut rng)));
+
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(component_id("cast_cancel", &[&user_id]))
+            .label("Reel In (Cancel)")
+            .style(ButtonStyle::Danger),
+    ]);
+
+    let mut message = CreateInteractionResponseMessage::new()
+            .ephemeral(true)
+            .embed(embed)
+            .components(vec![buttons]);
+
+    let attachment = CreateAttachment::path("./assets/FishingRod.png").await;
+
+    // use the attachment if found
+    if let Ok(file) = attachment {
+        message = message.add_file(file);
+    }
 
-                    let _ = channel.send_message(&ctx, msg_final).await;
-                }
-            });
+    let builder = CreateInteractionResponse::Message(message);
+
+    if let Err(e) = messenger.create_response(request.interaction, &request.interaction_token, builder).await {
+        nay!({user: user_id, guild: request.log_guild()}, "Failed to send cast message: {}", e);
+        let mut fishing_set = users_fishing.lock().await;
+        fishing_set.remove(&user_id);
+        canceled.store(true, Ordering::Relaxed);
+        CastStore::remove(&user_id);
+        return Ok(());
+    }
+
+    Ok(())
+}
+
+/// Handles the "Reel In" button on a cast, ids look like `cast_cancel:<user id>`
+pub struct CastCancelComponent;
+
+#[async_trait]
+impl ComponentHandler for CastCancelComponent {
+    fn prefix(&self) -> &'static str {
+        "cast_cancel"
+    }
+
+    fn command(&self) -> &'static str {
+        "cast"
+    }
+
+    async fn handle(&self, data: &ComponentData<'_>) -> Result<(), CommandError> {
+        let owner: UserId = data.arg(0)?;
+        if owner != data.sender.id {
+            return Err("That isn't your line!".into());
         }
 
-        // schedule the catch
-        tokio::spawn(async move {
-            tokio::time::sleep(Duration::from_secs(catch_time as u64)).await;
-            catch(cast, catch_time >= 90.0).await;
-        });
+        // remove the user from the fishing set
+        let canceled = data.handler.users_fishing.lock().await.remove(&owner);
 
-        // let the user know they have cast their line
-        let random_mysterious_message = MYSTERIOUS_MESSAGES[rand::rng().random_range(0..MYSTERIOUS_MESSAGES.len())];
+        CastStore::remove(&owner);
 
-        // create the embed
+        let update_embed = if let Some(canceled) = canceled {
+            // User clicked cancel
+            if Config::load().general.log_cast_data {
+                say!({user: owner, guild: data.log_guild()}, "{} clicked the cancel button!", data.sender.display_name());
+            }
+            canceled.store(true, Ordering::Relaxed);
 
-        // Check inventory for depth finder support
-        let depth_display = if user_file.file.inventory.depth_finder {
-            format!("{:.2} ft", generated_depth)
+            CreateEmbed::new()
+                .title("🛑 Cast Canceled")
+                .description("You reeled in your line early.")
+                .color(0x95a5a6) // Grey color
         } else {
-            "??? ft".to_string()
+            // the cast already finished (or the bot restarted since it was cast)
+            CreateEmbed::new()
+                .title("🎣 Nothing To Reel In")
+                .description("This cast has already ended.")
+                .color(0x95a5a6)
         };
 
-        let embed = CreateEmbed::new()
-        .title(format!("🎣 You cast your {} into the pond!", loadout.rod.name))
-        //.description(format!("\n**Strange Angler Darryl:** *{}*\n\nCast to {}. Waiting for a bite...", random_mysterious_message, depth_display))
-        .description("Waiting for a bite...".to_string())
-        .fields(vec![
-            ("🌊 Cast Depth", format!("{}", depth_display), false),
-            ("🧙 Strange Angler Darryl", format!("*{}*", random_mysterious_message), false),
-        ])
-        .thumbnail("attachment://FishingRod.png")
-        .color(0x3498db)
-        .footer(CreateEmbedFooter::new(format!("{}", random_tip())));
+        data.update_message(
+            CreateInteractionResponseMessage::new()
+                .embed(update_embed)
+                .components(vec![]) // Remove buttons
+        ).await
+    }
+}
 
-        let button_id = format!("cancel_cast_{}", user_id);
-        let buttons = CreateActionRow::Buttons(vec![
-            CreateButton::new(&button_id)
-                .label("Reel In (Cancel)")
-                .style(ButtonStyle::Danger),
-        ]);
+/// Pick up the casts that were in flight when the bot last stopped.
+/// Casts that should have finished while the bot was down are caught right away.
+pub async fn resume_casts(ctx: &Context, users_fishing: Arc<Mutex<HashMap<UserId, Arc<AtomicBool>>>>) {
+    let casts = CastStore::load();
+    if casts.is_empty() {
+        return;
+    }
+    say!("Resuming {} casts from before the restart", casts.len());
 
-        let mut message = CreateInteractionResponseMessage::new()
-                .ephemeral(true)
-                .embed(embed)
-                .components(vec![buttons]);
+    let now = Utc::now().timestamp_millis();
+    for pending in casts {
+        let user = UserId::new(pending.user_id);
+        let canceled = Arc::new(AtomicBool::new(false));
+        users_fishing.lock().await.insert(user, canceled.clone());
 
-        let attachment = CreateAttachment::path("./assets/FishingRod.png").await;
+        let cast = CastHandler {
+            messenger: Arc::new(SerenityMessenger::new(ctx.clone())),
+            user,
+            channel: ChannelId::new(pending.channel_id),
+            fish: pending.fish,
+            users_fishing: users_fishing.clone(),
+            canceled,
+            interaction_token: pending.interaction_token,
+            user_name: pending.user_name,
+            long_cast: pending.long_cast,
+            guild: pending.guild_id.map(GuildId::new),
+            precise_cast: pending.precise_cast,
+            avatar_url: pending.avatar_url,
+        };
 
-        // use the attachment if found
-        if let Ok(file) = attachment {
-            message = message.add_file(file);
+        let remaining = (pending.ends_at - now).max(0) as u64;
+        let seed = pending.seed;
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(remaining)).await;
+            catch(cast, &mut cast_rng(seed, CATCH_STREAM)).await;
+        });
+    }
+}
+
+/// Tell the angler their profile couldn't be read for a catch, with an error code to report
+async fn profile_read_failed(catch: &CastHandler, e: ReelError) {
+    let error_code = format!("{}-{}", e.code(), generate_error_code());
+    nay!({user: catch.user, guild: catch.log_guild(), code: error_code}, "Failed to read user data for a catch: {}", e);
+
+    let message = CreateMessage::new()
+        .content(format!("{} {}", catch.user.mention(), error_message(&error_code)));
+    if let Err(e) = catch.messenger.send_message(catch.channel, message).await {
+        nay!({user: catch.user, guild: catch.log_guild()}, "Failed to send cast response message: {}", e);
+    }
+}
+
+/// Read the angler's profile again after a QTE or fight, which can take minutes.
+/// The player is free to shop, sell or cast in the meantime, and saving the profile
+/// read before it would undo all of that. Returns false (and the fish is lost) if it can't be read.
+async fn reload_profile(catch: &CastHandler, user_file: &mut UserFile) -> bool {
+    match user_file.reload() {
+        Ok(()) => true,
+        Err(e) => {
+            profile_read_failed(catch, e).await;
+            false
         }
+    }
+}
 
-        let builder = CreateInteractionResponse::Message(message);
+impl CastHandler {
+    /// the guild for log lines, so `/errorcode` can find them
+    fn log_guild(&self) -> String {
+        self.guild.map_or("dm".to_string(), |g| g.to_string())
+    }
+}
 
-        if let Err(e) = data.command.create_response(&data.ctx, builder).await {
-            nay!("Failed to send cast message: {}", e);
-            let mut fishing_set = users_fishing.lock().await;
-            fishing_set.remove(&user_id);
-            return Ok(());
+/// remove the user from the casting set (unless a newer cast has taken its place)
+async fn release_cast(catch: &CastHandler) {
+    let mut fishing_set = catch.users_fishing.lock().await;
+    if fishing_set.get(&catch.user).is_some_and(|c| Arc::ptr_eq(c, &catch.canceled)) {
+        fishing_set.remove(&catch.user);
+    }
+}
+
+/// Send one of a catch's results to the angler.
+/// Results are posted in the channel, or only shown to the angler if the guild keeps them private.
+async fn send_result(catch: &CastHandler, public: bool, embed: CreateEmbed, attachment: Option<&str>) -> Result<(), ReelError> {
+    send_result_with_buttons(catch, public, embed, attachment, vec![]).await?;
+    Ok(())
+}
+
+/// Where a result sent with `send_result_with_buttons` ended up, so `edit_result` can find it
+#[derive(Clone, Copy)]
+enum SentResult {
+    /// a message in the cast's channel, or in the angler's DMs
+    Message(ChannelId, MessageId),
+    /// an ephemeral follow up to /cast
+    Followup(MessageId),
+}
+
+impl SentResult {
+    fn id(self) -> MessageId {
+        match self {
+            SentResult::Message(_, id) | SentResult::Followup(id) => id,
         }
+    }
 
-        // wait for the user to click the cancel button
-        let Ok(interaction) = data.command.get_response(&data.ctx).await else {
-            // Failed to get the response message, likely due to a timeout or other issue. Just return and let the catch happen.
-            return Ok(());
+    /// Where the angler answers the message, their DMs if that's where it went
+    fn channel(self, catch: &CastHandler) -> ChannelId {
+        match self {
+            SentResult::Message(channel, _) => channel,
+            SentResult::Followup(_) => catch.channel,
+        }
+    }
+}
+
+/// `send_result`, with buttons. Returns where the message went so it can be edited with `edit_result`.
+/// Private results fall back to a DM once the interaction's token has expired, e.g. after a long cast or a restart.
+async fn send_result_with_buttons(
+    catch: &CastHandler,
+    public: bool,
+    embed: CreateEmbed,
+    attachment: Option<&str>,
+    buttons: Vec<CreateActionRow>,
+) -> Result<SentResult, ReelError> {
+    let file = match attachment {
+        Some(path) => CreateAttachment::path(path).await.ok(),
+        None => None,
+    };
+
+    let mut message = CreateMessage::new().embed(embed.clone()).components(buttons.clone());
+    if let Some(file) = file.clone() {
+        message = message.add_file(file);
+    }
+
+    if public {
+        let message = message.content(format!("{}", catch.user.mention()));
+        let id = catch.messenger.send_message(catch.channel, message).await?;
+        return Ok(SentResult::Message(catch.channel, id));
+    }
+
+    let mut followup = CreateInteractionResponseFollowup::new()
+        .embed(embed)
+        .components(buttons)
+        .ephemeral(true);
+    if let Some(file) = file {
+        followup = followup.add_file(file);
+    }
+    match catch.messenger.send_followup(&catch.interaction_token, followup).await {
+        Ok(id) => Ok(SentResult::Followup(id)),
+        Err(e) => {
+            hey!({user: catch.user, guild: catch.log_guild()}, "Failed to send {} a private result, sending it by DM instead: {}", catch.user_name, e);
+            let (channel, id) = catch.messenger.send_dm(catch.user, message).await?;
+            Ok(SentResult::Message(channel, id))
+        }
+    }
+}
+
+/// Replace the embed and buttons of a result sent with `send_result_with_buttons`
+async fn edit_result(
+    catch: &CastHandler,
+    sent: SentResult,
+    embed: CreateEmbed,
+    buttons: Vec<CreateActionRow>,
+) -> Result<(), ReelError> {
+    match sent {
+        SentResult::Message(channel, message) => {
+            let edit = EditMessage::new().embed(embed).components(buttons);
+            catch.messenger.edit_message(channel, message, edit).await
+        }
+        SentResult::Followup(message) => {
+            let edit = CreateInteractionResponseFollowup::new().embed(embed).components(buttons);
+            catch.messenger.edit_followup(&catch.interaction_token, message, edit).await
+        }
+    }
+}
+
+/// The embed for one stage of a QTE
+fn qte_embed(qte: &dyn Qte, index: usize, tip: &str, sonar: Option<&str>) -> CreateEmbed {
+    let stage = &qte.stages()[index];
+    let time = match stage.live_after {
+        Some(_) => format!("You'll have **{:.1}s** once it's green.", stage.seconds),
+        None => format!("You have **{:.1}s**!", stage.seconds),
+    };
+
+    let mut description = format!("{} {}", stage.instructions, time);
+    if qte.stages().len() > 1 {
+        description = format!("**Round {}/{}:** {}", index + 1, qte.stages().len(), description);
+    }
+
+    let mut embed = CreateEmbed::new()
+        .title(qte.title())
+        .description(description)
+        .thumbnail("attachment://FishingRod.png")
+        .color(Color::RED)
+        .footer(CreateEmbedFooter::new(tip));
+    if let Some((name, value)) = &stage.prompt {
+        embed = embed.field(*name, value, false);
+    }
+    if let Some(reading) = sonar {
+        embed = embed.field("📡 Sonar", reading, false);
+    }
+    embed
+}
+
+/// The buttons for a QTE stage. Stages that wait before going live show grey buttons until they are.
+/// No component handler uses the `qte` prefix, so presses are left to the catch's collector.
+fn qte_buttons(stage: &QteStage, live: bool) -> Vec<CreateActionRow> {
+    let QteInput::Buttons(buttons) = &stage.input else {
+        return vec![];
+    };
+
+    let style = match (stage.live_after, live) {
+        (Some(_), false) => ButtonStyle::Secondary,
+        (Some(_), true) => ButtonStyle::Success,
+        (None, _) => ButtonStyle::Primary,
+    };
+
+    vec![CreateActionRow::Buttons(
+        buttons
+            .iter()
+            .map(|b| CreateButton::new(component_id("qte", &[&b.id])).label(&b.label).style(style))
+            .collect(),
+    )]
+}
+
+/// remove the bait the player had on the line when it snaps
+fn snap_bait(user_file: &mut UserFile) {
+    if user_file.file.inventory.selected_bait.is_some() {
+        user_file.file.inventory.use_selected_bait(false);
+        user_file.update();
+    }
+}
+
+/// Wear down the angler's gear after a cast, and let them know if anything broke
+async fn wear_gear(catch: &CastHandler, user_file: &mut UserFile, wear: f32, public: bool) {
+    let broken = user_file.file.inventory.wear_gear(wear);
+    user_file.update();
+    if broken.is_empty() {
+        return;
+    }
+
+    let embed = CreateEmbed::new()
+        .title("💥 Gear Broken!")
+        .description(format!(
+            "Your **{}** broke! Starter gear will stand in until it's repaired at the `/shop`.",
+            broken.join("** and **")
+        ))
+        .color(Color::RED);
+    if let Err(e) = send_result(catch, public, embed, None).await {
+        nay!({user: catch.user, guild: catch.log_guild()}, "Failed to send cast response message: {}", e);
+    }
+}
+
+/// The embed for the current round of a fight
+fn fight_embed(fight: &Fight, config: &Config, tip: &str, sonar: Option<&str>) -> CreateEmbed {
+    let embed = CreateEmbed::new()
+        .title("🎣 FISH ON! 🎣")
+        .description(format!(
+            "**Round {}/{}:** {}\nPick your move, you have **{:.0}s**. Doing nothing holds the line.",
+            fight.round + 1,
+            config.fight.max_rounds,
+            fight.next_move.describe(),
+            config.fight.round_time
+        ))
+        .field("🧵 Line Tension", format!("`{}`", meter(fight.tension, MAX_TENSION)), false)
+        .field("💪 Fish Stamina", format!("`{}`", meter(fight.stamina, MAX_STAMINA)), false)
+        .thumbnail("attachment://FishingRod.png")
+        .color(Color::ORANGE)
+        .footer(CreateEmbedFooter::new(tip));
+    match sonar {
+        Some(reading) => embed.field("📡 Sonar", reading, false),
+        None => embed,
+    }
+}
+
+/// The Reel / Ease Off / Hold buttons, pressed buttons are left to the catch's collector
+fn fight_buttons() -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(
+        FightAction::ALL
+            .iter()
+            .map(|action| {
+                let style = match action {
+                    FightAction::Reel => ButtonStyle::Primary,
+                    FightAction::EaseOff | FightAction::Hold => ButtonStyle::Secondary,
+                };
+                CreateButton::new(component_id("fight", &[&action.id()])).label(action.label()).style(style)
+            })
+            .collect(),
+    )]
+}
+
+/// Fight a hooked fish one round at a time, until it's landed or lost
+async fn run_fight(
+    catch: &CastHandler,
+    fish: &Fish,
+    loadout: &RodLoadout,
+    sonar: Option<&str>,
+    public: bool,
+    config: &Config,
+    rng: &mut (impl Rng + Send),
+) -> Result<FightOutcome, ReelError> {
+    let mut fight = Fight::new(fish, loadout, &config.fight, rng);
+    let tip = random_tip(rng);
+    let round_time = Duration::from_secs_f32(config.fight.round_time.max(1.0));
+
+    let message = send_result_with_buttons(
+        catch,
+        public,
+        fight_embed(&fight, config, &tip, sonar),
+        Some("./assets/FishingRod.png"),
+        fight_buttons(),
+    ).await?;
+
+    loop {
+        let action = catch.messenger
+            .await_button(message.id(), catch.user, round_time)
+            .await
+            .and_then(|id| id.split_once(ID_SEPARATOR).and_then(|(_, action)| FightAction::from_id(action)))
+            .unwrap_or(FightAction::Hold);
+
+        if let Some(outcome) = fight.step(action, rng) {
+            if let Err(e) = edit_result(catch, message, fight_embed(&fight, config, &tip, sonar), vec![]).await {
+                hey!({user: catch.user, guild: catch.log_guild()}, "Failed to remove a fight's buttons: {}", e);
+            }
+            return Ok(outcome);
+        }
+
+        edit_result(catch, message, fight_embed(&fight, config, &tip, sonar), fight_buttons()).await?;
+    }
+}
+
+/// Show a QTE's stages to the angler and wait for their answers.
+/// The outcome is Err with how the QTE was lost, the whole result is Err if the QTE couldn't be sent.
+async fn run_qte(
+    catch: &CastHandler,
+    qte: &dyn Qte,
+    sonar: Option<&str>,
+    public: bool,
+    config: &Config,
+    rng: &mut (impl Rng + Send),
+) -> Result<Result<(), QteMiss>, ReelError> {
+    let tip = random_tip(rng);
+    let mut message = None;
+
+    for (index, stage) in qte.stages().iter().enumerate() {
+        let embed = qte_embed(qte, index, &tip, sonar);
+        let live = stage.live_after.is_none();
+
+        // the first stage is sent, later stages replace it
+        let sent = match message {
+            None => send_result_with_buttons(catch, public, embed.clone(), Some("./assets/FishingRod.png"), qte_buttons(stage, live)).await?,
+            Some(sent) => {
+                edit_result(catch, sent, embed.clone(), qte_buttons(stage, live)).await?;
+                sent
+            }
         };
-        let interaction = interaction.await_component_interaction(&data.ctx)
-            .author_id(user_id)
-            .timeout(Duration::from_secs(catch_time as u64))
-            .await;
-
-        if let Some(interaction) = interaction {
-            if interaction.data.custom_id == button_id {
-                // User clicked cancel
-                if config.general.log_cast_data {
-                    say!("{} clicked the cancel button!", data.sender.display_name());
-                }
-                canceled.store(true, Ordering::Relaxed);
+        message = Some(sent);
 
-                // remove the user from the fishing set
-                {
-                    let mut fishing_set = users_fishing.lock().await;
-                    fishing_set.remove(&user_id);
-                }
+        let mut outcome = Ok(());
 
-                let update_embed = CreateEmbed::new()
-                    .title("🛑 Cast Canceled")
-                    .description("You reeled in your line early.")
-                    .color(0x95a5a6); // Grey color
-
-                let _ = interaction.create_response(&data.ctx,
-                    CreateInteractionResponse::UpdateMessage(
-                        CreateInteractionResponseMessage::new()
-                            .embed(update_embed)
-                            .components(vec![]) // Remove buttons
-                    )
-                ).await;
+        // pressing the button before the stage goes live loses the fish
+        if let Some(delay) = stage.live_after {
+            if catch.messenger.await_button(sent.id(), catch.user, Duration::from_secs_f32(delay)).await.is_some() {
+                outcome = Err(QteMiss::Early);
+            } else {
+                edit_result(catch, sent, embed.clone(), qte_buttons(stage, true)).await?;
             }
         }
 
-        Ok(())
+        if outcome.is_ok() {
+            let sent_at = Instant::now();
+            let timeout = Duration::from_secs_f32(stage.seconds);
+            let reply = match stage.input {
+                QteInput::Text => catch.messenger.await_reply(sent.channel(catch), catch.user, timeout).await,
+                QteInput::Buttons(_) => catch.messenger
+                    .await_button(sent.id(), catch.user, timeout)
+                    .await
+                    .map(|id| id.split_once(ID_SEPARATOR).map_or(id.clone(), |(_, button)| button.to_string())),
+            };
+
+            outcome = match reply {
+                Some(reply) => {
+                    // watch for replies that are too fast or too consistent to be a person
+                    if qte.tracks_reply_times()
+                        && let Some(reason) = QteStats::record(&catch.user, sent_at.elapsed().as_secs_f32(), &config.anti_macro)
+                    {
+                        hey!({user: catch.user, guild: catch.log_guild()}, "{} was flagged for possible macro use: {}", catch.user_name, reason);
+                    }
+
+                    if qte.is_answer(index, &reply) { Ok(()) } else { Err(QteMiss::Wrong(reply)) }
+                }
+                None => Err(QteMiss::Timeout),
+            };
+        }
+
+        // the buttons are removed once the QTE is over
+        let last = index + 1 == qte.stages().len();
+        if (outcome.is_err() || last)
+            && matches!(stage.input, QteInput::Buttons(_))
+            && let Err(e) = edit_result(catch, sent, embed, vec![]).await
+        {
+            hey!({user: catch.user, guild: catch.log_guild()}, "Failed to remove a QTE's buttons: {}", e);
+        }
+
+        if outcome.is_err() {
+            return Ok(outcome);
+        }
     }
+
+    Ok(Ok(()))
 }
 
-pub async fn catch(catch: CastHandler, long_cast: bool) {
+pub async fn catch(catch: CastHandler, rng: &mut (impl Rng + Send)) {
     // check if the cast was canceled during that time
     if catch.canceled.load(Ordering::Relaxed) {
         // cast was canceled, do not send a message or remove the user from the fishing set
         return;
     }
 
-    let _ = catch.interaction.edit_response(&catch.ctx,
-                                            EditInteractionResponse::new()
-                                                .components(vec![]), // Empty components vector removes buttons
+    // take the cast out of the store, if it's already gone it was resolved somewhere else
+    // (or the store lost it), either way the player mustn't be left fishing
+    if !CastStore::claim(&catch.user, &catch.interaction_token) {
+        release_cast(&catch).await;
+        return;
+    }
+
+    let _ = catch.messenger.edit_response(&catch.interaction_token,
+        EditInteractionResponse::new()
+            .components(vec![]), // Empty components vector removes buttons
     ).await;
 
-    let mut user_file = UserFile::read(&catch.user);
+    // the guild's settings may have changed since the cast, so they are resolved again here
+    let settings = EffectiveSettings::for_guild(catch.guild.as_ref()).unwrap_or_else(|e| {
+        hey!({user: catch.user, guild: catch.log_guild()}, "Failed to load guild settings for a catch, using the global config: {}", e);
+        EffectiveSettings::global()
+    });
+
+    let mut user_file = match UserFile::read_in(&catch.user, settings.profile_guild) {
+        Ok(user_file) => user_file,
+        Err(e) => {
+            // the fish is lost, but the player can cast again and is told why
+            release_cast(&catch).await;
+            profile_read_failed(&catch, e).await;
+            return;
+        }
+    };
 
-    let config = Config::load();
+    let config = &settings.config;
+    // players can keep their catches to themselves with /privacy
+    let private = user_file.file.private_catches;
+    let public = settings.features.public_results && !private;
+    let darryl = settings.features.darryl_messages;
 
     // Use up the user's bait if they had any (Inventory Update)
     if let Some(index) = user_file.file.inventory.selected_bait {
@@ -465,39 +1153,22 @@ pub async fn catch(catch: CastHandler, long_cast: bool) {
         };
 
         if should_remove {
-            // 20% chance to keep bait
-            let keep_bait = rand::rng().random_range(0..20);
-            if keep_bait != 7 {
-                // Unequip since the item is gone
-                user_file.file.inventory.selected_bait = None;
-
-                // if the user has the same bait in their bucket, select it again
-                // determine same bait by name AND description, not just name
-                if user_file.file.autobait {
-                    let bait_bucket = &user_file.file.inventory.bait_bucket;
-                    let current_bait = bait_bucket.get(index).unwrap();
-
-                    if let Some(new_index) = bait_bucket.baits.iter().position(|b| {
-                        b.name == current_bait.name &&
-                        b.description == current_bait.description
-                    }) {
-                        user_file.file.inventory.selected_bait = Some(new_index);
-                    }
-                }
-
-                // Remove the item physically from the bucket
-                user_file.file.inventory.bait_bucket.remove_index(index);
-
+            if !keeps_bait(rng) {
+                // with AutoBait the rest of the stack stays on the line
+                let autobait = user_file.file.autobait;
+                user_file.file.inventory.use_selected_bait(autobait);
                 user_file.update();
             }
         }
     }
 
-    // remove the user from the casting set
-    {
-        let mut fishing_set = catch.users_fishing.lock().await;
-        fishing_set.remove(&catch.user);
-    }
+    release_cast(&catch).await;
+
+    // Get snapshot of loadout for calculations
+    let loadout = user_file.file.inventory.get_loadout();
+    let wear = &config.durability;
+    let gadgets = GadgetCatalog::load().effects(&user_file.file.inventory.gadgets);
+    let luck = (gadgets.luck + if catch.precise_cast { PRECISE_CAST_LUCK } else { 0.0 }).min(1.0);
 
     // TODO: Junk, Trash, Treasure and etc here because it is a guaranteed catch
 
@@ -508,51 +1179,35 @@ pub async fn catch(catch: CastHandler, long_cast: bool) {
             .description("You felt your line go taught but nothing came up. Better luck next time!".to_string())
             .thumbnail("attachment://FishingRod.png")
             .color(0x3498db)
-            .footer(CreateEmbedFooter::new(format!("{}", random_tip())));
+            .footer(CreateEmbedFooter::new(random_tip(rng)));
 
-        let mut message = CreateMessage::new()
-            .content(format!("{}", catch.user.mention()))
-            .embed(embed);
-
-        let attachment = CreateAttachment::path("./assets/FishingRod.png").await;
-
-        // use the attachment if found
-        if let Ok(file) = attachment {
-            message = message.add_file(file);
-        }
-
-        if let Err(e) = catch.channel.send_message(&catch.ctx.http, message).await {
-            nay!("Failed to send cast response message: {}", e);
-            return;
+        if let Err(e) = send_result(&catch, public, embed, Some("./assets/FishingRod.png")).await {
+            nay!({user: catch.user, guild: catch.log_guild()}, "Failed to send cast response message: {}", e);
         }
+        wear_gear(&catch, &mut user_file, loadout.cast_wear(None, false, wear), public).await;
         return;
     };
 
-    // Get snapshot of loadout for calculations
-    let loadout = user_file.file.inventory.get_loadout();
-
     // Catch chance didn't succeed
-    let mut caught = fish.try_hook(&loadout);
+    let mut caught = fish.try_hook(&loadout, config, rng);
+    // lucky charms and precise casts give the fish a second chance to bite
+    if !caught && luck > 0.0 && rng.random::<f32>() < luck {
+        caught = true;
+    }
 
     let mut force_qte = false;
 
-    if long_cast && !caught {
-        let c = rand::rng().random_range(0..100);
-        if c < 20 {
-            force_qte = true;
-            caught = true;
-        }
+    if catch.long_cast && !caught && long_cast_hooks(rng) {
+        force_qte = true;
+        caught = true;
     }
 
     if config.general.log_cast_data {
-        let base = config.fishing.base_catch_chance;
-        let sensitivity = loadout.catch_chance_multiplier();
-        let fight_chance = fish.category.fight_multiplier();
-        let chance = (base + sensitivity) / fight_chance;
-        say!("{}'s catch chance was {}%", catch.interaction.user.display_name(), (chance * 100.0) as u32);
+        let chance = fish.hook_chance(&loadout, config);
+        say!({user: catch.user, guild: catch.log_guild()}, "{}'s catch chance was {}%", catch.user_name, (chance * 100.0) as u32);
     }
     if !caught {
-        let lost = missed_fish(&fish, &user_file);
+        let lost = missed_fish(fish, gadgets.underwater_camera, darryl, rng);
 
         let embed = CreateEmbed::new()
             .title("💨 The fish got away!")
@@ -560,150 +1215,111 @@ pub async fn catch(catch: CastHandler, long_cast: bool) {
             .fields(lost)
             .thumbnail("attachment://FishingRod.png")
             .color(0x3498db)
-            .footer(CreateEmbedFooter::new(format!("{}", random_tip())));
-
-        let mut message = CreateMessage::new()
-            .content(format!("{}", catch.user.mention()))
-            .embed(embed);
+            .footer(CreateEmbedFooter::new(random_tip(rng)));
 
-        let attachment = CreateAttachment::path("./assets/FishingRod.png").await;
-
-        // use the attachment if found
-        if let Ok(file) = attachment {
-            message = message.add_file(file);
-        }
-
-        if let Err(e) = catch.channel.send_message(&catch.ctx.http, message).await {
-            nay!("Failed to send cast response message: {}", e);
-            return;
+        if let Err(e) = send_result(&catch, public, embed, Some("./assets/FishingRod.png")).await {
+            nay!({user: catch.user, guild: catch.log_guild()}, "Failed to send cast response message: {}", e);
         }
+        wear_gear(&catch, &mut user_file, loadout.cast_wear(None, false, wear), public).await;
         return;
     }
 
-    // Weight Check
-    // calculate the total weight load on the line (fish weight + sinker weight)
-    let weight_load = fish.weight + loadout.sinker.weight;
-    let max_weight = loadout.total_strength();
+    let sonar = sonar_reading(fish, &gadgets);
 
-    if weight_load > max_weight {
+    // Weight Check
+    // the fish is heavier than the loadout can handle (fish weight + sinker weight)
+    // guilds with QTEs turned off land heavy fish without one
+    let qte_time_limit = loadout.qte_time_limit(fish, config).filter(|_| settings.features.qte);
+    if let Some(time_limit_secs) = qte_time_limit {
         // Quick Time Event (QTE)
-        // 5 digit numeric code
-        let code = rand::rng().random_range(10000..99999).to_string();
-
-        // add spaces between characters to prevent copy + paste
-        let code_display = code.chars().map(|c| format!("{}", c)).collect::<Vec<String>>().join(" ");
-
-        // Calculate time limit
-        let base_time = config.fishing.base_qte_time;
-        let min_time = config.fishing.min_qte_time;
-
-        let ratio = weight_load / max_weight;
-        let time_limit_secs = (base_time / ratio).max(min_time);
+        // the kind of QTE depends on the fish, and how far it is over what the loadout can take
+        let qte = choose_qte(fish, loadout.qte_excess(fish), time_limit_secs, config, rng);
 
-        let embed = CreateEmbed::new()
-            .title("⚠️ LINE TENSION CRITICAL! ⚠️")
-            .description(format!("The fish is too heavy! Type the code below in **{:.1}s** to save the line!", time_limit_secs))
-            .thumbnail("attachment://FishingRod.png")
-            .field("⌨️ Type The code!", format!("`{}`", code_display), false)
-            .color(Color::RED)
-            .footer(CreateEmbedFooter::new(format!("{}", random_tip())));
-
-        let mut message = CreateMessage::new()
-            .content(format!("{}", catch.user.mention()))
-            .embed(embed);
-
-        let attachment = CreateAttachment::path("./assets/FishingRod.png").await;
-
-        // use the attachment if found
-        if let Ok(file) = attachment {
-            message = message.add_file(file);
-        }
-
-        if let Err(e) = catch.channel.send_message(&catch.ctx.http, message).await {
-            nay!("Failed to send cast response message: {}", e);
+        let outcome = match run_qte(&catch, qte.as_ref(), sonar.as_deref(), public, config, rng).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                nay!({user: catch.user, guild: catch.log_guild()}, "Failed to send cast response message: {}", e);
+                return;
+            }
+        };
+        if !reload_profile(&catch, &mut user_file).await {
             return;
         }
 
-        // wait for the message
-        let collector = catch.channel.await_reply(&catch.ctx)
-            .author_id(catch.user)
-            .timeout(Duration::from_secs_f32(time_limit_secs));
-
-        if let Some(msg) = collector.await {
-            // User Replied
-            let user_input = msg.content.replace(" ", "");
-
-            // be nice and ignore case
-            if user_input.eq_ignore_ascii_case(&code) {
+        match outcome {
+            Ok(()) => {
                 // SUCCESS
+                let (title, description) = qte.success();
                 let embed = CreateEmbed::new()
-                    .title("✅ Line Stabilized!")
-                    .description("You managed to reel it in safely.")
+                    .title(title)
+                    .description(description)
                     .color(Color::DARK_GREEN);
 
-                let message = CreateMessage::new()
-                    .content(format!("{}", catch.user.mention()))
-                    .embed(embed);
-
-                if let Err(e) = catch.channel.send_message(&catch.ctx.http, message).await {
-                    nay!("Failed to send cast response message: {}", e);
+                if let Err(e) = send_result(&catch, public, embed, None).await {
+                    nay!({user: catch.user, guild: catch.log_guild()}, "Failed to send cast response message: {}", e);
                 }
 
                 // don't return, proceed to successful catch handling
-            } else {
+            }
+            Err(miss) => {
                 // FAILURE
 
-                // remove any bait the user may have (Snap Logic)
-                if let Some(index) = user_file.file.inventory.selected_bait {
-                    user_file.file.inventory.bait_bucket.remove_index(index);
-                    user_file.file.inventory.selected_bait = None;
-                    user_file.update();
-                }
+                snap_bait(&mut user_file);
 
-                let lost = missed_fish(&fish, &user_file);
+                let lost = missed_fish(fish, gadgets.underwater_camera, darryl, rng);
 
+                let (title, description) = qte.failure(&miss);
                 let embed = CreateEmbed::new()
-                    .title("💥 SNAP!")
-                    .description(format!("You typed the wrong code (`{}`).",
-                                         user_input))
-
+                    .title(title)
+                    .description(description)
                     .fields(lost)
                     .color(Color::RED);
 
-                let message = CreateMessage::new()
-                    .content(format!("{}", catch.user.mention()))
-                    .embed(embed);
-
-                if let Err(e) = catch.channel.send_message(&catch.ctx.http, message).await {
-                    nay!("Failed to send cast response message: {}", e);
+                if let Err(e) = send_result(&catch, public, embed, None).await {
+                    nay!({user: catch.user, guild: catch.log_guild()}, "Failed to send cast response message: {}", e);
                 }
+                wear_gear(&catch, &mut user_file, loadout.cast_wear(Some(fish), true, wear), public).await;
                 return;
             }
-        } else {
-            // TIMEOUT
+        }
+    }
 
-            // remove any bait the user may have (Snap Logic)
-            if let Some(index) = user_file.file.inventory.selected_bait {
-                user_file.file.inventory.bait_bucket.remove_index(index);
-                user_file.file.inventory.selected_bait = None;
-                user_file.update();
+    // big fish the loadout can hold still put up a fight
+    if settings.features.fights && Fight::should_fight(fish, &loadout, &config.fight) {
+        let outcome = match run_fight(&catch, fish, &loadout, sonar.as_deref(), public, config, rng).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                nay!({user: catch.user, guild: catch.log_guild()}, "Failed to send cast response message: {}", e);
+                return;
             }
+        };
+        if !reload_profile(&catch, &mut user_file).await {
+            return;
+        }
 
-            let lost = missed_fish(&fish, &user_file);
+        let lost_to = match outcome {
+            FightOutcome::Landed => None,
+            FightOutcome::Snapped => {
+                snap_bait(&mut user_file);
+                Some(("💥 SNAP!", "You pulled too hard and the line snapped!"))
+            }
+            FightOutcome::Escaped => Some(("💨 The fish got away!", "You let the line go slack and the fish threw the hook.")),
+            FightOutcome::Outlasted => Some(("💨 The fish got away!", "The fish outlasted you and swam off.")),
+        };
 
+        if let Some((title, description)) = lost_to {
+            let lost = missed_fish(fish, gadgets.underwater_camera, darryl, rng);
             let embed = CreateEmbed::new()
-                .title("💥 SNAP!")
-                .description("You weren't fast enough and your line snapped!".to_string())
+                .title(title)
+                .description(description)
                 .fields(lost)
                 .color(Color::RED);
 
-            let message = CreateMessage::new()
-                .content(format!("{}", catch.user.mention()))
-                .embed(embed);
-
-            if let Err(e) = catch.channel.send_message(&catch.ctx.http, message).await {
-                nay!("Failed to send cast response message: {}", e);
+            if let Err(e) = send_result(&catch, public, embed, None).await {
+                nay!({user: catch.user, guild: catch.log_guild()}, "Failed to send cast response message: {}", e);
             }
+            let snapped = outcome == FightOutcome::Snapped;
+            wear_gear(&catch, &mut user_file, loadout.cast_wear(Some(fish), snapped, wear), public).await;
             return;
         }
     }
@@ -717,6 +1333,8 @@ pub async fn catch(catch: CastHandler, long_cast: bool) {
         user_file.file.caught_fish.push(fish.fish_type.name.clone());
     }
     user_file.file.total_catches += 1;
+    // kept for a while so it can be mounted in the aquarium
+    user_file.file.aquarium.record_catch(Trophy::from_catch(fish, Utc::now().timestamp_millis()));
     user_file.update();
 
     let embed = CreateEmbed::new()
@@ -732,18 +1350,587 @@ pub async fn catch(catch: CastHandler, long_cast: bool) {
             ]
         )
         .color(Color::GOLD)
-        .thumbnail("attachment://rod_with_fish.png");
+        .thumbnail("attachment://rod_with_fish.png")
+        .footer(CreateEmbedFooter::new("🐠 Show it off with /trophy mount 1"));
 
-    let mut message = CreateMessage::new()
-        .content(format!("{}", catch.user.mention()))
-        .embed(embed);
+    if let Err(e) = send_result(&catch, public, embed, Some("./assets/rod_with_fish.png")).await {
+        nay!({user: catch.user, guild: catch.log_guild()}, "Failed to send cast response message: {}", e);
+    }
+    wear_gear(&catch, &mut user_file, loadout.cast_wear(Some(fish), false, wear), public).await;
 
-    if let Ok(file) = CreateAttachment::path("./assets/rod_with_fish.png").await {
-        message = message.add_file(file);
+    // let the rest of the server know about rare and record-breaking catches
+    // private catches still count for the records, they just aren't announced
+    announce_catch(&catch, fish, &settings, !private).await;
+}
+
+/// Keep the guild's record for the fish's species, and post the catch in the guild's hall of fame
+/// if it's Legendary or Mythical, or broke the record (unless `public` is off)
+async fn announce_catch(catch: &CastHandler, fish: &Fish, settings: &EffectiveSettings, public: bool) {
+    let Some(guild) = catch.guild else {
+        return;
+    };
+
+    let mut guild = match GuildSettings::get(&guild) {
+        Ok(guild) => guild,
+        Err(e) => {
+            hey!({user: catch.user, guild: catch.log_guild()}, "Failed to read a guild's catch records: {}", e);
+            return;
+        }
+    };
+
+    // the guild file is only written when the catch is a record, most catches leave it alone
+    let rare = matches!(fish.fish_type.rarity, FishRarity::Legendary | FishRarity::Mythical);
+    let record = guild.file.is_record(&fish.fish_type.name, fish.weight);
+    if !rare && !record {
+        return;
+    }
+
+    let mut broken = None;
+    if record {
+        let record = CatchRecord {
+            user_id: catch.user.get(),
+            user_name: catch.user_name.clone(),
+            weight: fish.weight,
+        };
+        broken = guild.edit(|file| file.record_catch(&fish.fish_type.name, record)).unwrap_or_else(|e| {
+            hey!({user: catch.user, guild: catch.log_guild()}, "Failed to keep a guild's catch record: {}", e);
+            None
+        });
     }
 
-    if let Err(e) = catch.channel.send_message(&catch.ctx, message).await {
-        nay!("Failed to send cast response message: {}", e);
+    let Some(channel) = settings.announcement_channel.filter(|_| public && (rare || broken.is_some())) else {
         return;
+    };
+
+    let title = if rare {
+        format!("🏆 {} Catch! 🏆", fish.fish_type.rarity)
+    } else {
+        "📈 New Server Record! 📈".to_string()
+    };
+    let mut description = format!("{} just landed a **{}** weighing {:.2} lbs!", catch.user.mention(), fish.fish_type.name, fish.weight);
+    if let Some(old) = &broken {
+        description.push_str(&format!("\nThat beats the server record of {:.2} lbs set by {}.", old.weight, old.user_name));
     }
-}
\ No newline at end of file
+
+    let mut author = CreateEmbedAuthor::new(&catch.user_name);
+    let mut embed = CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .fields(vec![
+            ("📏 Size", format!("{:.2} in", fish.size), true),
+            ("⚖️ Weight", format!("{:.2} lbs", fish.weight), true),
+            ("💲 Value", format!("{}", fish.value), true),
+            ("🌊 Depth", format!("{:.0} ft", fish.depth), true),
+        ])
+        .color(Color::GOLD)
+        .timestamp(Timestamp::now());
+    if let Some(avatar) = &catch.avatar_url {
+        author = author.icon_url(avatar);
+        embed = embed.thumbnail(avatar);
+    }
+
+    if let Err(e) = catch.messenger.send_message(channel, CreateMessage::new().embed(embed.author(author))).await {
+        nay!({user: catch.user, guild: catch.log_guild()}, "Failed to send catch announcement: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_management::config::Logging;
+    use crate::data_management::data_path;
+    use crate::data_management::monetary::MonetaryAmount;
+    use crate::logging::{LogLevel, find_error_code};
+    use crate::fishing::fish_data::fish::FishCategory;
+    use crate::fishing::qte::QteFormat;
+    use crate::fishing::rod_data::bait::{Bait, BaitPotency};
+    use crate::testing::{FakeMessenger, FakeReply, TestDataDir, test_fish, test_pond};
+
+    const USER: UserId = UserId::new(42);
+    const TOKEN: &str = "test-token";
+
+    /// a config where every fish is hooked, so outcomes only depend on the QTE
+    fn always_hook_config() -> Config {
+        let mut config = Config::default();
+        config.fishing.base_catch_chance = 1.0;
+        config.anti_macro.qte_formats = vec![QteFormat::Digits];
+        config
+    }
+
+    /// register a cast the same way /cast does and build its handler
+    fn start_cast(messenger: &Arc<FakeMessenger>, fish: Option<Fish>) -> CastHandler {
+        CastStore::add(PendingCast {
+            user_id: USER.get(),
+            user_name: "tester".to_string(),
+            channel_id: 1,
+            fish: fish.clone(),
+            ends_at: 0,
+            long_cast: false,
+            interaction_token: TOKEN.to_string(),
+            seed: 1,
+            guild_id: None,
+            precise_cast: false,
+            avatar_url: None,
+        });
+
+        let canceled = Arc::new(AtomicBool::new(false));
+        let users_fishing = Arc::new(Mutex::new(HashMap::from([(USER, canceled.clone())])));
+
+        CastHandler {
+            messenger: messenger.clone(),
+            user: USER,
+            channel: ChannelId::new(1),
+            fish,
+            users_fishing,
+            canceled,
+            interaction_token: TOKEN.to_string(),
+            user_name: "tester".to_string(),
+            long_cast: false,
+            guild: None,
+            precise_cast: false,
+            avatar_url: None,
+        }
+    }
+
+    /// type back whatever code the QTE embed asked for
+    fn type_the_code() -> FakeReply {
+        FakeReply::FromLastMessage(Box::new(|msg| {
+            msg["embeds"][0]["fields"][0]["value"]
+                .as_str()
+                .unwrap_or_default()
+                .chars()
+                .filter(char::is_ascii_digit)
+                .collect()
+        }))
+    }
+
+    #[tokio::test]
+    async fn roll_cast_picks_from_the_pond() {
+        let _dir = TestDataDir::new();
+        let config = always_hook_config();
+
+        let rolled = roll_cast(&RodLoadout::default(), None, &test_pond(), &config, &mut cast_rng(7, ROLL_STREAM)).ok().unwrap();
+
+        assert_eq!(rolled.fish.unwrap().fish_type.name, "Test Minnow");
+        assert!(rolled.catch_time >= config.fishing.min_cast_wait);
+        assert!(rolled.catch_time <= config.fishing.max_cast_wait);
+    }
+
+    #[tokio::test]
+    async fn same_seed_rolls_the_same_cast() {
+        let _dir = TestDataDir::new();
+        let config = always_hook_config();
+        let roll = |seed| roll_cast(&RodLoadout::default(), None, &test_pond(), &config, &mut cast_rng(seed, ROLL_STREAM)).ok().unwrap();
+
+        let (first, again) = (roll(1234), roll(1234));
+        assert_eq!(first.depth, again.depth);
+        assert_eq!(first.catch_time, again.catch_time);
+        assert_eq!(first.fish.unwrap().weight, again.fish.unwrap().weight);
+    }
+
+    #[test]
+    fn aimed_casts_land_near_the_target() {
+        // a 100-400 ft sinker cast from the shore reaches 100-200 ft
+        let mut loadout = RodLoadout::default();
+        loadout.sinker.depth_range = crate::fishing::Attribute { min: 100.0, max: 400.0, average: 250.0 };
+        assert_eq!(loadout.depth_reach(), (100.0, 200.0));
+
+        assert!(aim_target(&loadout, Some(300.0), None).is_err());
+        assert!(aim_target(&loadout, None, Some("Shallow")).is_err());
+        assert_eq!(aim_target(&loadout, None, Some("MidWater")), Ok(Some(150.0)));
+
+        let mut rng = cast_rng(3, ROLL_STREAM);
+        for _ in 0..20 {
+            let depth = loadout.generate_depth(Some(120.0), &mut rng).unwrap();
+            assert!((100.0..=120.0 + loadout.sinker.spread()).contains(&depth), "cast to {}", depth);
+        }
+        assert!(loadout.is_precise(120.0, 125.0));
+        assert!(!loadout.is_precise(120.0, 190.0));
+        assert_eq!(loadout.near_bottom(200.0), 1.0);
+    }
+
+    #[tokio::test]
+    async fn catch_pays_out_the_fish() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+        let messenger = Arc::new(FakeMessenger::new());
+        let cast = start_cast(&messenger, Some(test_fish(0.1, 25.0)));
+        let users_fishing = cast.users_fishing.clone();
+
+        catch(cast, &mut StdRng::seed_from_u64(1)).await;
+
+        let user = UserFile::read(&USER).unwrap();
+        assert_eq!(user.file.balance.get(), 125.0);
+        assert_eq!(user.file.total_catches, 1);
+        assert_eq!(user.file.caught_fish, vec!["Test Minnow".to_string()]);
+        assert_eq!(user.file.aquarium.recent[0].weight, 0.1);
+        assert_eq!(messenger.embed_titles(), vec!["✨ Fish Caught! ✨"]);
+
+        // the cancel button is removed and the cast is cleaned up
+        assert_eq!(messenger.edits.lock().unwrap()[0].0, TOKEN);
+        assert!(CastStore::load().is_empty());
+        assert!(users_fishing.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cast_command_runs_through_to_the_catch() {
+        let mut config = always_hook_config();
+        config.fishing.min_cast_wait = 0.0;
+        config.fishing.max_cast_wait = 0.0;
+        let _dir = TestDataDir::new().with_config(&config);
+        test_pond().save().unwrap();
+        let messenger = Arc::new(FakeMessenger::new());
+        messenger.reply(type_the_code());
+
+        let users_fishing = Arc::new(Mutex::new(HashMap::new()));
+        let request = CastRequest {
+            user: USER,
+            user_name: "tester".to_string(),
+            avatar_url: None,
+            channel: ChannelId::new(1),
+            guild: None,
+            interaction: InteractionId::new(1),
+            interaction_token: TOKEN.to_string(),
+            settings: EffectiveSettings::global(),
+            users_fishing: users_fishing.clone(),
+        };
+        assert!(cast_line(messenger.clone(), request, None, None, None, 1).await.is_ok());
+
+        // the player sees their cast with the cancel button
+        {
+            let responses = messenger.responses.lock().unwrap();
+            assert_eq!(responses[0].0, TOKEN);
+            assert!(responses[0].1["data"]["embeds"][0]["title"].as_str().unwrap().starts_with("🎣 You cast your"));
+        }
+
+        // wait for the scheduled catch
+        for _ in 0..200 {
+            if users_fishing.lock().await.is_empty() && !messenger.embed_titles().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(messenger.embed_titles().last().map(String::as_str), Some("✨ Fish Caught! ✨"));
+        assert_eq!(UserFile::read(&USER).unwrap().file.total_catches, 1);
+        assert!(CastStore::load().is_empty());
+        assert!(users_fishing.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn empty_cast_pays_nothing() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+        let messenger = Arc::new(FakeMessenger::new());
+
+        catch(start_cast(&messenger, None), &mut StdRng::seed_from_u64(1)).await;
+
+        assert_eq!(UserFile::read(&USER).unwrap().file.balance.get(), 100.0);
+        assert_eq!(messenger.embed_titles(), vec!["🍃 Nothing came up!"]);
+    }
+
+    #[tokio::test]
+    async fn typing_the_qte_code_lands_a_heavy_fish() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+        let messenger = Arc::new(FakeMessenger::new());
+        messenger.reply(type_the_code());
+
+        catch(start_cast(&messenger, Some(test_fish(10_000.0, 50.0))), &mut StdRng::seed_from_u64(1)).await;
+
+        assert_eq!(
+            messenger.embed_titles(),
+            vec!["⚠️ LINE TENSION CRITICAL! ⚠️", "✅ Line Stabilized!", "✨ Fish Caught! ✨"]
+        );
+        assert_eq!(UserFile::read(&USER).unwrap().file.balance.get(), 150.0);
+    }
+
+    #[tokio::test]
+    async fn wrong_qte_code_snaps_the_line_and_the_bait() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+
+        let mut user = UserFile::read(&USER).unwrap();
+        user.file.inventory.bait_bucket.add(Bait::generate(BaitPotency::Low, false, &mut rand::rng()));
+        user.file.inventory.selected_bait = Some(0);
+        user.update();
+
+        let messenger = Arc::new(FakeMessenger::new());
+        messenger.reply(FakeReply::Text("not the code".to_string()));
+
+        catch(start_cast(&messenger, Some(test_fish(10_000.0, 50.0))), &mut StdRng::seed_from_u64(1)).await;
+
+        let user = UserFile::read(&USER).unwrap();
+        assert_eq!(messenger.embed_titles().last().unwrap(), "💥 SNAP!");
+        assert_eq!(user.file.balance.get(), 100.0);
+        assert!(user.file.inventory.bait_bucket.is_empty());
+        assert_eq!(user.file.inventory.selected_bait, None);
+    }
+
+    #[tokio::test]
+    async fn qte_timeout_snaps_the_line() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+        let messenger = Arc::new(FakeMessenger::new());
+        messenger.reply(FakeReply::Timeout);
+
+        catch(start_cast(&messenger, Some(test_fish(10_000.0, 50.0))), &mut StdRng::seed_from_u64(1)).await;
+
+        assert_eq!(messenger.embed_titles().last().unwrap(), "💥 SNAP!");
+        assert_eq!(UserFile::read(&USER).unwrap().file.total_catches, 0);
+    }
+
+    #[tokio::test]
+    async fn a_cast_is_only_caught_once() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+        let messenger = Arc::new(FakeMessenger::new());
+
+        let first = start_cast(&messenger, Some(test_fish(0.1, 25.0)));
+        // e.g. the catch task and a resumed copy of the same cast after a reconnect
+        let second = CastHandler {
+            messenger: messenger.clone(),
+            user: USER,
+            channel: ChannelId::new(1),
+            fish: first.fish.clone(),
+            users_fishing: first.users_fishing.clone(),
+            canceled: Arc::new(AtomicBool::new(false)),
+            interaction_token: TOKEN.to_string(),
+            user_name: "tester".to_string(),
+            long_cast: false,
+            guild: None,
+            precise_cast: false,
+            avatar_url: None,
+        };
+
+        catch(first, &mut StdRng::seed_from_u64(1)).await;
+        catch(second, &mut StdRng::seed_from_u64(1)).await;
+
+        assert_eq!(messenger.embed_titles().len(), 1);
+        assert_eq!(UserFile::read(&USER).unwrap().file.balance.get(), 125.0);
+    }
+
+    #[tokio::test]
+    async fn a_cast_missing_from_the_store_still_lets_the_player_cast_again() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+        let messenger = Arc::new(FakeMessenger::new());
+
+        let cast = start_cast(&messenger, Some(test_fish(0.1, 25.0)));
+        let users_fishing = cast.users_fishing.clone();
+        // e.g. the store was moved aside, or the cast was already claimed
+        CastStore::remove(&USER);
+        catch(cast, &mut StdRng::seed_from_u64(1)).await;
+
+        assert!(messenger.sent.lock().unwrap().is_empty());
+        assert!(users_fishing.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn catch_error_codes_can_be_looked_up_in_the_guild() {
+        let dir = TestDataDir::new().with_config(&always_hook_config());
+        let guild = GuildId::new(7);
+
+        // a profile that can't be read loses the fish, and the player is given an error code
+        for path in ["users/42.ron", "guilds/7/users/42.ron"] {
+            let path = data_path(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "not a profile").unwrap();
+        }
+
+        crate::logging::init(&Logging { level: LogLevel::Info, file_logging: true, retention_days: 0 });
+        let messenger = Arc::new(FakeMessenger::new());
+        let mut cast = start_cast(&messenger, Some(test_fish(0.1, 25.0)));
+        cast.guild = Some(guild);
+        catch(cast, &mut StdRng::seed_from_u64(1)).await;
+        crate::logging::init(&Logging { level: LogLevel::Info, file_logging: false, retention_days: 0 });
+
+        let sent = messenger.sent.lock().unwrap();
+        let content = sent[0].1["content"].as_str().unwrap();
+        let code = content.rsplit('`').nth(1).unwrap();
+
+        let logs = dir.path.join("logs");
+        assert_eq!(find_error_code(&logs, code, Some("7"), 5).len(), 1);
+        assert!(find_error_code(&logs, code, Some("8"), 5).is_empty());
+    }
+
+    #[tokio::test]
+    async fn canceled_cast_is_never_caught() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+        let messenger = Arc::new(FakeMessenger::new());
+
+        let cast = start_cast(&messenger, Some(test_fish(0.1, 25.0)));
+        cast.canceled.store(true, Ordering::Relaxed);
+        catch(cast, &mut StdRng::seed_from_u64(1)).await;
+
+        assert!(messenger.sent.lock().unwrap().is_empty());
+        assert_eq!(UserFile::read(&USER).unwrap().file.balance.get(), 100.0);
+    }
+
+    #[tokio::test]
+    async fn guild_features_change_how_a_catch_is_reported() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+        let guild = GuildId::new(7);
+        GuildSettings::get(&guild).unwrap().edit(|file| {
+            file.features.qte = false;
+            file.features.public_results = false;
+        }).unwrap();
+
+        let messenger = Arc::new(FakeMessenger::new());
+        let mut cast = start_cast(&messenger, Some(test_fish(10_000.0, 50.0)));
+        cast.guild = Some(guild);
+        catch(cast, &mut StdRng::seed_from_u64(1)).await;
+
+        // the heavy fish is landed without a QTE, and only the angler is told
+        assert!(messenger.sent.lock().unwrap().is_empty());
+        let followups = messenger.followups.lock().unwrap();
+        assert_eq!(followups.len(), 1);
+        assert_eq!(followups[0].1["embeds"][0]["title"], "✨ Fish Caught! ✨");
+        assert_eq!(UserFile::read(&USER).unwrap().file.balance.get(), 150.0);
+    }
+
+    #[tokio::test]
+    async fn record_catches_go_to_the_hall_of_fame_unless_private() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+        let guild = GuildId::new(7);
+        let hall = ChannelId::new(99);
+        GuildSettings::get(&guild).unwrap().edit(|file| file.announcement_channel = Some(hall.get())).unwrap();
+
+        let messenger = Arc::new(FakeMessenger::new());
+        let announcements = || messenger.sent.lock().unwrap().iter().filter(|(channel, _)| *channel == hall).count();
+        for weight in [0.1, 0.2] {
+            let mut cast = start_cast(&messenger, Some(test_fish(weight, 10.0)));
+            cast.guild = Some(guild);
+            catch(cast, &mut StdRng::seed_from_u64(1)).await;
+        }
+
+        // the first catch sets the record, the heavier one breaks it
+        assert_eq!(announcements(), 1);
+        assert_eq!(messenger.embed_titles().last().unwrap(), "📈 New Server Record! 📈");
+
+        let mut user = UserFile::read(&USER).unwrap();
+        user.file.private_catches = true;
+        user.update();
+        let mut cast = start_cast(&messenger, Some(test_fish(0.3, 10.0)));
+        cast.guild = Some(guild);
+        catch(cast, &mut StdRng::seed_from_u64(1)).await;
+
+        // the private catch still takes the record, it just isn't announced
+        assert_eq!(announcements(), 1);
+        assert_eq!(messenger.followups.lock().unwrap().len(), 1);
+        assert_eq!(GuildSettings::get(&guild).unwrap().file.records["Test Minnow"].weight, 0.3);
+    }
+
+    #[tokio::test]
+    async fn private_results_are_sent_by_dm_once_the_token_expires() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+        let messenger = Arc::new(FakeMessenger::new());
+        catch(start_cast(&messenger, None), &mut StdRng::seed_from_u64(1)).await;
+        let mut user = UserFile::read(&USER).unwrap();
+        user.file.private_catches = true;
+        user.update();
+
+        messenger.fail_followups.store(true, Ordering::Relaxed);
+        catch(start_cast(&messenger, Some(test_fish(0.1, 10.0))), &mut StdRng::seed_from_u64(1)).await;
+
+        // the angler is still paid, and told by DM instead of in the channel
+        assert!(messenger.followups.lock().unwrap().is_empty());
+        let dms = messenger.dms.lock().unwrap();
+        assert_eq!(dms.len(), 1);
+        assert_eq!(dms[0].0, USER);
+        assert_eq!(dms[0].1["embeds"][0]["title"], "✨ Fish Caught! ✨");
+        assert_eq!(UserFile::read(&USER).unwrap().file.balance.get(), 110.0);
+    }
+
+    #[tokio::test]
+    async fn text_qtes_sent_by_dm_are_answered_in_the_dm() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+        let mut user = UserFile::read(&USER).unwrap();
+        user.file.private_catches = true;
+        user.update();
+
+        let messenger = Arc::new(FakeMessenger::new());
+        messenger.fail_followups.store(true, Ordering::Relaxed);
+        messenger.reply(FakeReply::Text("not the code".to_string()));
+        catch(start_cast(&messenger, Some(test_fish(10_000.0, 50.0))), &mut StdRng::seed_from_u64(1)).await;
+
+        // the fake DM channel has the user's id
+        assert_eq!(*messenger.reply_channels.lock().unwrap(), vec![ChannelId::new(USER.get())]);
+    }
+
+    #[tokio::test]
+    async fn reaction_qtes_wait_for_the_button_to_turn_green() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+        let mut predator = test_fish(10_000.0, 50.0);
+        predator.category = FishCategory::Predatory;
+        // predators fight off the hook sometimes, seed 2 hooks them
+
+        // striking before the button turns green spooks the fish
+        let messenger = Arc::new(FakeMessenger::new());
+        messenger.reply(FakeReply::Button("qte:strike".to_string()));
+        catch(start_cast(&messenger, Some(predator.clone())), &mut StdRng::seed_from_u64(2)).await;
+        assert_eq!(messenger.embed_titles(), vec!["🦈 STEADY... 🦈", "💥 Too Early!"]);
+
+        // waiting for green, then striking, lands it
+        let messenger = Arc::new(FakeMessenger::new());
+        messenger.reply(FakeReply::Timeout);
+        messenger.reply(FakeReply::Button("qte:strike".to_string()));
+        catch(start_cast(&messenger, Some(predator)), &mut StdRng::seed_from_u64(2)).await;
+        assert_eq!(
+            messenger.embed_titles(),
+            vec!["🦈 STEADY... 🦈", "⚡ Perfect Strike!", "✨ Fish Caught! ✨"]
+        );
+
+        // the button is sent grey, turned green, then removed
+        let sent = messenger.sent.lock().unwrap();
+        assert_eq!(sent[0].1["components"][0]["components"][0]["style"], 2);
+        let edits = messenger.message_edits.lock().unwrap();
+        assert_eq!(edits[0].1["components"][0]["components"][0]["style"], 3);
+        assert_eq!(edits[1].1["components"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn reeling_nonstop_in_a_fight_snaps_the_line() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+
+        let mut user = UserFile::read(&USER).unwrap();
+        user.file.inventory.bait_bucket.add(Bait::generate(BaitPotency::Low, false, &mut rand::rng()));
+        user.file.inventory.selected_bait = Some(0);
+        user.update();
+
+        // an apex fish the starter loadout can hold, so it's fought instead of a QTE
+        let mut shark = test_fish(1.0, 50.0);
+        shark.category = FishCategory::Apex;
+
+        let messenger = Arc::new(FakeMessenger::new());
+        for _ in 0..20 {
+            messenger.reply(FakeReply::Button("fight:reel".to_string()));
+        }
+        catch(start_cast(&messenger, Some(shark)), &mut StdRng::seed_from_u64(2)).await;
+
+        assert_eq!(messenger.embed_titles(), vec!["🎣 FISH ON! 🎣", "💥 SNAP!"]);
+        assert!(UserFile::read(&USER).unwrap().file.inventory.bait_bucket.is_empty());
+
+        // every round updates the meters, and the last one removes the buttons
+        let edits = messenger.message_edits.lock().unwrap();
+        assert!(!edits.is_empty());
+        assert_eq!(edits.last().unwrap().1["components"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn changes_made_during_a_fight_are_kept() {
+        let _dir = TestDataDir::new().with_config(&always_hook_config());
+
+        let mut shark = test_fish(1.0, 50.0);
+        shark.category = FishCategory::Apex;
+
+        // the player sells something in the shop while the fish is still on the line
+        let messenger = Arc::new(FakeMessenger::new());
+        messenger.reply(FakeReply::FromLastMessage(Box::new(|_| {
+            let mut user = UserFile::read(&USER).unwrap();
+            user.file.balance += MonetaryAmount::new(400.0);
+            user.update();
+            "fight:reel".to_string()
+        })));
+        for _ in 0..19 {
+            messenger.reply(FakeReply::Button("fight:reel".to_string()));
+        }
+        catch(start_cast(&messenger, Some(shark)), &mut StdRng::seed_from_u64(2)).await;
+
+        assert_eq!(messenger.embed_titles(), vec!["🎣 FISH ON! 🎣", "💥 SNAP!"]);
+        assert_eq!(UserFile::read(&USER).unwrap().file.balance.get(), 500.0);
+    }
+}
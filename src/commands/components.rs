@@ -0,0 +1,140 @@
+use serenity::{
+    all::{
+        ComponentInteraction, Context, CreateInteractionResponse, CreateInteractionResponseMessage,
+        User,
+    },
+    async_trait,
+};
+
+use crate::commands::{aquarium, bait, cast, error_message, inventory, shop};
+use crate::data_management::guildfile::EffectiveSettings;
+use crate::data_management::userfile::UserFile;
+use crate::error::{CommandError, ReelError};
+use crate::helpers::generate_error_code;
+use crate::nay;
+
+/// Separates the prefix and the arguments in a component's custom_id
+/// e.g. `shop:buy:2:0` is routed to the `shop` handler with the args `["buy", "2", "0"]`
+pub const ID_SEPARATOR: char = ':';
+
+pub fn get_all_components() -> Vec<Box<dyn ComponentHandler>> {
+    vec![
+        Box::new(shop::ShopComponent),
+        Box::new(bait::BaitComponent),
+        Box::new(inventory::InventoryComponent),
+        Box::new(cast::CastCancelComponent),
+        Box::new(aquarium::AquariumComponent),
+    ]
+}
+
+/// Handles every button (or select menu) whose custom_id starts with `prefix`.
+/// Any state a view needs is stored in the custom_id itself, so buttons keep
+/// working no matter how old the message is or how many times the bot has restarted.
+#[async_trait]
+pub trait ComponentHandler: Send + Sync {
+    fn prefix(&self) -> &'static str;
+
+    /// the command whose message the component is on, the guild's rules for that command apply to it too
+    fn command(&self) -> &'static str;
+
+    async fn handle(&self, data: &ComponentData<'_>) -> Result<(), CommandError>;
+}
+
+pub struct ComponentData<'a> {
+    pub handler: &'a crate::discord::Handler,
+    pub ctx: &'a Context,
+    pub interaction: &'a ComponentInteraction,
+    pub sender: &'a User,
+    // everything in the custom_id after the prefix
+    pub args: Vec<&'a str>,
+    /// the config and features for where the component was used
+    pub settings: EffectiveSettings,
+}
+
+impl ComponentData<'_> {
+    /// get the argument at `index`, parsed into `T`
+    pub fn arg<T: std::str::FromStr>(&self, index: usize) -> Result<T, String> {
+        self.args
+            .get(index)
+            .and_then(|a| a.parse().ok())
+            .ok_or(format!("Malformed button id: {}", self.interaction.data.custom_id))
+    }
+
+    /// the guild for log lines, "dm" outside of guilds
+    pub fn log_guild(&self) -> String {
+        self.interaction.guild_id.map_or("dm".to_string(), |g| g.to_string())
+    }
+
+    /// the sender's profile for where the component was used
+    pub fn user_file(&self) -> Result<UserFile, ReelError> {
+        UserFile::read_in(&self.sender.id, self.settings.profile_guild)
+    }
+
+    /// replace the message the component is attached to
+    pub async fn update_message(&self, message: CreateInteractionResponseMessage) -> Result<(), CommandError> {
+        self.interaction
+            .create_response(&self.ctx.http, CreateInteractionResponse::UpdateMessage(message))
+            .await?;
+        Ok(())
+    }
+
+    /// Let the player know their button press failed, the same way commands do
+    pub async fn respond_with_error(&self, error: CommandError) {
+        match error {
+            CommandError::Player(msg) => component_response_ephemeral(self.ctx, self.interaction, msg).await,
+            CommandError::Internal { code, error } => {
+                let error_code = format!("{}-{}", code, generate_error_code());
+                nay!(
+                    {user: self.sender.id, guild: self.log_guild(), component: self.interaction.data.custom_id, code: error_code},
+                    "Component {} failed: {}",
+                    self.interaction.data.custom_id,
+                    error
+                );
+                component_response_ephemeral(self.ctx, self.interaction, error_message(&error_code)).await;
+            }
+        }
+    }
+}
+
+/// Build a custom_id for a component handler
+pub fn component_id(prefix: &str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut id = prefix.to_string();
+    for arg in args {
+        id.push(ID_SEPARATOR);
+        id.push_str(&arg.to_string());
+    }
+    id
+}
+
+pub async fn component_response_ephemeral<S: Into<String>>(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+    msg: S,
+) {
+    let data = CreateInteractionResponseMessage::new()
+        .content(msg.into())
+        .ephemeral(true);
+    let builder = CreateInteractionResponse::Message(data);
+    if let Err(err) = interaction.create_response(&ctx.http, builder).await {
+        nay!("Failed to respond to component: {}", err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::get_all_cmds;
+
+    #[test]
+    fn every_component_belongs_to_a_command() {
+        let commands = get_all_cmds();
+        for component in get_all_components() {
+            assert!(
+                commands.iter().any(|c| c.name() == component.command()),
+                "`{}` components name an unknown command `{}`",
+                component.prefix(),
+                component.command()
+            );
+        }
+    }
+}
@@ -0,0 +1,92 @@
+// cooldowns declared on commands with `cooldown: User(5),` in the `command!` macro
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serenity::all::{GuildId, UserId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CooldownScope {
+    /// each player waits on their own
+    User,
+    /// everyone in a guild shares the wait (players in DMs wait on their own)
+    Guild,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Cooldown {
+    pub scope: CooldownScope,
+    pub seconds: f32,
+}
+
+/// When each command's cooldowns end, shared by every interaction
+#[derive(Default)]
+pub struct Cooldowns {
+    ready_at: Mutex<HashMap<(String, u64), Instant>>,
+}
+
+impl Cooldowns {
+    /// Start a command's cooldown, or return how long is left if it's still cooling down
+    pub fn try_use(
+        &self,
+        command: &str,
+        cooldown: Cooldown,
+        user: UserId,
+        guild: Option<GuildId>,
+    ) -> Result<(), Duration> {
+        if cooldown.seconds <= 0.0 || !cooldown.seconds.is_finite() {
+            return Ok(());
+        }
+
+        let key = match cooldown.scope {
+            CooldownScope::User => user.get(),
+            CooldownScope::Guild => guild.map_or(user.get(), GuildId::get),
+        };
+
+        let now = Instant::now();
+        let mut ready_at = self.ready_at.lock().unwrap_or_else(|e| e.into_inner());
+
+        // forget cooldowns that are over, so the map doesn't grow forever
+        ready_at.retain(|_, ready| *ready > now);
+
+        let key = (command.to_string(), key);
+        if let Some(ready) = ready_at.get(&key) {
+            return Err(*ready - now);
+        }
+
+        ready_at.insert(key, now + Duration::from_secs_f32(cooldown.seconds));
+        Ok(())
+    }
+}
+
+/// "try again in" text for a remaining cooldown
+pub fn cooldown_message(command: &str, scope: CooldownScope, remaining: Duration) -> String {
+    let seconds = remaining.as_secs_f32().ceil() as u64;
+    match scope {
+        CooldownScope::User => format!("⏳ Slow down! You can use `/{}` again in {}s.", command, seconds),
+        CooldownScope::Guild => format!("⏳ `/{}` is cooling down, try again in {}s.", command, seconds),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_cooldown_blocks_only_its_own_scope() {
+        let cooldowns = Cooldowns::default();
+        let per_user = Cooldown { scope: CooldownScope::User, seconds: 60.0 };
+        let (alice, bob) = (UserId::new(1), UserId::new(2));
+
+        assert!(cooldowns.try_use("cast", per_user, alice, None).is_ok());
+        assert!(cooldowns.try_use("cast", per_user, alice, None).is_err());
+        assert!(cooldowns.try_use("cast", per_user, bob, None).is_ok());
+        assert!(cooldowns.try_use("shop", per_user, alice, None).is_ok());
+
+        let per_guild = Cooldown { scope: CooldownScope::Guild, seconds: 60.0 };
+        let guild = Some(GuildId::new(7));
+        assert!(cooldowns.try_use("info", per_guild, alice, guild).is_ok());
+        assert!(cooldowns.try_use("info", per_guild, bob, guild).is_err());
+    }
+}
@@ -12,6 +12,6 @@ pub const GAME_TIPS: &[&str] = &[
     "🎣 Tip: Jerrie cheats!",
 ];
 
-pub fn random_tip() -> String {
-    GAME_TIPS[rand::rng().random_range(0..GAME_TIPS.len())].to_string()
+pub fn random_tip(rng: &mut impl Rng) -> String {
+    GAME_TIPS[rng.random_range(0..GAME_TIPS.len())].to_string()
 }
\ No newline at end of file
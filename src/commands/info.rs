@@ -1,16 +1,17 @@
 use serenity::all::{Color, CreateAttachment, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage};
 use crate::{command, nay};
-use crate::data_management::userfile::UserFile;
 use crate::fishing::fish_data::fish::Pond;
+use crate::fishing::gadgets::GadgetCatalog;
 
 command! {
     struct: InfoCommand,
     name: "info",
     desc: "List your stats and loadout information.",
     requires_guild: false,
+    cooldown: User(3),
 
     run: async |data| {
-        let userfile = UserFile::read(&data.sender.id);
+        let userfile = data.user_file()?;
 
         let loadout = &userfile.file.inventory.get_loadout();
 
@@ -21,9 +22,11 @@ command! {
         };
 
         // --- Tech/Unique Items Check ---
-        let mut tech_list = Vec::new();
-        if loadout.has_depth_finder { tech_list.push("✅ Depth Finder"); }
-        if loadout.has_underwater_camera { tech_list.push("✅ Underwater Camera"); }
+        let catalog = GadgetCatalog::load();
+        let tech_list: Vec<String> = userfile.file.inventory.gadgets
+            .iter()
+            .map(|id| format!("✅ {}", catalog.name_of(id)))
+            .collect();
 
         let tech_display = if tech_list.is_empty() {
             "No special equipment equipped.".to_string()
@@ -46,17 +49,19 @@ command! {
 
             // Profile Stats
             .description(format!(
-                "**💳 Balance:** {}\n**🐟 Total Catches:** {}\n🐠 **Fish Discovered:** {}/{}\n",
-                userfile.file.balance, userfile.file.total_catches, userfile.file.caught_fish.len(), fish_count
+                "**💳 Balance:** {}\n**🐟 Total Catches:** {}\n🐠 **Fish Discovered:** {}/{}\n🏆 **Aquarium:** {}/{} fish\n",
+                userfile.file.balance, userfile.file.total_catches, userfile.file.caught_fish.len(), fish_count,
+                userfile.file.aquarium.trophies.len(), data.settings.config.aquarium.capacity
             ))
 
             // Main Gear
             .field("🎒 Fishing Gear", format!(
-                "🎣 **Rod:** {}\n⚙️ **Reel:** {}\n🧵 **Line:** {}\n⚓ **Sinker:** {}\n🪱 **Bait:** {}",
+                "🎣 **Rod:** {}\n⚙️ **Reel:** {}\n🧵 **Line:** {}\n⚓ **Sinker:** {}\n⛵ **Boat:** {}\n🪱 **Bait:** {}",
                 loadout.rod.name,
                 loadout.reel.name,
                 loadout.line.name,
                 loadout.sinker.name,
+                loadout.boat.as_ref().map_or("None (fishing from the shore)", |b| b.name.as_str()),
                 bait_display
             ), false)
 
@@ -64,7 +69,7 @@ command! {
             .field("📊 Stats", format!(
                 "**Strength:** {}\n**Speed:** {}\n**Luck:** {}%\n**Depth:** {}",
                 loadout.get_total_strength_display(),
-                loadout.get_speed_multiplier_display(),
+                loadout.get_speed_multiplier_display(&data.settings.config),
                 (loadout.rod.sensitivity * 100.0) as u32,
                 loadout.get_depth_range_display()
             ), true)
@@ -1,12 +1,15 @@
 use crate::command;
+use crate::commands::components::{ComponentData, ComponentHandler, component_id};
+use crate::data_management::monetary::MonetaryAmount;
 use crate::data_management::userfile::UserFile;
+use crate::error::CommandError;
+use crate::fishing::inventory::GearKind;
+use crate::fishing::rod_data::durability;
 use serenity::all::{
-    ButtonStyle, ComponentInteractionDataKind, CreateActionRow, CreateButton, CreateEmbed,
-    CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
-    EditMessage,
+    ButtonStyle, CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter,
+    CreateInteractionResponse, CreateInteractionResponseMessage,
 };
-use serenity::futures::StreamExt;
-use std::time::Duration;
+use serenity::async_trait;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum InventoryCategory {
@@ -35,6 +38,16 @@ impl InventoryCategory {
         }
     }
 
+    fn from_index(index: u8) -> Option<Self> {
+        match index {
+            0 => Some(Self::Rods),
+            1 => Some(Self::Reels),
+            2 => Some(Self::Lines),
+            3 => Some(Self::Sinkers),
+            _ => None,
+        }
+    }
+
     fn name(&self) -> &str {
         match self {
             Self::Rods => "Fishing Rods",
@@ -44,6 +57,15 @@ impl InventoryCategory {
         }
     }
 
+    fn gear_kind(&self) -> GearKind {
+        match self {
+            Self::Rods => GearKind::Rod,
+            Self::Reels => GearKind::Reel,
+            Self::Lines => GearKind::Line,
+            Self::Sinkers => GearKind::Sinker,
+        }
+    }
+
     fn description(&self) -> &str {
         match self {
             Self::Rods => "View and equip your fishing rods.",
@@ -58,18 +80,16 @@ command! {
     struct: InventoryCommand,
     name: "inventory",
     desc: "View and equip your fishing gear.",
+    cooldown: User(2),
     run: async |data| {
-        let load_file = || UserFile::read(&data.sender.id);
-        let mut user_file = load_file();
+        let user_file = data.user_file()?;
 
-        let mut category = InventoryCategory::Rods;
-        let mut cursor_index = 0; // The item currently highlighted by the user
-        let mut feedback: Option<String> = None;
+        let category = InventoryCategory::Rods;
 
         // --- PREVENT EXPLOIT: Check if fishing ---
         {
             let fishing_set = data.handler.users_fishing.lock().await;
-            if fishing_set.contains(&data.sender.id) {
+            if fishing_set.contains_key(&data.sender.id) {
                 let embed = CreateEmbed::new()
                     .title("🎒 Inventory")
                     .description("You can't change your gear while your rod is cast!")
@@ -77,94 +97,111 @@ command! {
 
                 data.command.create_response(&data.ctx.http, CreateInteractionResponse::Message(
                     CreateInteractionResponseMessage::new().embed(embed).ephemeral(true)
-                )).await.map_err(|e| e.to_string())?;
+                )).await?;
                 return Ok(());
             }
         }
 
         // Initial Embed Construction
-        let embed = build_inventory_embed(&user_file, category, cursor_index, &feedback);
-        let components = build_inventory_components();
+        let embed = build_inventory_embed(&user_file, category, 0, &None, &None);
+        let components = build_inventory_components(category, 0, &None);
 
         let response = CreateInteractionResponseMessage::new()
             .embed(embed)
             .components(components)
             .ephemeral(true);
 
-        data.command.create_response(&data.ctx.http, CreateInteractionResponse::Message(response)).await.map_err(|e| e.to_string())?;
-
-        // Interaction Loop
-        let mut message = data.command.get_response(&data.ctx.http).await.map_err(|e| e.to_string())?;
+        data.command.create_response(&data.ctx.http, CreateInteractionResponse::Message(response)).await?;
 
-        let mut collector = message.await_component_interactions(&data.ctx.shard).stream();
+        Ok(())
+    }
+}
 
-        // 120s timeout loop
-        while let Ok(Some(interaction)) = tokio::time::timeout(Duration::from_secs(120), collector.next()).await {
-            let custom_id = match &interaction.data.kind {
-                ComponentInteractionDataKind::Button => interaction.data.custom_id.clone(),
-                _ => continue,
-            };
+/// Handles the inventory's buttons, ids look like `inv:<action>:<category>:<cursor index>`
+pub struct InventoryComponent;
 
-            // Refresh file data to ensure persistence integrity
-            user_file = load_file();
+#[async_trait]
+impl ComponentHandler for InventoryComponent {
+    fn prefix(&self) -> &'static str {
+        "inv"
+    }
 
-            if custom_id != "inv_equip" {
-                feedback = None;
-            }
+    fn command(&self) -> &'static str {
+        "inventory"
+    }
 
-            match custom_id.as_str() {
-                "inv_left" => {
-                    category = category.prev();
-                    cursor_index = 0;
-                },
-                "inv_right" => {
-                    category = category.next();
-                    cursor_index = 0;
-                },
-                "inv_up" => {
-                    if cursor_index > 0 {
-                        cursor_index -= 1;
-                    } else {
-                        let max_items = get_item_count(&user_file, category);
-                        cursor_index = max_items.saturating_sub(1);
-                    }
-                },
-                "inv_down" => {
+    async fn handle(&self, data: &ComponentData<'_>) -> Result<(), CommandError> {
+        let action: String = data.arg(0)?;
+        let mut category = InventoryCategory::from_index(data.arg(1)?).ok_or("Unknown inventory category")?;
+        let mut cursor_index: usize = data.arg(2)?; // The item currently highlighted by the user
+        let mut feedback: Option<String> = None;
+        // The sale waiting on the player to confirm it
+        let mut pending_sale: Option<(String, f32)> = None;
+
+        // Refresh file data to ensure persistence integrity
+        let mut user_file = data.user_file()?;
+
+        match action.as_str() {
+            "left" => {
+                category = category.prev();
+                cursor_index = 0;
+            },
+            "right" => {
+                category = category.next();
+                cursor_index = 0;
+            },
+            "up" => {
+                if cursor_index > 0 {
+                    cursor_index -= 1;
+                } else {
                     let max_items = get_item_count(&user_file, category);
-                    if cursor_index < max_items.saturating_sub(1) {
-                        cursor_index += 1;
-                    } else {
-                        cursor_index = 0;
-                    }
-                },
-                "inv_equip" => {
+                    cursor_index = max_items.saturating_sub(1);
+                }
+            },
+            "down" => {
+                let max_items = get_item_count(&user_file, category);
+                if cursor_index < max_items.saturating_sub(1) {
+                    cursor_index += 1;
+                } else {
+                    cursor_index = 0;
+                }
+            },
+            "equip" => {
+                // --- PREVENT EXPLOIT: the view can outlive the cast check in /inventory ---
+                if data.handler.users_fishing.lock().await.contains_key(&data.sender.id) {
+                    feedback = Some("Invalid selection, you can't change your gear while your rod is cast!".to_string());
+                } else {
                     match handle_equip(&mut user_file, category, cursor_index) {
                         Ok(msg) => feedback = Some(msg),
                         Err(msg) => feedback = Some(msg),
                     }
-                },
-                _ => {}
-            }
-
-            let embed = build_inventory_embed(&user_file, category, cursor_index, &feedback);
-
-            let _ = interaction.create_response(&data.ctx.http, CreateInteractionResponse::UpdateMessage(
-                CreateInteractionResponseMessage::new().embed(embed).components(build_inventory_components())
-            )).await;
+                }
+            },
+            "sell" => {
+                let fraction = data.settings.config.shop.sell_back_fraction;
+                match user_file.file.inventory.sale_quote(category.gear_kind(), cursor_index, fraction) {
+                    Ok(quote) => pending_sale = Some(quote),
+                    Err(msg) => feedback = Some(format!("Invalid selection, {}.", msg)),
+                }
+            },
+            "confirm_sell" => {
+                if data.handler.users_fishing.lock().await.contains_key(&data.sender.id) {
+                    feedback = Some("Invalid selection, you can't sell gear while your rod is cast!".to_string());
+                } else {
+                    feedback = Some(handle_sell(&mut user_file, category, cursor_index, data.settings.config.shop.sell_back_fraction));
+                    cursor_index = cursor_index.min(get_item_count(&user_file, category).saturating_sub(1));
+                }
+            },
+            _ => {}
         }
 
-        // Timeout Handling
-        let closed_embed = CreateEmbed::new()
-            .title("🎒 Inventory - Closed")
-            .description("Inventory closed to save resources.\nReopen with `/inventory`.")
-            .color(0x2B2D31);
+        let embed = build_inventory_embed(&user_file, category, cursor_index, &feedback, &pending_sale);
 
-        let _ = message.edit(&data.ctx.http, EditMessage::new()
-            .embed(closed_embed)
-            .components(vec![])
-        ).await;
-
-        Ok(())
+        data.update_message(
+            CreateInteractionResponseMessage::new()
+                .embed(embed)
+                .components(build_inventory_components(category, cursor_index, &pending_sale))
+        ).await
     }
 }
 
@@ -212,11 +249,28 @@ fn handle_equip(
     Ok(format!("Equipped **{}**!", item_name))
 }
 
+fn handle_sell(
+    user_file: &mut UserFile,
+    category: InventoryCategory,
+    index: usize,
+    sell_back_fraction: f32,
+) -> String {
+    match user_file.file.inventory.sell(category.gear_kind(), index, sell_back_fraction) {
+        Ok((name, value)) => {
+            user_file.file.balance += MonetaryAmount::new(value);
+            user_file.update();
+            format!("Sold **{}** for ${:.2}!", name, value)
+        }
+        Err(msg) => format!("Invalid selection, {}.", msg),
+    }
+}
+
 fn build_inventory_embed(
     user_file: &UserFile,
     category: InventoryCategory,
     cursor_index: usize,
     feedback: &Option<String>,
+    pending_sale: &Option<(String, f32)>,
 ) -> CreateEmbed {
     let mut description = String::new();
 
@@ -226,6 +280,10 @@ fn build_inventory_embed(
         description.push_str(&format!("### {} {}\n\n", icon, msg));
     }
 
+    if let Some((name, value)) = pending_sale {
+        description.push_str(&format!("### 💰 Sell **{}** for ${:.2}?\n\n", name, value));
+    }
+
     description.push_str(&format!("ℹ️ *{}*\n\n", category.description()));
 
     // Helper closure to generate item lines
@@ -254,19 +312,22 @@ fn build_inventory_embed(
         InventoryCategory::Rods => {
             let active = user_file.file.inventory.selected_rod;
             for (i, item) in user_file.file.inventory.rods.iter().enumerate() {
-                add_item_line(i, &item.name, &item.description, active);
+                let name = format!("{} [{}]", item.name, durability::display(item.durability));
+                add_item_line(i, &name, &item.description, active);
             }
         },
         InventoryCategory::Reels => {
             let active = user_file.file.inventory.selected_reel;
             for (i, item) in user_file.file.inventory.reels.iter().enumerate() {
-                add_item_line(i, &item.name, &item.description, active);
+                let name = format!("{} [{}]", item.name, durability::display(item.durability));
+                add_item_line(i, &name, &item.description, active);
             }
         },
         InventoryCategory::Lines => {
             let active = user_file.file.inventory.selected_line;
             for (i, item) in user_file.file.inventory.lines.iter().enumerate() {
-                add_item_line(i, &item.name, &item.description, active);
+                let name = format!("{} [{}]", item.name, durability::display(item.durability));
+                add_item_line(i, &name, &item.description, active);
             }
         },
         InventoryCategory::Sinkers => {
@@ -281,18 +342,34 @@ fn build_inventory_embed(
         .title(format!("🎒 Inventory - {}", category.name()))
         .description(description)
         .color(0x2B2D31)
-        .footer(CreateEmbedFooter::new("Use arrows to browse | 'Equip' to use selected item"))
+        .footer(CreateEmbedFooter::new("Use arrows to browse | 'Equip' to use selected item | 'Sell' to sell unequipped gear"))
 }
 
-fn build_inventory_components() -> Vec<CreateActionRow> {
-    let left = CreateButton::new("inv_left").label("◀ Category").style(ButtonStyle::Secondary);
-    let right = CreateButton::new("inv_right").label("Category ▶").style(ButtonStyle::Secondary);
-    let up = CreateButton::new("inv_up").label("▲ Up").style(ButtonStyle::Primary);
-    let down = CreateButton::new("inv_down").label("▼ Down").style(ButtonStyle::Primary);
-    let equip = CreateButton::new("inv_equip").label("🎣 Equip").style(ButtonStyle::Success);
+fn build_inventory_components(
+    category: InventoryCategory,
+    cursor_index: usize,
+    pending_sale: &Option<(String, f32)>,
+) -> Vec<CreateActionRow> {
+    let id = |action: &str| component_id("inv", &[&action, &(category as u8), &cursor_index]);
+
+    // Only the confirmation is offered while a sale is pending
+    if let Some((_, value)) = pending_sale {
+        let confirm = CreateButton::new(id("confirm_sell"))
+            .label(format!("💰 Sell for ${:.2}", value))
+            .style(ButtonStyle::Danger);
+        let cancel = CreateButton::new(id("cancel")).label("✖ Cancel").style(ButtonStyle::Secondary);
+        return vec![CreateActionRow::Buttons(vec![confirm, cancel])];
+    }
+
+    let left = CreateButton::new(id("left")).label("◀ Category").style(ButtonStyle::Secondary);
+    let right = CreateButton::new(id("right")).label("Category ▶").style(ButtonStyle::Secondary);
+    let up = CreateButton::new(id("up")).label("▲ Up").style(ButtonStyle::Primary);
+    let down = CreateButton::new(id("down")).label("▼ Down").style(ButtonStyle::Primary);
+    let equip = CreateButton::new(id("equip")).label("🎣 Equip").style(ButtonStyle::Success);
+    let sell = CreateButton::new(id("sell")).label("💰 Sell").style(ButtonStyle::Danger);
 
     vec![
         CreateActionRow::Buttons(vec![left, right, up, down]),
-        CreateActionRow::Buttons(vec![equip]),
+        CreateActionRow::Buttons(vec![equip, sell]),
     ]
 }
\ No newline at end of file
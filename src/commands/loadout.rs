@@ -0,0 +1,112 @@
+use crate::{
+    command,
+    commands::command_response_ephemeral,
+    fishing::inventory::{Inventory, LoadoutPreset},
+};
+
+/// A preset's gear, with the pieces the player doesn't own anymore crossed out
+fn describe_preset(inventory: &Inventory, preset: &LoadoutPreset) -> String {
+    let missing = inventory.missing_from_preset(preset);
+    let show = |name: &str| {
+        if missing.iter().any(|m| m == name) {
+            format!("~~{}~~ *(missing)*", name)
+        } else {
+            name.to_string()
+        }
+    };
+
+    format!(
+        "🎣 {} · 🧵 {} · ⚙️ {} · ⚓ {} · 🪱 {}",
+        show(&preset.rod),
+        show(&preset.line),
+        show(&preset.reel),
+        show(&preset.sinker),
+        preset.bait.as_deref().map_or("No Bait".to_string(), show)
+    )
+}
+
+command! {
+    struct: LoadoutCommand,
+    name: "loadout",
+    desc: "Save and switch between sets of fishing gear.",
+    requires_guild: false,
+    cooldown: User(2),
+
+    run: async |data|
+
+    // SUBCOMMANDS:
+    sub: save("Save your equipped gear as a preset") => async |data,
+        name("The preset's name, e.g. Shallows"): String| {
+        let mut user_file = data.user_file()?;
+
+        let replaced = user_file.file.inventory.save_preset(&name)?;
+        user_file.update();
+
+        let response = if replaced {
+            format!("💾 Updated the **{}** preset with your equipped gear.", name.trim())
+        } else {
+            format!("💾 Saved your equipped gear as **{}**. Switch to it with `/loadout load` or `/cast preset`.", name.trim())
+        };
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+
+    sub: load("Equip a saved preset") => async |data,
+        name("The preset to equip"): String| {
+        // --- PREVENT EXPLOIT: Check if fishing ---
+        if data.handler.users_fishing.lock().await.contains_key(&data.sender.id) {
+            return Err("You can't change your gear while your rod is cast!".into());
+        }
+
+        let mut user_file = data.user_file()?;
+        let missing = user_file.file.inventory.load_preset(&name)?;
+        user_file.update();
+
+        let mut response = format!("🎒 Equipped the **{}** preset.", name.trim());
+        if !missing.is_empty() {
+            response.push_str(&format!("\n⚠️ You don't own **{}** anymore, so you kept what you had instead.", missing.join("**, **")));
+        }
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+
+    sub: list("Show your saved presets") => async |data| {
+        let user_file = data.user_file()?;
+        let inventory = &user_file.file.inventory;
+
+        if inventory.presets.is_empty() {
+            command_response_ephemeral(data.ctx, data.command,
+                "You don't have any presets yet. Equip some gear and use `/loadout save`.").await;
+            return Ok(());
+        }
+
+        let mut response = String::from("🎒 **Loadout Presets**\n");
+        for preset in &inventory.presets {
+            response.push_str(&format!("\n**{}**\n{}\n", preset.name, describe_preset(inventory, preset)));
+        }
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+
+    sub: delete("Delete a saved preset") => async |data,
+        name("The preset to delete"): String| {
+        let mut user_file = data.user_file()?;
+
+        if !user_file.file.inventory.delete_preset(&name) {
+            return Err(format!("You don't have a preset called `{}`.", name.trim()).into());
+        }
+        user_file.update();
+
+        command_response_ephemeral(data.ctx, data.command, format!("🗑️ Deleted the **{}** preset.", name.trim())).await;
+        Ok(())
+    }
+
+    {
+        command_response_ephemeral(
+            data.ctx,
+            data.command,
+            "Please select a subcommand: `save`, `load`, `list`, or `delete`."
+        ).await;
+        Ok(())
+    }
+}
@@ -3,21 +3,31 @@ use serenity::{
     all::{
         Attachment, ChannelId, Command, CommandInteraction, CommandOptionType, Context,
         CreateCommand, CreateCommandOption, CreateInteractionResponse,
-        CreateInteractionResponseMessage, GuildId, PartialChannel, ResolvedOption, ResolvedValue,
-        Role, User,
+        CreateInteractionResponseMessage, GuildId, PartialChannel, Permissions, ResolvedOption,
+        ResolvedValue, Role, User,
     },
     async_trait,
 };
 
+use crate::commands::cooldown::Cooldown;
+use crate::data_management::guildfile::EffectiveSettings;
+use crate::data_management::userfile::UserFile;
+use crate::error::{CommandError, ReelError};
+use crate::helpers::generate_error_code;
 use crate::nay;
 
 mod admin;
-mod cast;
+pub mod components;
+pub mod cooldown;
+pub mod cast;
 mod game_tips;
 mod info;
 mod shop;
 mod bait;
 mod inventory;
+mod loadout;
+mod privacy;
+mod aquarium;
 
 pub fn get_all_cmds() -> Vec<Box<dyn BotCommand>> {
     vec![
@@ -27,8 +37,15 @@ pub fn get_all_cmds() -> Vec<Box<dyn BotCommand>> {
         Box::new(shop::ShopCommand),
         Box::new(bait::BaitCommand),
         Box::new(inventory::InventoryCommand),
+        Box::new(loadout::LoadoutCommand),
+        Box::new(privacy::PrivacyCommand),
+        Box::new(aquarium::AquariumCommand),
+        Box::new(aquarium::TrophyCommand),
         // admin commands
         Box::new(admin::register_channel::RegisterChannelCommand),
+        Box::new(admin::error_code::ErrorCodeCommand),
+        Box::new(admin::settings::SettingsCommand),
+        Box::new(admin::permissions::PermissionsCommand),
     ]
 }
 
@@ -64,27 +81,44 @@ pub async fn command_response_ephemeral<S: Into<String>>(
     }
 }
 
-pub async fn error_command_response<S: Into<String>>(
-    ctx: &Context,
-    command: &CommandInteraction,
-    error_code: S,
-) {
-    let code = error_code.into();
-
-    let responses = vec![
+/// The themed message a player sees for an error code
+pub fn error_message(code: &str) -> String {
+    let responses = [
         "🐡🐡🐡 You discovered Bob Blowfish's Blunder! 🐡🐡🐡 Please report this! 🐡🐡🐡 Error Code: ",
         "🐟🐟🐟 You encountered Minny Minnow's Mistake! 🐟🐟🐟 Please report this! 🐟🐟🐟 Error Code: ",
         "🐋🐋🐋 You encountered Wally Whale's Whoopsie! 🐋🐋🐋 Please report this! 🐋🐋🐋 Error Code: ",
         "🦈🦈🦈 You encountered Sally Shark's Short-Circuit! 🦈🦈🦈 Please report this! 🦈🦈🦈 Error Code: ",
     ];
 
-    let chosen_response = responses
-        .choose(&mut rand::rng())
-        .expect("Error message list should never be empty!");
+    let chosen_response = responses.choose(&mut rand::rng()).unwrap_or(&responses[0]);
+
+    format!("{}`{}`", chosen_response, code)
+}
 
-    let chosen_response = format!("{}`{}`", chosen_response, code);
+pub async fn error_command_response<S: Into<String>>(
+    ctx: &Context,
+    command: &CommandInteraction,
+    error_code: S,
+) {
+    command_response_ephemeral(ctx, command, error_message(&error_code.into())).await;
+}
 
-    command_response_ephemeral(ctx, command, chosen_response).await;
+/// Let the player know their command failed.
+/// Internal errors are logged with a new error code, which is what the player is shown.
+pub async fn respond_with_error(data: &CommandData<'_>, error: CommandError) {
+    match error {
+        CommandError::Player(msg) => command_response_ephemeral(data.ctx, data.command, msg).await,
+        CommandError::Internal { code, error } => {
+            let error_code = format!("{}-{}", code, generate_error_code());
+            nay!(
+                {user: data.sender.id, guild: data.log_guild(), command: data.command_name, code: error_code},
+                "Command /{} failed: {}",
+                data.command_name,
+                error
+            );
+            error_command_response(data.ctx, data.command, error_code).await;
+        }
+    }
 }
 
 #[async_trait]
@@ -94,12 +128,19 @@ pub trait BotCommand: Send + Sync {
     fn is_admin(&self) -> bool {
         false
     }
+    /// permissions a member needs to run the command (the `WITH [...]` list), None for everyone
+    fn required_permissions(&self) -> Option<Permissions> {
+        None
+    }
+    fn cooldown(&self) -> Option<Cooldown> {
+        None
+    }
 
     // returns the builder to send to Discord
     fn register(&self) -> CreateCommand;
 
     // execution logic
-    async fn run(&self, data: &CommandData<'_>) -> Result<(), String>;
+    async fn run(&self, data: &CommandData<'_>) -> Result<(), CommandError>;
 }
 
 pub struct CommandData<'a> {
@@ -112,10 +153,26 @@ pub struct CommandData<'a> {
     pub guild_id: Option<&'a GuildId>,
     pub command_options: Vec<ResolvedOption<'a>>, // TODO: This may need to be changed later
     pub channel: ChannelId,
+    /// the config and features for where the command was run
+    pub settings: EffectiveSettings,
+}
+
+impl CommandData<'_> {
+    /// the guild the command was run in, for structured log fields
+    pub fn log_guild(&self) -> String {
+        self.guild_id.map_or("dm".to_string(), |g| g.to_string())
+    }
+
+    /// the sender's profile for where the command was run
+    pub fn user_file(&self) -> Result<UserFile, ReelError> {
+        UserFile::read_in(&self.sender.id, self.settings.profile_guild)
+    }
 }
 
 // Wrapper for arguments to move from an imperative style to a declarative style
 pub trait CommandArgument<'a>: Sized {
+    // what the choices in `command!` are turned into, optional arguments take their inner type's choices
+    type Choice;
     // what type does the argument map to?
     fn option_type() -> CommandOptionType;
     fn is_required() -> bool {
@@ -127,7 +184,7 @@ pub trait CommandArgument<'a>: Sized {
     fn add_choice(
         builder: CreateCommandOption,
         name: impl Into<String>,
-        value: Self,
+        value: Self::Choice,
     ) -> CreateCommandOption {
         // default impl just returns builder (useful for non-autocomplete types)
         builder
@@ -135,6 +192,7 @@ pub trait CommandArgument<'a>: Sized {
 }
 
 impl<'a> CommandArgument<'a> for i64 {
+    type Choice = Self;
     fn option_type() -> CommandOptionType {
         CommandOptionType::Integer
     }
@@ -149,13 +207,14 @@ impl<'a> CommandArgument<'a> for i64 {
     fn add_choice(
         builder: CreateCommandOption,
         name: impl Into<String>,
-        value: Self,
+        value: Self::Choice,
     ) -> CreateCommandOption {
         builder.add_int_choice(name, value as i32)
     }
 }
 
 impl<'a> CommandArgument<'a> for f64 {
+    type Choice = Self;
     fn option_type() -> CommandOptionType {
         CommandOptionType::Number
     }
@@ -169,13 +228,14 @@ impl<'a> CommandArgument<'a> for f64 {
     fn add_choice(
         builder: CreateCommandOption,
         name: impl Into<String>,
-        value: Self,
+        value: Self::Choice,
     ) -> CreateCommandOption {
         builder.add_number_choice(name, value)
     }
 }
 
 impl<'a> CommandArgument<'a> for bool {
+    type Choice = Self;
     fn option_type() -> CommandOptionType {
         CommandOptionType::Boolean
     }
@@ -189,6 +249,7 @@ impl<'a> CommandArgument<'a> for bool {
 }
 
 impl<'a> CommandArgument<'a> for String {
+    type Choice = Self;
     fn option_type() -> CommandOptionType {
         CommandOptionType::String
     }
@@ -202,7 +263,7 @@ impl<'a> CommandArgument<'a> for String {
     fn add_choice(
         builder: CreateCommandOption,
         name: impl Into<String>,
-        value: Self,
+        value: Self::Choice,
     ) -> CreateCommandOption {
         let name = name.into();
         builder.add_string_choice(name, value)
@@ -210,6 +271,7 @@ impl<'a> CommandArgument<'a> for String {
 }
 
 impl<'a> CommandArgument<'a> for &'a User {
+    type Choice = Self;
     fn option_type() -> CommandOptionType {
         CommandOptionType::User
     }
@@ -223,6 +285,7 @@ impl<'a> CommandArgument<'a> for &'a User {
 }
 
 impl<'a> CommandArgument<'a> for &'a Attachment {
+    type Choice = Self;
     fn option_type() -> CommandOptionType {
         CommandOptionType::Attachment
     }
@@ -236,6 +299,7 @@ impl<'a> CommandArgument<'a> for &'a Attachment {
 }
 
 impl<'a> CommandArgument<'a> for &'a Role {
+    type Choice = Self;
     fn option_type() -> CommandOptionType {
         CommandOptionType::Role
     }
@@ -249,6 +313,7 @@ impl<'a> CommandArgument<'a> for &'a Role {
 }
 
 impl<'a> CommandArgument<'a> for &'a PartialChannel {
+    type Choice = Self;
     fn option_type() -> CommandOptionType {
         CommandOptionType::Channel
     }
@@ -266,6 +331,8 @@ impl<'a, T> CommandArgument<'a> for Option<T>
 where
     T: CommandArgument<'a>,
 {
+    type Choice = T::Choice;
+
     fn option_type() -> CommandOptionType {
         T::option_type()
     }
@@ -284,13 +351,9 @@ where
     fn add_choice(
         builder: CreateCommandOption,
         name: impl Into<String>,
-        value: Self,
+        value: Self::Choice,
     ) -> CreateCommandOption {
-        if let Some(v) = value {
-            T::add_choice(builder, name, v)
-        } else {
-            builder
-        }
+        T::add_choice(builder, name, value)
     }
 }
 
@@ -316,6 +379,7 @@ macro_rules! command {
         desc: $desc:expr,
         requires_guild: $req_guild:expr,
         $( is_admin_command: $is_admin:expr, )?
+        $( cooldown: $cd_scope:ident ( $cd_secs:expr ), )?
 
         // Logic Block
         // Parse the pattern |data, arg(description | (autofill_name : choice), ...): type, ...| WITH [PERMISSIONS...] { body }
@@ -337,12 +401,16 @@ macro_rules! command {
             fn name(&self) -> &'static str { $name }
             fn requires_guild(&self) -> bool { $req_guild }
             $( fn is_admin(&self) -> bool { $is_admin } )?
+            $(
+                fn cooldown(&self) -> Option<crate::commands::cooldown::Cooldown> {
+                    Some(crate::commands::cooldown::Cooldown {
+                        scope: crate::commands::cooldown::CooldownScope::$cd_scope,
+                        seconds: $cd_secs as f32,
+                    })
+                }
+            )?
 
-            fn register(&self) -> serenity::builder::CreateCommand {
-                let mut cmd = serenity::builder::CreateCommand::new($name)
-                .description($desc)
-                .dm_permission(!$req_guild);
-
+            fn required_permissions(&self) -> Option<serenity::all::Permissions> {
                 // default to None (allow everyone)
                 #[allow(unused_mut)]
                 #[allow(unused_assignments)]
@@ -358,8 +426,17 @@ macro_rules! command {
                     required_perms = Some(p);
                 )?
 
-                // Apply to builder if set
-                if let Some(perms) = required_perms {
+                required_perms
+            }
+
+            fn register(&self) -> serenity::builder::CreateCommand {
+                let mut cmd = serenity::builder::CreateCommand::new($name)
+                .description($desc)
+                .dm_permission(!$req_guild);
+
+                // hide admin commands from members who can't use them.
+                // the bot checks them again (see `discord::check_access`) along with the guild's rules
+                if let Some(perms) = self.required_permissions() {
                     cmd = cmd.default_member_permissions(perms);
                 }
 
@@ -424,7 +501,7 @@ macro_rules! command {
                 cmd
             }
 
-            async fn run(&self, $data: &crate::commands::CommandData<'_>) -> Result<(), String> {
+            async fn run(&self, $data: &crate::commands::CommandData<'_>) -> Result<(), crate::error::CommandError> {
                 //  Subcommand Extraction
                 $(
                     if let Some(sub_option) = $data.command_options.iter().find(|o| o.name == stringify!($sub_name)) {
@@ -474,6 +551,7 @@ macro_rules! command {
         name: $name:expr,
         desc: $desc:expr,
         $( is_admin_command: $is_admin:expr, )?
+        $( cooldown: $cd_scope:ident ( $cd_secs:expr ), )?
 
         // 1. Capture the EXACT same patterns as Arm 1
         run: async |$data:ident $(, $arg_name:ident ( $arg_desc:literal $(| [ $( $choice_name:literal : $choice_val:literal ),* ] )? ) : $arg_type:ty )*|
@@ -497,7 +575,8 @@ macro_rules! command {
             name: $name,
             desc: $desc,
             requires_guild: false, // <--- Default is applied here
-            $( is_admin_command: $is_admin:expr, )?
+            $( is_admin_command: $is_admin, )?
+            $( cooldown: $cd_scope ( $cd_secs ), )?
 
             // Forward arguments, choices, and descriptions
             run: async |$data $(, $arg_name ( $arg_desc $(| [ $( $choice_name : $choice_val ),* ] )? ) : $arg_type )*|
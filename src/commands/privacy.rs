@@ -0,0 +1,26 @@
+use crate::{command, commands::command_response_ephemeral};
+
+command! {
+    struct: PrivacyCommand,
+    name: "privacy",
+    desc: "Choose whether your catches are shown to everyone.",
+    requires_guild: false,
+    cooldown: User(2),
+
+    run: async |data, private("Only show your catch results to you, and keep them out of the hall of fame"): Option<bool>| {
+        let mut user_file = data.user_file()?;
+
+        if let Some(private) = private {
+            user_file.file.private_catches = private;
+            user_file.update();
+        }
+
+        let response = if user_file.file.private_catches {
+            "🔒 Your catches are **private**. Only you see your catch results, and they're never announced in the hall of fame."
+        } else {
+            "🌍 Your catches are **public**. Results are posted in the channel (if the server allows it), and rare or record-breaking catches are announced in the hall of fame."
+        };
+        command_response_ephemeral(data.ctx, data.command, response).await;
+        Ok(())
+    }
+}
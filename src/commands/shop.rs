@@ -1,15 +1,17 @@
 use crate::command;
+use crate::commands::components::{ComponentData, ComponentHandler, component_id};
 use crate::data_management::monetary::MonetaryAmount;
+use crate::data_management::config::Config;
 use crate::data_management::userfile::UserFile;
+use crate::fishing::rod_data::durability;
 use crate::fishing::shop::Shop;
+use crate::error::CommandError;
 use chrono::Local;
 use serenity::all::{
-    ButtonStyle, ComponentInteractionDataKind, CreateActionRow, CreateButton, CreateEmbed,
-    CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
-    EditInteractionResponse,
+    ButtonStyle, CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter,
+    CreateInteractionResponse, CreateInteractionResponseMessage,
 };
-use serenity::futures::StreamExt;
-use std::time::Duration;
+use serenity::async_trait;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum ShopCategory {
@@ -19,6 +21,8 @@ enum ShopCategory {
     Sinkers = 3,
     Bait = 4,
     Unique = 5,
+    Repair = 6,
+    Boats = 7,
 }
 
 impl ShopCategory {
@@ -29,18 +33,36 @@ impl ShopCategory {
             Self::Lines => Self::Sinkers,
             Self::Sinkers => Self::Bait,
             Self::Bait => Self::Unique,
-            Self::Unique => Self::Rods,
+            Self::Unique => Self::Boats,
+            Self::Boats => Self::Repair,
+            Self::Repair => Self::Rods,
         }
     }
 
     fn prev(&self) -> Self {
         match self {
-            Self::Rods => Self::Unique,
+            Self::Rods => Self::Repair,
             Self::Reels => Self::Rods,
             Self::Lines => Self::Reels,
             Self::Sinkers => Self::Lines,
             Self::Bait => Self::Sinkers,
             Self::Unique => Self::Bait,
+            Self::Boats => Self::Unique,
+            Self::Repair => Self::Boats,
+        }
+    }
+
+    fn from_index(index: u8) -> Option<Self> {
+        match index {
+            0 => Some(Self::Rods),
+            1 => Some(Self::Reels),
+            2 => Some(Self::Lines),
+            3 => Some(Self::Sinkers),
+            4 => Some(Self::Bait),
+            5 => Some(Self::Unique),
+            6 => Some(Self::Repair),
+            7 => Some(Self::Boats),
+            _ => None,
         }
     }
 
@@ -52,6 +74,8 @@ impl ShopCategory {
             Self::Sinkers => "Sinkers",
             Self::Bait => "Bait (Daily Stock)",
             Self::Unique => "Unique Equipment",
+            Self::Boats => "Boats",
+            Self::Repair => "Repairs",
         }
     }
 
@@ -62,149 +86,174 @@ impl ShopCategory {
             Self::Lines => "Determines the maximum weight you can pull. Stronger lines prevent snapping.",
             Self::Sinkers => "Determines the depth range you can reach. Different fish live at different depths.",
             Self::Bait => "Consumables that attract specific fish, sizes, or rarities. Refreshes daily. See stored bait with `/bait`.",
-            Self::Unique => "Special utility items that provide permanent bonuses or information.",
+            Self::Unique => "Gadgets that provide permanent bonuses or information. Some upgrade a gadget you need to own first.",
+            Self::Boats => "From the shore your line only reaches 200ft, however deep your sinker goes. Boats reach deeper water and carry more bait. A new boat replaces your old one.",
+            Self::Repair => "Worn rods, lines and reels perform worse, and broken ones can't be used. Repairs cost more the more worn the gear is.",
         }
     }
 }
 
-struct UniqueItem {
-    name: &'static str,
-    price: f32,
-    description: &'static str,
-}
-
-const UNIQUE_ITEMS: &[UniqueItem] = &[
-    UniqueItem {
-        name: "Underwater Camera",
-        price: 2500.0,
-        description: "Allows you to see which fish got away if your line snaps.",
-    },
-    UniqueItem {
-        name: "Depth Finder",
-        price: 5000.0,
-        description: "Reveals the exact depth your line reaches when casting.",
-    },
-];
+/// How many baits the quantity button cycles through
+const BAIT_QUANTITIES: &[u32] = &[1, 5, 10, 25];
 
 command! {
     struct: ShopCommand,
     name: "shop",
     desc: "Open the Angler Shop to buy gear and bait.",
+    cooldown: User(2),
     run: async |data| {
         let shop = Shop::load();
 
-        let mut category = ShopCategory::Rods;
-        let mut item_index = 0;
-
-        // Store feedback (Success/Failure, Message)
-        let mut feedback: Option<(bool, String)> = None;
+        let category = ShopCategory::Rods;
+        let item_index = 0;
 
         // Initial Embed Construction
-        let embed = build_shop_embed(&shop, category, item_index, data.sender.id, &feedback);
+        let user_file = data.user_file()?;
+        let embed = build_shop_embed(&shop, category, item_index, &user_file, &None, &data.settings.config);
 
-        let components = build_shop_components();
+        let components = build_shop_components(&shop, category, item_index, 1);
 
         let response = CreateInteractionResponseMessage::new()
             .embed(embed)
             .components(components)
             .ephemeral(true);
 
-        data.command.create_response(&data.ctx.http, CreateInteractionResponse::Message(response)).await.map_err(|e| e.to_string())?;
+        data.command.create_response(&data.ctx.http, CreateInteractionResponse::Message(response)).await?;
 
-        // Component Interaction Loop
-        let message = data.command.get_response(&data.ctx.http).await.map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
 
-        let mut collector = message.await_component_interactions(&data.ctx.shard)
-            .stream();
+/// Handles the shop's buttons, ids look like `shop:<action>:<category>:<item index>:<bait quantity>:<bait stock id>`
+pub struct ShopComponent;
 
-        // Use tokio::time::timeout to reset timer on every interaction
-        while let Ok(Some(interaction)) = tokio::time::timeout(Duration::from_secs(120), collector.next()).await {
-            let custom_id = match &interaction.data.kind {
-                ComponentInteractionDataKind::Button => interaction.data.custom_id.clone(),
-                _ => continue,
-            };
+#[async_trait]
+impl ComponentHandler for ShopComponent {
+    fn prefix(&self) -> &'static str {
+        "shop"
+    }
 
-            if custom_id != "shop_buy" {
-                feedback = None;
-            }
+    fn command(&self) -> &'static str {
+        "shop"
+    }
 
-            match custom_id.as_str() {
-                "shop_left" => {
-                    category = category.prev();
-                    item_index = 0;
-                },
-                "shop_right" => {
-                    category = category.next();
-                    item_index = 0;
-                },
-                "shop_up" => {
-                    if item_index > 0 {
-                        item_index -= 1;
-                    } else {
-                        let max_items = get_item_count(&shop, category);
-                        item_index = max_items.saturating_sub(1);
-                    }
-                },
-                "shop_down" => {
-                    let max_items = get_item_count(&shop, category);
-                    if item_index < max_items.saturating_sub(1) {
-                        item_index += 1;
-                    } else {
-                        item_index = 0;
-                    }
-                },
-                "shop_buy" => {
-                    let res = handle_purchase(&shop, category, item_index, data);
+    async fn handle(&self, data: &ComponentData<'_>) -> Result<(), CommandError> {
+        let action: String = data.arg(0)?;
+        let mut category = ShopCategory::from_index(data.arg(1)?).ok_or("Unknown shop category")?;
+        let mut item_index: usize = data.arg(2)?;
+        // shops opened before bulk buying have no quantity
+        let mut quantity: u32 = data.arg(3).unwrap_or(1);
+        // the bait stock the shop was showing, baits are bought by their place in it
+        let stock_id: Option<String> = data.arg(4).ok();
 
-                    match res {
-                        Ok(msg) => feedback = Some((true, msg)),
-                        Err(msg) => feedback = Some((false, msg)),
-                    }
-                },
-                _ => {}
-            }
+        let shop = Shop::load();
+        let mut user_file = data.user_file()?;
 
-            let embed = build_shop_embed(&shop, category, item_index, data.sender.id, &feedback);
+        // Store feedback (Success/Failure, Message)
+        let mut feedback: Option<(bool, String)> = None;
 
-            let _ = interaction.create_response(&data.ctx.http, CreateInteractionResponse::UpdateMessage(
-                CreateInteractionResponseMessage::new().embed(embed).components(build_shop_components())
-            )).await;
-        }
+        match action.as_str() {
+            "left" => {
+                category = category.prev();
+                item_index = 0;
+            },
+            "right" => {
+                category = category.next();
+                item_index = 0;
+            },
+            "up" => {
+                if item_index > 0 {
+                    item_index -= 1;
+                } else {
+                    let max_items = get_item_count(&shop, category, &user_file);
+                    item_index = max_items.saturating_sub(1);
+                }
+            },
+            "down" => {
+                let max_items = get_item_count(&shop, category, &user_file);
+                if item_index < max_items.saturating_sub(1) {
+                    item_index += 1;
+                } else {
+                    item_index = 0;
+                }
+            },
+            "buy" => {
+                let res = if category == ShopCategory::Bait && stock_id != Some(shop.state.stock_id()) {
+                    item_index = 0;
+                    Err("The bait stock has changed since this shop was opened! Take a look at the new stock before buying.".to_string())
+                } else if category == ShopCategory::Repair {
+                    let res = handle_repair(item_index, &mut user_file, &data.settings.config);
+                    item_index = item_index.min(get_item_count(&shop, category, &user_file).saturating_sub(1));
+                    res
+                } else {
+                    handle_purchase(&shop, category, item_index, quantity, &mut user_file, &data.settings.config)
+                };
 
-        // --- Timeout Handling ---
-        let closed_embed = CreateEmbed::new()
-            .title("🛒 Angler Shop - Closed")
-            .description("Shop closed to save resources.\nReopen with `/shop` to continue browsing.")
-            .color(0x2B2D31);
+                match res {
+                    Ok(msg) => feedback = Some((true, msg)),
+                    Err(msg) => feedback = Some((false, msg)),
+                }
+            },
+            "quantity" => {
+                let next = BAIT_QUANTITIES.iter().position(|q| *q == quantity).map_or(0, |i| i + 1);
+                quantity = BAIT_QUANTITIES[next % BAIT_QUANTITIES.len()];
+            },
+            _ => {}
+        }
 
-        let _ = data.command.edit_response(&data.ctx.http, EditInteractionResponse::new()
-            .embed(closed_embed)
-            .components(vec![]) // Removes all buttons
-        ).await;
+        let embed = build_shop_embed(&shop, category, item_index, &user_file, &feedback, &data.settings.config);
 
-        Ok(())
+        data.update_message(
+            CreateInteractionResponseMessage::new().embed(embed).components(build_shop_components(&shop, category, item_index, quantity))
+        ).await
     }
 }
 
-fn get_item_count(shop: &Shop, category: ShopCategory) -> usize {
+fn get_item_count(shop: &Shop, category: ShopCategory, user_file: &UserFile) -> usize {
     match category {
         ShopCategory::Rods => shop.rods.len(),
         ShopCategory::Reels => shop.reels.len(),
         ShopCategory::Lines => shop.lines.len(),
         ShopCategory::Sinkers => shop.sinkers.len(),
         ShopCategory::Bait => shop.state.daily_baits.len(),
-        ShopCategory::Unique => UNIQUE_ITEMS.len(),
+        ShopCategory::Unique => shop.gadgets.gadgets.len(),
+        ShopCategory::Boats => shop.boats.len(),
+        ShopCategory::Repair => user_file.file.inventory.damaged_gear().len(),
     }
 }
 
+/// Repair the player's damaged gear at `index` of `Inventory::damaged_gear`
+fn handle_repair(index: usize, user_file: &mut UserFile, config: &Config) -> Result<String, String> {
+    let (kind, gear_index) = *user_file
+        .file
+        .inventory
+        .damaged_gear()
+        .get(index)
+        .ok_or("Nothing needs repairing!")?;
+    let inventory = &user_file.file.inventory;
+    let (name, _) = inventory.item_info(kind, gear_index).ok_or("Item not found")?;
+    let price = inventory
+        .repair_cost(kind, gear_index, config.durability.repair_cost)
+        .ok_or("Item not found")?;
+
+    let balance = user_file.file.balance.get();
+    if balance < price { return Err(format!("Insufficient funds! Need ${:.2}", price)); }
+
+    user_file.file.inventory.repair(kind, gear_index);
+    user_file.file.balance = MonetaryAmount::new(balance - price);
+    user_file.update();
+
+    Ok(format!("Repaired **{}** for ${:.2}!", name, price))
+}
+
 fn handle_purchase(
     shop: &Shop,
     category: ShopCategory,
     index: usize,
-    data: &crate::commands::CommandData,
+    quantity: u32,
+    user_file: &mut UserFile,
+    config: &Config,
 ) -> Result<String, String> {
-    let mut user_file = UserFile::read(&data.sender.id);
-
     let balance = user_file.file.balance.get();
     let price;
     let item_name;
@@ -274,35 +323,53 @@ fn handle_purchase(
         },
         ShopCategory::Bait => {
             let item = shop.state.daily_baits.get(index).ok_or("Item not found")?;
-            price = item.price;
-            item_name = item.name.clone();
+            let quantity = quantity.max(1);
+            price = item.price * quantity as f32;
+            item_name = if quantity > 1 { format!("{}x {}", quantity, item.name) } else { item.name.clone() };
             if balance < price { return Err(format!("Insufficient funds! Need ${:.2}", price)); }
 
-            user_file.file.inventory.bait_bucket.add(item.clone());
+            // gadgets like the tackle box make room for more
+            let inventory = &user_file.file.inventory;
+            let capacity = config.shop.bait_capacity
+                + shop.gadgets.effects(&inventory.gadgets).bait_capacity
+                + inventory.boat.as_ref().map_or(0, |b| b.storage);
+            let room = capacity.saturating_sub(inventory.bait_bucket.total());
+            if quantity > room {
+                return Err(format!("Your bait bucket only has room for {} more! Boats and gadgets hold more.", room));
+            }
+
+            user_file.file.inventory.bait_bucket.add_many(item.clone(), quantity);
         },
         ShopCategory::Unique => {
-            let item = UNIQUE_ITEMS.get(index).ok_or("Item not found")?;
-            price = item.price;
-            item_name = item.name.to_string();
+            let gadget = shop.gadgets.gadgets.get(index).ok_or("Item not found")?;
+            shop.gadgets.check_purchase(gadget, &user_file.file.inventory.gadgets)?;
 
+            price = gadget.price;
+            item_name = gadget.name.clone();
             if balance < price { return Err(format!("Insufficient funds! Need ${:.2}", price)); }
 
-            match index {
-                0 => { // Underwater Camera
-                    if user_file.file.inventory.underwater_cam {
-                        return Err("You already own an Underwater Camera!".to_string());
-                    }
-                    user_file.file.inventory.underwater_cam = true;
-                },
-                1 => { // Depth Finder
-                    if user_file.file.inventory.depth_finder {
-                        return Err("You already own a Depth Finder!".to_string());
-                    }
-                    user_file.file.inventory.depth_finder = true;
-                },
-                _ => return Err("Unknown Item".to_string()),
+            user_file.file.inventory.gadgets.push(gadget.id.clone());
+        },
+        ShopCategory::Boats => {
+            let item = shop.boats.get(index).ok_or("Item not found")?;
+
+            if let Some(boat) = &user_file.file.inventory.boat {
+                if boat.name == item.name {
+                    return Err("You already own this boat!".to_string());
+                }
+                if boat.max_depth >= item.max_depth {
+                    return Err(format!("Your {} already reaches deeper water!", boat.name));
+                }
             }
+
+            price = item.price;
+            item_name = item.name.clone();
+            if balance < price { return Err(format!("Insufficient funds! Need ${:.2}", price)); }
+
+            user_file.file.inventory.boat = Some(item.clone());
         },
+        // repairs go through handle_repair
+        ShopCategory::Repair => return Err("Unknown Item".to_string()),
     }
 
     let new_balance = balance - price;
@@ -316,11 +383,10 @@ fn build_shop_embed(
     shop: &Shop,
     category: ShopCategory,
     selected_index: usize,
-    user_id: serenity::all::UserId,
+    user_file: &UserFile,
     feedback: &Option<(bool, String)>,
+    config: &Config,
 ) -> CreateEmbed {
-    let user_file = UserFile::read(&user_id);
-
     let mut description = String::new();
 
     // Transaction Feedback Banner
@@ -378,28 +444,62 @@ fn build_shop_embed(
         },
         ShopCategory::Bait => {
             for (i, item) in shop.state.daily_baits.iter().enumerate() {
-                add_item_line(i, &item.name, item.price, &item.description, false);
+                // the net effect, with the current config's bait weights
+                let desc = format!("{} 📊 {}", item.description, item.effect(&config.bait).summary());
+                add_item_line(i, &item.name, item.price, &desc, false);
             }
             if shop.state.daily_baits.is_empty() {
                 description.push_str("*Sold out for today! Check back tomorrow.*");
             }
         },
         ShopCategory::Unique => {
-            for (i, item) in UNIQUE_ITEMS.iter().enumerate() {
-                let is_owned = match i {
-                    0 => user_file.file.inventory.underwater_cam, // Camera (Index 0 in Array)
-                    1 => user_file.file.inventory.depth_finder,   // Depth Finder (Index 1 in Array)
-                    _ => false,
+            let owned = &user_file.file.inventory.gadgets;
+            for (i, gadget) in shop.gadgets.gadgets.iter().enumerate() {
+                let desc = match &gadget.requires {
+                    Some(required) if !owned.contains(required) => {
+                        format!("🔒 Requires the {}. {}", shop.gadgets.name_of(required), gadget.description)
+                    }
+                    _ => gadget.description.clone(),
                 };
-                add_item_line(i, item.name, item.price, item.description, is_owned);
+                add_item_line(i, &gadget.name, gadget.price, &desc, owned.contains(&gadget.id));
+            }
+        },
+        ShopCategory::Boats => {
+            let current = user_file.file.inventory.boat.as_ref();
+            for (i, item) in shop.boats.iter().enumerate() {
+                let owned = current.is_some_and(|b| b.name == item.name);
+                add_item_line(i, &item.name, item.price, &item.description, owned);
+            }
+        },
+        ShopCategory::Repair => {
+            let inventory = &user_file.file.inventory;
+            let damaged = inventory.damaged_gear();
+            for (i, (kind, index)) in damaged.iter().enumerate() {
+                let (Some((name, _)), Some(condition), Some(cost)) = (
+                    inventory.item_info(*kind, *index),
+                    inventory.durability(*kind, *index),
+                    inventory.repair_cost(*kind, *index, config.durability.repair_cost),
+                ) else {
+                    continue;
+                };
+                let desc = format!("{:?} in {} condition", kind, durability::display(condition));
+                add_item_line(i, &name, cost, &desc, false);
+            }
+            if damaged.is_empty() {
+                description.push_str("*All of your gear is in perfect condition!*");
             }
         },
     }
 
     // --- Footer Logic with Time Calculation ---
     let now = Local::now();
-    let tomorrow_midnight = now.date_naive().succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap();
-    let tomorrow_midnight_local = tomorrow_midnight.and_local_timezone(Local).unwrap();
+    // midnight can be skipped by a daylight saving change, in which case the restock is "now"
+    let tomorrow_midnight_local = now
+        .date_naive()
+        .succ_opt()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .and_then(|d| d.and_local_timezone(Local).earliest())
+        .unwrap_or(now);
 
     let duration = tomorrow_midnight_local - now;
     let hours = duration.num_hours();
@@ -410,20 +510,35 @@ fn build_shop_embed(
         .description(description)
         .color(0x2B2D31)
         .footer(CreateEmbedFooter::new(format!(
-            "Bait refresh in: {}h {}m | This will close in 2 minutes to save resources.",
+            "Bait refresh in: {}h {}m",
             hours, minutes
         )))
 }
 
-fn build_shop_components() -> Vec<CreateActionRow> {
-    let left = CreateButton::new("shop_left").label("◀ Category").style(ButtonStyle::Secondary);
-    let right = CreateButton::new("shop_right").label("Category ▶").style(ButtonStyle::Secondary);
-    let up = CreateButton::new("shop_up").label("▲ Up").style(ButtonStyle::Primary);
-    let down = CreateButton::new("shop_down").label("▼ Down").style(ButtonStyle::Primary);
-    let buy = CreateButton::new("shop_buy").label("🛒 Buy Selected").style(ButtonStyle::Success);
+fn build_shop_components(shop: &Shop, category: ShopCategory, item_index: usize, quantity: u32) -> Vec<CreateActionRow> {
+    // every button carries the current view so the handler can work out the next one
+    let stock_id = shop.state.stock_id();
+    let id = |action: &str| component_id("shop", &[&action, &(category as u8), &item_index, &quantity, &stock_id]);
+
+    let left = CreateButton::new(id("left")).label("◀ Category").style(ButtonStyle::Secondary);
+    let right = CreateButton::new(id("right")).label("Category ▶").style(ButtonStyle::Secondary);
+    let up = CreateButton::new(id("up")).label("▲ Up").style(ButtonStyle::Primary);
+    let down = CreateButton::new(id("down")).label("▼ Down").style(ButtonStyle::Primary);
+    let label = match category {
+        ShopCategory::Repair => "🔧 Repair Selected".to_string(),
+        ShopCategory::Bait => format!("🛒 Buy {}x Selected", quantity),
+        _ => "🛒 Buy Selected".to_string(),
+    };
+    let buy = CreateButton::new(id("buy")).label(label).style(ButtonStyle::Success);
+
+    let mut actions = vec![buy];
+    // baits can be bought in bulk
+    if category == ShopCategory::Bait {
+        actions.push(CreateButton::new(id("quantity")).label(format!("🔢 Quantity: {}", quantity)).style(ButtonStyle::Secondary));
+    }
 
     vec![
         CreateActionRow::Buttons(vec![left, right, up, down]),
-        CreateActionRow::Buttons(vec![buy]),
+        CreateActionRow::Buttons(actions),
     ]
 }
\ No newline at end of file
@@ -0,0 +1,176 @@
+use std::{fs, sync::Mutex};
+
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+
+use crate::data_management::{data_path, set_aside};
+use crate::fishing::fish_data::fish::Fish;
+use crate::{hey, nay};
+
+const CAST_STORE_LOCATION: &str = "casts.ron";
+
+// casts are added and removed from many tasks at once, so all file access goes through this
+static CAST_STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A cast that is waiting for its catch, stored so it survives a restart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingCast {
+    pub user_id: u64,
+    pub user_name: String,
+    pub channel_id: u64,
+    // the fish is rolled when the line is cast, so a restart can't reroll it
+    pub fish: Option<Fish>,
+    /// unix time in milliseconds when the catch happens
+    pub ends_at: i64,
+    pub long_cast: bool,
+    /// token of the `/cast` interaction, used to remove the cancel button
+    pub interaction_token: String,
+    /// seed the cast's rolls come from (see `commands::cast::cast_rng`)
+    #[serde(default)]
+    pub seed: u64,
+    /// the guild the cast was made in, None for DMs
+    #[serde(default)]
+    pub guild_id: Option<u64>,
+    /// an aimed cast that landed on target with a depth finder, which gives the fish a second chance to bite
+    #[serde(default)]
+    pub precise_cast: bool,
+    /// the angler's avatar, for the hall of fame
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+}
+
+/// Every in-flight cast, stored in ./data/casts.ron
+pub struct CastStore;
+
+impl CastStore {
+    pub fn load() -> Vec<PendingCast> {
+        let _lock = CAST_STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        Self::read().unwrap_or_default()
+    }
+
+    /// store a new cast, replacing any older cast from the same user
+    pub fn add(cast: PendingCast) {
+        let _lock = CAST_STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(mut casts) = Self::read() else {
+            return;
+        };
+        casts.retain(|c| c.user_id != cast.user_id);
+        casts.push(cast);
+        Self::write(&casts);
+    }
+
+    /// Remove a user's cast. Returns true if this call removed it, so only one
+    /// caller (the catch or the cancel button) ever gets to resolve a cast.
+    pub fn claim(user_id: &UserId, interaction_token: &str) -> bool {
+        let _lock = CAST_STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(mut casts) = Self::read() else {
+            return false;
+        };
+        let before = casts.len();
+        casts.retain(|c| !(c.user_id == user_id.get() && c.interaction_token == interaction_token));
+
+        if casts.len() == before {
+            return false;
+        }
+
+        Self::write(&casts);
+        true
+    }
+
+    /// Remove whatever cast a user has in flight (used when they reel in early)
+    pub fn remove(user_id: &UserId) -> bool {
+        let _lock = CAST_STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(mut casts) = Self::read() else {
+            return false;
+        };
+        let before = casts.len();
+        casts.retain(|c| c.user_id != user_id.get());
+
+        if casts.len() == before {
+            return false;
+        }
+
+        Self::write(&casts);
+        true
+    }
+
+    /// None if the file is there but can't be read, it must not be written over then.
+    /// A file that can't be parsed is moved aside for an admin to look at, and a new one is started.
+    fn read() -> Option<Vec<PendingCast>> {
+        let raw_path = data_path(CAST_STORE_LOCATION);
+        let path = raw_path.as_path();
+        if !path.exists() {
+            return Some(Vec::new());
+        }
+
+        let data = match fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(e) => {
+                nay!("Failed to read the cast store, leaving it alone: {}", e);
+                return None;
+            }
+        };
+
+        match ron::from_str(&data) {
+            Ok(casts) => Some(casts),
+            Err(e) => match set_aside(CAST_STORE_LOCATION) {
+                Ok(moved) => {
+                    nay!("Failed to deserialize the cast store, moved it to {}: {}", moved.display(), e);
+                    Some(Vec::new())
+                }
+                Err(move_error) => {
+                    nay!("Failed to deserialize the cast store or move it aside, leaving it alone: {} ({})", e, move_error);
+                    None
+                }
+            },
+        }
+    }
+
+    fn write(casts: &[PendingCast]) {
+        let Ok(data) = ron::to_string(casts) else {
+            hey!("Failed to serialize the cast store");
+            return;
+        };
+
+        if let Err(e) = fs::write(data_path(CAST_STORE_LOCATION), data) {
+            hey!("Failed to write the cast store: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestDataDir;
+
+    fn pending(user_id: u64) -> PendingCast {
+        PendingCast {
+            user_id,
+            user_name: "tester".to_string(),
+            channel_id: 1,
+            fish: None,
+            ends_at: 0,
+            long_cast: false,
+            interaction_token: "token".to_string(),
+            seed: 1,
+            guild_id: None,
+            precise_cast: false,
+            avatar_url: None,
+        }
+    }
+
+    #[test]
+    fn a_corrupt_store_is_moved_aside_instead_of_overwritten() {
+        let dir = TestDataDir::new();
+        fs::write(data_path(CAST_STORE_LOCATION), "not a cast store").unwrap();
+
+        CastStore::add(pending(1));
+
+        assert_eq!(CastStore::load().len(), 1);
+        let kept: Vec<String> = fs::read_dir(&dir.path)
+            .unwrap()
+            .filter_map(|entry| fs::read_to_string(entry.ok()?.path()).ok())
+            .collect();
+        assert!(kept.iter().any(|data| data == "not a cast store"));
+    }
+}
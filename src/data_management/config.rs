@@ -1,4 +1,9 @@
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
+use crate::data_management::data_path;
+use crate::error::ReelError;
+use crate::fishing::qte::QteFormat;
+use crate::logging::LogLevel;
 use crate::nay;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -12,6 +17,9 @@ pub enum ValueCalculationType {
 pub struct General {
     pub motd: String,
     pub log_cast_data: bool,
+    /// run without the admin window (same as the `--headless` flag)
+    #[serde(default)]
+    pub headless: bool,
 }
 
 // fishing section of the config
@@ -36,33 +44,204 @@ pub struct BaitConfig {
     pub high_bait_weight: f32,
 }
 
+// logging section of the config
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Logging {
+    /// lines below this level are not printed or written (Info, Warn or Error)
+    pub level: LogLevel,
+    /// write logs to ./data/logs/YYYY-MM-DD.log
+    pub file_logging: bool,
+    /// days to keep old log files for, 0 keeps them forever
+    pub retention_days: u32,
+}
+
+impl Default for Logging {
+    fn default() -> Self {
+        Self {
+            level: LogLevel::Info,
+            file_logging: true,
+            retention_days: 30,
+        }
+    }
+}
+
+// anti_macro section of the config
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AntiMacro {
+    /// the QTE formats to pick from (Digits, Words, Math, EmojiOrder)
+    pub qte_formats: Vec<QteFormat>,
+    /// how many of a player's most recent QTE reply times are kept
+    pub reply_window: usize,
+    /// replies needed before a player can be flagged
+    pub min_samples: usize,
+    /// flag players whose reply times vary by less than this many seconds (standard deviation)
+    pub min_reply_deviation: f32,
+    /// flag players whose replies average less than this many seconds
+    pub min_average_reply: f32,
+}
+
+impl Default for AntiMacro {
+    fn default() -> Self {
+        Self {
+            qte_formats: QteFormat::ALL.to_vec(),
+            reply_window: 10,
+            min_samples: 6,
+            min_reply_deviation: 0.15,
+            min_average_reply: 1.0,
+        }
+    }
+}
+
+// fight section of the config
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FightConfig {
+    /// fish with at least this fight multiplier are always fought (Apex is 2.5)
+    pub min_fight_multiplier: f32,
+    /// fish weighing at least this share of the loadout's strength are fought
+    pub min_weight_ratio: f32,
+    /// how hard a fish pulls on the line each round, before its category and weight
+    pub base_pull: f32,
+    /// how much stamina reeling takes from a fish each round, before the reel's speed
+    pub base_reel_power: f32,
+    /// seconds the player has to pick a move each round, doing nothing holds the line
+    pub round_time: f32,
+    /// the fish gets away if it isn't landed in this many rounds
+    pub max_rounds: u32,
+}
+
+impl Default for FightConfig {
+    fn default() -> Self {
+        Self {
+            min_fight_multiplier: 2.0,
+            min_weight_ratio: 0.75,
+            base_pull: 10.0,
+            base_reel_power: 15.0,
+            round_time: 10.0,
+            max_rounds: 20,
+        }
+    }
+}
+
+// shop section of the config
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ShopConfig {
+    /// the share of an item's price a player gets back when selling it (0 to 1)
+    pub sell_back_fraction: f32,
+    /// how many baits a bait bucket holds before gadgets add room, more can't be bought past it
+    pub bait_capacity: u32,
+}
+
+impl Default for ShopConfig {
+    fn default() -> Self {
+        Self { sell_back_fraction: 0.4, bait_capacity: 50 }
+    }
+}
+
+// aquarium section of the config
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AquariumConfig {
+    /// how many fish a player can mount at once
+    pub capacity: usize,
+    /// the share of each species' most valuable mounted fish the aquarium earns an hour
+    pub income_rate: f32,
+    /// the most hours of income that build up between visits
+    pub max_income_hours: f32,
+}
+
+impl Default for AquariumConfig {
+    fn default() -> Self {
+        Self { capacity: 10, income_rate: 0.01, max_income_hours: 24.0 }
+    }
+}
+
+// durability section of the config
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DurabilityConfig {
+    /// durability every cast takes from the equipped rod, line and reel (gear starts at 1.0)
+    pub cast_wear: f32,
+    /// extra wear from a hooked fish, times how close its weight is to the loadout's strength
+    pub heavy_fish_wear: f32,
+    /// extra wear when the line snaps from a failed QTE or fight
+    pub snap_wear: f32,
+    /// the share of an item's price it costs to repair it from broken to new
+    pub repair_cost: f32,
+}
+
+impl Default for DurabilityConfig {
+    fn default() -> Self {
+        Self {
+            cast_wear: 0.005,
+            heavy_fish_wear: 0.01,
+            snap_wear: 0.1,
+            repair_cost: 0.5,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
     pub general: General,
     pub fishing: Fishing,
     pub bait: BaitConfig,
+    #[serde(default)]
+    pub logging: Logging,
+    #[serde(default)]
+    pub anti_macro: AntiMacro,
+    #[serde(default)]
+    pub fight: FightConfig,
+    #[serde(default)]
+    pub shop: ShopConfig,
+    #[serde(default)]
+    pub durability: DurabilityConfig,
+    #[serde(default)]
+    pub aquarium: AquariumConfig,
+    /// seconds between uses of a command by name, replacing the command's own cooldown (0 turns it off)
+    #[serde(default)]
+    pub cooldowns: HashMap<String, f32>,
 }
 
 impl Config {
+    /// Load ./data/config.toml, falling back to the defaults if it is missing or broken
     pub fn load() -> Self {
-        let raw_path = "./data/config.toml".to_string();
-        let path = std::path::Path::new(raw_path.as_str());
+        let raw_path = data_path("config.toml");
+        let path = raw_path.as_path();
 
         if !path.exists() {
-            nay!("Config file does not exist at path: {}", raw_path);
+            nay!("Config file does not exist at path: {}", raw_path.display());
             return Self::default();
         }
 
-        let contents = std::fs::read_to_string(path).unwrap();
+        Self::load_from(path).unwrap_or_else(|e| {
+            nay!("Failed to load the config, using the defaults: {}", e);
+            Self::default()
+        })
+    }
+
+    /// Load a config from an arbitrary path (used by tools like the balance simulator)
+    pub fn load_from(path: &std::path::Path) -> Result<Self, ReelError> {
+        let contents = std::fs::read_to_string(path)?;
 
-        toml::from_str(contents.as_str()).unwrap()
+        toml::from_str(contents.as_str())
+            .map_err(|e| ReelError::FileLoadFailed(format!("{}: {}", path.display(), e)))
     }
 
     pub fn save(&self) {
-        let raw_path = "./data/config.toml".to_string();
-        let path = std::path::Path::new(raw_path.as_str());
+        let raw_path = data_path("config.toml");
+        let path = raw_path.as_path();
 
-        let config_string = toml::to_string(self).unwrap();
+        let config_string = match toml::to_string(self) {
+            Ok(config_string) => config_string,
+            Err(e) => {
+                nay!("Failed to serialize config: {}", e);
+                return;
+            }
+        };
 
         if let Err(e) = std::fs::write(path, config_string) {
             nay!("Failed to write config: {}", e);
@@ -76,6 +255,7 @@ impl Default for Config {
             general: General {
                 motd: "Welcome to Angler Bot!".to_string(),
                 log_cast_data: false,
+                headless: false,
             },
             fishing: Fishing {
                 fish_weight_time_multiplier: 1.2,
@@ -93,6 +273,13 @@ impl Default for Config {
                 medium_bait_weight: 3.5,
                 high_bait_weight: 5.0,
             },
+            logging: Logging::default(),
+            anti_macro: AntiMacro::default(),
+            fight: FightConfig::default(),
+            shop: ShopConfig::default(),
+            durability: DurabilityConfig::default(),
+            aquarium: AquariumConfig::default(),
+            cooldowns: HashMap::new(),
         }
     }
 }
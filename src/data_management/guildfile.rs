@@ -1,22 +1,385 @@
 use std::{
+    collections::HashMap,
     fs::{self, OpenOptions},
     io::Write,
-    path::Path,
+    sync::Mutex,
 };
 
 use serde::{Deserialize, Serialize};
-use serenity::all::{ChannelId, GuildId};
+use serenity::all::{ChannelId, GuildId, RoleId};
 
+use crate::data_management::config::{Config, ValueCalculationType};
+use crate::data_management::data_path;
+use crate::error::ReelError;
 use crate::hey;
 
-const GUILDFILE_LOCATION: &str = "./data/guilds/";
+const GUILDFILE_LOCATION: &str = "guilds";
 
-#[derive(Serialize, Deserialize)]
+// catches and commands edit guild files at the same time, so every read-modify-write goes through this
+static GUILD_FILE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct GuildFile {
     // channels where fishing commands are allowed
     // if empty, fishing commands are allowed in all channels
     pub fishing_channels: Vec<u64>,
-    // other guild specific settings can go here
+    pub features: GuildFeatures,
+    /// the hall of fame, where rare and record-breaking catches are announced, if anywhere
+    pub announcement_channel: Option<u64>,
+    /// the heaviest catch of each species in the guild, by species name
+    pub records: HashMap<String, CatchRecord>,
+    pub overrides: ConfigOverrides,
+    pub profile_mode: ProfileMode,
+    /// members with this role can use admin commands without discord permissions
+    pub manager_role: Option<u64>,
+    /// per-command rules, by command name. Commands without an entry are open to everyone
+    pub commands: HashMap<String, CommandRules>,
+}
+
+/// The heaviest fish of a species caught in a guild
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatchRecord {
+    pub user_id: u64,
+    pub user_name: String,
+    pub weight: f32,
+}
+
+/// Who can run a command in a guild, and where
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandRules {
+    pub enabled: bool,
+    /// if not empty, only members with one of these roles can run the command
+    pub roles: Vec<u64>,
+    /// if not empty, the command can only be run in these channels (instead of the fishing channels)
+    pub channels: Vec<u64>,
+}
+
+impl Default for CommandRules {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            roles: Vec::new(),
+            channels: Vec::new(),
+        }
+    }
+}
+
+impl CommandRules {
+    /// rules that don't restrict anything don't need to be stored
+    pub fn is_default(&self) -> bool {
+        self.enabled && self.roles.is_empty() && self.channels.is_empty()
+    }
+}
+
+impl GuildFile {
+    /// Keep a catch if it's the heaviest of its species.
+    /// Returns the record it broke, None if it didn't break one (the first catch of a species sets a record without breaking one).
+    pub fn record_catch(&mut self, species: &str, record: CatchRecord) -> Option<CatchRecord> {
+        if !self.is_record(species, record.weight) {
+            return None;
+        }
+        self.records.insert(species.to_string(), record)
+    }
+
+    /// Whether a catch of this weight would be the heaviest of its species
+    pub fn is_record(&self, species: &str, weight: f32) -> bool {
+        self.records.get(species).is_none_or(|best| weight > best.weight)
+    }
+
+    /// Check a regular command against the guild's rules.
+    /// Returns the message to show the member if they can't run it.
+    pub fn check_command(&self, command: &str, roles: &[RoleId], channel: ChannelId) -> Result<(), String> {
+        let rules = self.commands.get(command).cloned().unwrap_or_default();
+
+        if !rules.enabled {
+            return Err(format!("`/{}` is turned off in this server.", command));
+        }
+
+        if !rules.roles.is_empty() && !roles.iter().any(|r| rules.roles.contains(&r.get())) {
+            return Err(format!("You don't have a role that can use `/{}` here.", command));
+        }
+
+        // a command's own channels replace the fishing channels
+        let channels = if rules.channels.is_empty() { &self.fishing_channels } else { &rules.channels };
+        if !channels.is_empty() && !channels.contains(&channel.get()) {
+            return Err("This channel is not allowed for fishing commands!".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Change a command's rules, dropping them once they no longer restrict anything
+    pub fn edit_rules(&mut self, command: &str, edit: impl FnOnce(&mut CommandRules)) {
+        let rules = self.commands.entry(command.to_string()).or_default();
+        edit(rules);
+        if rules.is_default() {
+            self.commands.remove(command);
+        }
+    }
+
+    pub fn is_manager(&self, roles: &[RoleId]) -> bool {
+        self.manager_role.is_some_and(|manager| roles.iter().any(|r| r.get() == manager))
+    }
+}
+
+/// Whether players use their global profile in a guild, or have a separate one there
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProfileMode {
+    Global,
+    PerGuild,
+}
+
+impl Default for ProfileMode {
+    // the `guild_relative_userdata` feature makes new guilds start with their own profiles
+    fn default() -> Self {
+        if cfg!(feature = "guild_relative_userdata") {
+            ProfileMode::PerGuild
+        } else {
+            ProfileMode::Global
+        }
+    }
+}
+
+/// Parts of the bot a guild can turn off
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuildFeatures {
+    /// heavy fish need a QTE to land, otherwise they are reeled in without one
+    pub qte: bool,
+    /// catch results are posted in the channel, otherwise only the angler sees them
+    pub public_results: bool,
+    /// Strange Angler Darryl's comments on casts and lost fish
+    pub darryl_messages: bool,
+    /// big fish are fought with buttons, otherwise they are reeled in right away
+    pub fights: bool,
+}
+
+impl Default for GuildFeatures {
+    fn default() -> Self {
+        Self {
+            qte: true,
+            public_results: true,
+            darryl_messages: true,
+            fights: true,
+        }
+    }
+}
+
+impl GuildFeatures {
+    pub const NAMES: &[&str] = &["qte", "public_results", "darryl_messages", "fights"];
+
+    pub fn set(&mut self, feature: &str, enabled: bool) -> Result<(), String> {
+        match feature {
+            "qte" => self.qte = enabled,
+            "public_results" => self.public_results = enabled,
+            "darryl_messages" => self.darryl_messages = enabled,
+            "fights" => self.fights = enabled,
+            _ => return Err(format!("Unknown feature `{}`.", feature)),
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, feature: &str) -> Option<bool> {
+        match feature {
+            "qte" => Some(self.qte),
+            "public_results" => Some(self.public_results),
+            "darryl_messages" => Some(self.darryl_messages),
+            "fights" => Some(self.fights),
+            _ => None,
+        }
+    }
+}
+
+/// Guild values that replace the global config's fishing and bait sections.
+/// Anything left as None uses the global value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfigOverrides {
+    pub fishing: FishingOverrides,
+    pub bait: BaitOverrides,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FishingOverrides {
+    pub base_catch_chance: Option<f32>,
+    pub base_cast_wait: Option<f32>,
+    pub min_cast_wait: Option<f32>,
+    pub max_cast_wait: Option<f32>,
+    pub base_qte_time: Option<f32>,
+    pub min_qte_time: Option<f32>,
+    pub fish_value_calculation: Option<ValueCalculationType>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BaitOverrides {
+    pub low_bait_weight: Option<f32>,
+    pub medium_bait_weight: Option<f32>,
+    pub high_bait_weight: Option<f32>,
+}
+
+impl ConfigOverrides {
+    /// the numeric settings that can be overridden, as `section.name`
+    pub const NUMERIC: &[&str] = &[
+        "fishing.base_catch_chance",
+        "fishing.base_cast_wait",
+        "fishing.min_cast_wait",
+        "fishing.max_cast_wait",
+        "fishing.base_qte_time",
+        "fishing.min_qte_time",
+        "bait.low_bait_weight",
+        "bait.medium_bait_weight",
+        "bait.high_bait_weight",
+    ];
+
+    fn numeric_mut(&mut self, setting: &str) -> Option<&mut Option<f32>> {
+        match setting {
+            "fishing.base_catch_chance" => Some(&mut self.fishing.base_catch_chance),
+            "fishing.base_cast_wait" => Some(&mut self.fishing.base_cast_wait),
+            "fishing.min_cast_wait" => Some(&mut self.fishing.min_cast_wait),
+            "fishing.max_cast_wait" => Some(&mut self.fishing.max_cast_wait),
+            "fishing.base_qte_time" => Some(&mut self.fishing.base_qte_time),
+            "fishing.min_qte_time" => Some(&mut self.fishing.min_qte_time),
+            "bait.low_bait_weight" => Some(&mut self.bait.low_bait_weight),
+            "bait.medium_bait_weight" => Some(&mut self.bait.medium_bait_weight),
+            "bait.high_bait_weight" => Some(&mut self.bait.high_bait_weight),
+            _ => None,
+        }
+    }
+
+    /// Override a numeric setting, or go back to the global value with None.
+    /// Refused if it would leave the min cast wait above the max, or the min QTE time above the base, with the `global` config.
+    pub fn set(&mut self, setting: &str, value: Option<f32>, global: &Config) -> Result<(), String> {
+        if let Some(value) = value {
+            let valid = if setting == "fishing.base_catch_chance" {
+                (0.0..=1.0).contains(&value)
+            } else {
+                value > 0.0 && value.is_finite()
+            };
+            if !valid {
+                return Err(format!("`{}` can't be set to {}.", setting, value));
+            }
+        }
+
+        let mut changed = self.clone();
+        let slot = changed
+            .numeric_mut(setting)
+            .ok_or_else(|| format!("Unknown setting `{}`.", setting))?;
+        *slot = value;
+
+        let fishing = changed.apply(global.clone()).fishing;
+        if fishing.min_cast_wait > fishing.max_cast_wait {
+            return Err(format!(
+                "That would make the min cast wait ({}s) longer than the max cast wait ({}s) in this server.",
+                fishing.min_cast_wait, fishing.max_cast_wait
+            ));
+        }
+        if fishing.min_qte_time > fishing.base_qte_time {
+            return Err(format!(
+                "That would make the min QTE time ({}s) longer than the base QTE time ({}s) in this server.",
+                fishing.min_qte_time, fishing.base_qte_time
+            ));
+        }
+
+        *self = changed;
+        Ok(())
+    }
+
+    /// every override that is set, as `(section.name, value)`
+    pub fn list(&self) -> Vec<(String, String)> {
+        let (fishing, bait) = (&self.fishing, &self.bait);
+        let numeric = [
+            fishing.base_catch_chance,
+            fishing.base_cast_wait,
+            fishing.min_cast_wait,
+            fishing.max_cast_wait,
+            fishing.base_qte_time,
+            fishing.min_qte_time,
+            bait.low_bait_weight,
+            bait.medium_bait_weight,
+            bait.high_bait_weight,
+        ];
+
+        let mut list: Vec<(String, String)> = Self::NUMERIC
+            .iter()
+            .zip(numeric)
+            .filter_map(|(name, value)| Some((name.to_string(), value?.to_string())))
+            .collect();
+
+        if let Some(calculation) = &fishing.fish_value_calculation {
+            list.push(("fishing.fish_value_calculation".to_string(), format!("{:?}", calculation)));
+        }
+        list
+    }
+
+    /// the global config with these overrides applied
+    pub fn apply(&self, mut config: Config) -> Config {
+        let fishing = &self.fishing;
+        let set = |target: &mut f32, value: Option<f32>| {
+            if let Some(value) = value {
+                *target = value;
+            }
+        };
+
+        set(&mut config.fishing.base_catch_chance, fishing.base_catch_chance);
+        set(&mut config.fishing.base_cast_wait, fishing.base_cast_wait);
+        set(&mut config.fishing.min_cast_wait, fishing.min_cast_wait);
+        set(&mut config.fishing.max_cast_wait, fishing.max_cast_wait);
+        set(&mut config.fishing.base_qte_time, fishing.base_qte_time);
+        set(&mut config.fishing.min_qte_time, fishing.min_qte_time);
+        if let Some(calculation) = &fishing.fish_value_calculation {
+            config.fishing.fish_value_calculation = calculation.clone();
+        }
+
+        set(&mut config.bait.low_bait_weight, self.bait.low_bait_weight);
+        set(&mut config.bait.medium_bait_weight, self.bait.medium_bait_weight);
+        set(&mut config.bait.high_bait_weight, self.bait.high_bait_weight);
+
+        config
+    }
+}
+
+/// The settings an interaction runs with: the global config with the guild's overrides applied
+#[derive(Debug, Clone)]
+pub struct EffectiveSettings {
+    pub config: Config,
+    pub features: GuildFeatures,
+    pub announcement_channel: Option<ChannelId>,
+    /// the guild player profiles are read from, None for global profiles (always None in DMs)
+    pub profile_guild: Option<GuildId>,
+}
+
+impl EffectiveSettings {
+    /// the global config and default features, for DMs
+    pub fn global() -> Self {
+        Self::resolve(None)
+    }
+
+    pub fn resolve(guild: Option<&GuildSettings>) -> Self {
+        let config = Config::load();
+        match guild {
+            Some(guild) => Self {
+                config: guild.file.overrides.apply(config),
+                features: guild.file.features.clone(),
+                announcement_channel: guild.file.announcement_channel.map(ChannelId::new),
+                profile_guild: (guild.file.profile_mode == ProfileMode::PerGuild).then_some(guild.id),
+            },
+            None => Self {
+                config,
+                features: GuildFeatures::default(),
+                announcement_channel: None,
+                profile_guild: None,
+            },
+        }
+    }
+
+    pub fn for_guild(guild_id: Option<&GuildId>) -> Result<Self, ReelError> {
+        let guild = guild_id.map(GuildSettings::get).transpose()?;
+        Ok(Self::resolve(guild.as_ref()))
+    }
 }
 
 pub struct GuildSettings {
@@ -28,38 +391,34 @@ impl GuildSettings {
     pub fn new(guild_id: &GuildId) -> Self {
         Self {
             id: guild_id.clone(),
-            file: GuildFile {
-                fishing_channels: Vec::new(),
-            },
+            file: GuildFile::default(),
         }
     }
 
-    pub fn get(id: &GuildId) -> Self {
-        let raw_path = format!("{}/{}.ron", GUILDFILE_LOCATION, id);
-        let path = Path::new(&raw_path);
+    /// Read a guild's settings, creating the file for new guilds
+    pub fn get(id: &GuildId) -> Result<Self, ReelError> {
+        let raw_path = data_path(&format!("{}/{}.ron", GUILDFILE_LOCATION, id));
+        let path = raw_path.as_path();
 
         if !path.exists() {
             Self::generate(id);
-            return Self::new(id);
+            return Ok(Self::new(id));
         }
 
-        let Ok(data) = fs::read_to_string(path) else {
-            Self::generate(id);
-            return Self::new(id);
-        };
+        let data = fs::read_to_string(path)?;
 
         let guildfile: GuildFile = ron::from_str(data.as_str())
-            .expect(format!("failed to deserialize guild data with ID {}", id).as_str());
+            .map_err(|e| ReelError::FileLoadFailed(format!("guild data with ID {}: {}", id, e)))?;
 
-        Self {
+        Ok(Self {
             id: id.clone(),
             file: guildfile,
-        }
+        })
     }
 
     fn generate(id: &GuildId) {
-        let raw_path = format!("{}/{}.ron", GUILDFILE_LOCATION, id);
-        let path = Path::new(raw_path.as_str());
+        let raw_path = data_path(&format!("{}/{}.ron", GUILDFILE_LOCATION, id));
+        let path = raw_path.as_path();
 
         if path.exists() {
             hey!("Guild data already exists: {}", id);
@@ -89,18 +448,25 @@ impl GuildSettings {
         }
     }
 
-    fn reload(&mut self) {
-        *self = Self::get(&self.id);
+    fn reload(&mut self) -> Result<(), ReelError> {
+        *self = Self::get(&self.id)?;
+        Ok(())
     }
 
-    fn update(&self) {
-        let raw_path = format!("{}/{}.ron", GUILDFILE_LOCATION, self.id);
-        let path = Path::new(raw_path.as_str());
+    pub fn update(&self) {
+        let raw_path = data_path(&format!("{}/{}.ron", GUILDFILE_LOCATION, self.id));
+        let path = raw_path.as_path();
 
         if !path.exists() {
             Self::generate(&self.id);
         };
 
+        // serialize before the file is truncated, so a failure can't wipe the settings
+        let Ok(data) = ron::to_string(&self.file) else {
+            hey!("Failed to serialize guild data: {}", &self.id);
+            return;
+        };
+
         let Ok(mut file) = OpenOptions::new()
             .read(false)
             .write(true)
@@ -113,41 +479,151 @@ impl GuildSettings {
             return;
         };
 
-        let Ok(data) = ron::to_string(&self.file) else {
-            hey!("Failed to serialize guild data: {}", &self.id);
-            return;
-        };
-
         if let Err(e) = write!(file, "{}", data) {
             hey!("Failed to write to file for guild {}: {}", &self.id, e);
         }
     }
 
-    pub fn get_channels(&mut self) -> Vec<ChannelId> {
-        self.reload();
-        self.file
+    pub fn get_channels(&mut self) -> Result<Vec<ChannelId>, ReelError> {
+        self.reload()?;
+        Ok(self
+            .file
             .fishing_channels
             .clone()
             .iter()
             .map(|x| ChannelId::from(*x))
-            .collect::<Vec<ChannelId>>()
+            .collect::<Vec<ChannelId>>())
     }
 
-    pub fn add_channel(&mut self, channel_id: u64) {
-        self.reload();
+    pub fn add_channel(&mut self, channel_id: u64) -> Result<(), ReelError> {
+        let _lock = GUILD_FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        self.reload()?;
         self.file.fishing_channels.push(channel_id);
         self.update();
+        Ok(())
     }
 
-    pub fn remove_channel(&mut self, channel_id: u64) {
-        self.reload();
+    pub fn remove_channel(&mut self, channel_id: u64) -> Result<(), ReelError> {
+        let _lock = GUILD_FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        self.reload()?;
         self.file.fishing_channels.retain(|&x| x != channel_id);
         self.update();
+        Ok(())
+    }
+
+    /// Change the guild's settings. The file is reloaded first so other edits aren't lost.
+    pub fn edit<T>(&mut self, edit: impl FnOnce(&mut GuildFile) -> T) -> Result<T, ReelError> {
+        let _lock = GUILD_FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        self.reload()?;
+        let result = edit(&mut self.file);
+        self.update();
+        Ok(result)
     }
 
     /// check if a channel is allowed for fishing commands
-    pub fn check_channel(&mut self, channel_id: u64) -> bool {
-        self.reload();
-        self.file.fishing_channels.contains(&channel_id) || self.file.fishing_channels.is_empty()
+    pub fn check_channel(&mut self, channel_id: u64) -> Result<bool, ReelError> {
+        self.reload()?;
+        Ok(self.file.fishing_channels.contains(&channel_id) || self.file.fishing_channels.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_from_before_overrides_still_load() {
+        let file: GuildFile = ron::from_str("(fishing_channels: [12])").unwrap();
+
+        assert_eq!(file.fishing_channels, vec![12]);
+        assert!(file.features.qte && file.features.public_results && file.features.darryl_messages);
+        assert!(file.overrides.list().is_empty());
+    }
+
+    #[test]
+    fn overrides_replace_only_what_they_set() {
+        let global = Config::default();
+        let mut overrides = ConfigOverrides::default();
+        overrides.set("fishing.base_catch_chance", Some(0.9), &global).unwrap();
+        assert!(overrides.set("fishing.base_catch_chance", Some(1.5), &global).is_err());
+        assert!(overrides.set("general.motd", Some(1.0), &global).is_err());
+
+        let config = overrides.apply(global.clone());
+
+        assert_eq!(config.fishing.base_catch_chance, 0.9);
+        assert_eq!(config.fishing.base_cast_wait, global.fishing.base_cast_wait);
+        assert_eq!(overrides.list(), vec![("fishing.base_catch_chance".to_string(), "0.9".to_string())]);
+    }
+
+    #[test]
+    fn only_heavier_catches_break_records() {
+        let mut file = GuildFile::default();
+        let catch = |weight| CatchRecord { user_id: 1, user_name: "angler".to_string(), weight };
+
+        assert!(file.is_record("Carp", 1.0));
+        assert!(file.record_catch("Carp", catch(2.0)).is_none());
+        assert!(!file.is_record("Carp", 2.0));
+        assert!(file.record_catch("Carp", catch(2.0)).is_none());
+        assert_eq!(file.record_catch("Carp", catch(3.0)).unwrap().weight, 2.0);
+    }
+
+    #[test]
+    fn cast_wait_overrides_keep_min_below_max() {
+        let global = Config::default();
+        let max = global.fishing.max_cast_wait;
+        let mut overrides = ConfigOverrides::default();
+
+        assert!(overrides.set("fishing.min_cast_wait", Some(max + 1.0), &global).is_err());
+        assert!(overrides.list().is_empty());
+
+        // raising the max first makes room, but it can't be reset while the min needs it
+        overrides.set("fishing.max_cast_wait", Some(max + 10.0), &global).unwrap();
+        overrides.set("fishing.min_cast_wait", Some(max + 1.0), &global).unwrap();
+        assert!(overrides.set("fishing.max_cast_wait", None, &global).is_err());
+        assert!(overrides.set("fishing.max_cast_wait", Some(max), &global).is_err());
+        assert_eq!(overrides.apply(global).fishing.max_cast_wait, max + 10.0);
+    }
+
+    #[test]
+    fn qte_time_overrides_keep_min_below_base() {
+        let global = Config::default();
+        let base = global.fishing.base_qte_time;
+        let mut overrides = ConfigOverrides::default();
+
+        assert!(overrides.set("fishing.min_qte_time", Some(base + 1.0), &global).is_err());
+        assert!(overrides.list().is_empty());
+
+        // raising the base first makes room, but it can't be lowered while the min needs it
+        overrides.set("fishing.base_qte_time", Some(base + 10.0), &global).unwrap();
+        overrides.set("fishing.min_qte_time", Some(base + 1.0), &global).unwrap();
+        assert!(overrides.set("fishing.base_qte_time", None, &global).is_err());
+        assert!(overrides.set("fishing.base_qte_time", Some(base), &global).is_err());
+        assert_eq!(overrides.apply(global).fishing.base_qte_time, base + 10.0);
+    }
+
+    #[test]
+    fn command_rules_restrict_by_role_and_channel() {
+        let mut file = GuildFile { fishing_channels: vec![1], ..Default::default() };
+        let (member, fisher) = (RoleId::new(10), RoleId::new(20));
+
+        // without rules only the fishing channels apply
+        assert!(file.check_command("cast", &[member], ChannelId::new(1)).is_ok());
+        assert!(file.check_command("cast", &[member], ChannelId::new(2)).is_err());
+
+        // a command's channels replace the fishing channels, and its roles are required
+        file.edit_rules("cast", |rules| {
+            rules.roles.push(fisher.get());
+            rules.channels.push(2);
+        });
+        assert!(file.check_command("cast", &[member], ChannelId::new(2)).is_err());
+        assert!(file.check_command("cast", &[member, fisher], ChannelId::new(2)).is_ok());
+        assert!(file.check_command("cast", &[fisher], ChannelId::new(1)).is_err());
+
+        file.edit_rules("shop", |rules| rules.enabled = false);
+        assert!(file.check_command("shop", &[fisher], ChannelId::new(1)).is_err());
+
+        // rules that no longer restrict anything are dropped
+        file.edit_rules("shop", |rules| rules.enabled = true);
+        assert!(!file.commands.contains_key("shop"));
     }
 }
@@ -1,5 +1,42 @@
+pub mod cast_store;
 pub mod config;
 pub mod guildfile;
 pub mod monetary;
+pub mod qte_stats;
 pub mod userfile;
-pub mod version_uf_converter;
\ No newline at end of file
+pub mod version_uf_converter;
+
+use std::path::{Path, PathBuf};
+
+const DATA_DIR: &str = "./data";
+
+#[cfg(test)]
+thread_local! {
+    // tests each get their own data directory so they can run in parallel
+    static TEST_DATA_DIR: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Path to a file or folder inside the data directory
+pub fn data_path(relative: &str) -> PathBuf {
+    #[cfg(test)]
+    if let Some(dir) = TEST_DATA_DIR.with(|d| d.borrow().clone()) {
+        return dir.join(relative);
+    }
+
+    Path::new(DATA_DIR).join(relative)
+}
+
+/// Move a data file that couldn't be parsed out of the way (to `<file>.corrupt-<unix millis>`),
+/// so the next write starts a new file instead of overwriting what was in it.
+/// Returns where the file was moved.
+pub fn set_aside(relative: &str) -> std::io::Result<PathBuf> {
+    let moved = data_path(&format!("{}.corrupt-{}", relative, chrono::Utc::now().timestamp_millis()));
+    std::fs::rename(data_path(relative), &moved)?;
+    Ok(moved)
+}
+
+/// Point `data_path` at another directory for the current thread (None to go back to ./data)
+#[cfg(test)]
+pub fn set_test_data_dir(dir: Option<PathBuf>) {
+    TEST_DATA_DIR.with(|d| *d.borrow_mut() = dir);
+}
\ No newline at end of file
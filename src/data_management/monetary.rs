@@ -19,6 +19,13 @@ impl MonetaryAmount {
     pub fn get(&self) -> f32 {
         self.amount_x100 as f32 / 100.0
     }
+
+    /// Subtract another amount, returning None if it would go below zero
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.amount_x100
+            .checked_sub(other.amount_x100)
+            .map(|amount_x100| Self { amount_x100 })
+    }
 }
 
 // 1. Allows you to do: println!("You have {}", wallet); -> "You have $10.50"
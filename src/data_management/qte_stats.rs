@@ -0,0 +1,206 @@
+use std::{fs, sync::Mutex};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+
+use crate::data_management::config::AntiMacro;
+use crate::data_management::{data_path, set_aside};
+use crate::{hey, nay};
+
+const QTE_STATS_LOCATION: &str = "qte_stats.ron";
+
+// replies are recorded from many catch tasks at once, so all file access goes through this
+static QTE_STATS_LOCK: Mutex<()> = Mutex::new(());
+
+/// A player's recent QTE reply times, used to spot macros
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QteRecord {
+    pub user_id: u64,
+    /// seconds from the QTE being sent to the player's reply, most recent last
+    pub reply_times: Vec<f32>,
+    /// why the player was flagged, None if they haven't been
+    pub flag_reason: Option<String>,
+    /// unix time in milliseconds the player was flagged
+    pub flagged_at: Option<i64>,
+}
+
+/// Every player's QTE timings, stored in ./data/qte_stats.ron
+pub struct QteStats;
+
+impl QteStats {
+    /// Record how long a player took to reply to a QTE.
+    /// Returns the reason if this reply got the player flagged.
+    pub fn record(user_id: &UserId, seconds: f32, config: &AntiMacro) -> Option<String> {
+        let _lock = QTE_STATS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        // without the stats nothing is recorded, rather than losing everyone's timings
+        let mut records = Self::read()?;
+
+        let index = match records.iter().position(|r| r.user_id == user_id.get()) {
+            Some(index) => index,
+            None => {
+                records.push(QteRecord { user_id: user_id.get(), ..Default::default() });
+                records.len() - 1
+            }
+        };
+        let record = &mut records[index];
+
+        record.reply_times.push(seconds);
+        let overflow = record.reply_times.len().saturating_sub(config.reply_window.max(1));
+        record.reply_times.drain(..overflow);
+
+        // players stay flagged until an admin clears them
+        let mut newly_flagged = None;
+        if record.flag_reason.is_none()
+            && let Some(reason) = suspicious_timing(&record.reply_times, config)
+        {
+            record.flag_reason = Some(reason.clone());
+            record.flagged_at = Some(Utc::now().timestamp_millis());
+            newly_flagged = Some(reason);
+        }
+
+        Self::write(&records);
+        newly_flagged
+    }
+
+    /// every flagged player, most recently flagged first
+    pub fn flagged() -> Vec<QteRecord> {
+        let _lock = QTE_STATS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut flagged: Vec<QteRecord> = Self::read()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|r| r.flag_reason.is_some())
+            .collect();
+        flagged.sort_by_key(|r| std::cmp::Reverse(r.flagged_at));
+        flagged
+    }
+
+    /// Clear a player's flag and their timings. Returns false if they weren't flagged.
+    pub fn clear_flag(user_id: &UserId) -> bool {
+        let _lock = QTE_STATS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(mut records) = Self::read() else {
+            return false;
+        };
+        let before = records.len();
+        records.retain(|r| !(r.user_id == user_id.get() && r.flag_reason.is_some()));
+
+        if records.len() == before {
+            return false;
+        }
+
+        Self::write(&records);
+        true
+    }
+
+    /// None if the file is there but can't be read, it must not be written over then.
+    /// A file that can't be parsed is moved aside for an admin to look at, and a new one is started.
+    fn read() -> Option<Vec<QteRecord>> {
+        let raw_path = data_path(QTE_STATS_LOCATION);
+        let path = raw_path.as_path();
+        if !path.exists() {
+            return Some(Vec::new());
+        }
+
+        let data = match fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(e) => {
+                nay!("Failed to read the QTE stats, leaving it alone: {}", e);
+                return None;
+            }
+        };
+
+        match ron::from_str(&data) {
+            Ok(records) => Some(records),
+            Err(e) => match set_aside(QTE_STATS_LOCATION) {
+                Ok(moved) => {
+                    nay!("Failed to deserialize the QTE stats, moved it to {}: {}", moved.display(), e);
+                    Some(Vec::new())
+                }
+                Err(move_error) => {
+                    nay!("Failed to deserialize the QTE stats or move it aside, leaving it alone: {} ({})", e, move_error);
+                    None
+                }
+            },
+        }
+    }
+
+    fn write(records: &[QteRecord]) {
+        let Ok(data) = ron::to_string(records) else {
+            hey!("Failed to serialize the QTE stats");
+            return;
+        };
+
+        if let Err(e) = fs::write(data_path(QTE_STATS_LOCATION), data) {
+            hey!("Failed to write the QTE stats: {}", e);
+        }
+    }
+}
+
+/// Why a player's reply times look automated, if they do.
+/// People are never this fast, or this consistent, for long.
+pub fn suspicious_timing(reply_times: &[f32], config: &AntiMacro) -> Option<String> {
+    if reply_times.len() < config.min_samples.max(2) {
+        return None;
+    }
+
+    let count = reply_times.len() as f32;
+    let mean = reply_times.iter().sum::<f32>() / count;
+    let deviation = (reply_times.iter().map(|t| (t - mean).powi(2)).sum::<f32>() / count).sqrt();
+
+    if mean < config.min_average_reply {
+        return Some(format!("averaged {:.2}s over {} QTE replies", mean, reply_times.len()));
+    }
+    if deviation < config.min_reply_deviation {
+        return Some(format!(
+            "{} QTE replies all within {:.3}s of {:.2}s",
+            reply_times.len(),
+            deviation,
+            mean
+        ));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestDataDir;
+
+    #[test]
+    fn consistent_replies_get_a_player_flagged() {
+        let _dir = TestDataDir::new();
+        let config = AntiMacro::default();
+        let (bot, human) = (UserId::new(1), UserId::new(2));
+
+        let human_times = [3.1, 4.6, 2.8, 5.9, 3.7, 4.2, 6.3, 3.3];
+        for (i, seconds) in human_times.into_iter().enumerate() {
+            assert_eq!(QteStats::record(&human, seconds, &config), None);
+            // a macro replies in the same 2.5 seconds every time
+            let flagged = QteStats::record(&bot, 2.5 + (i % 2) as f32 * 0.01, &config);
+            assert_eq!(flagged.is_some(), i + 1 == config.min_samples);
+        }
+
+        let flagged = QteStats::flagged();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].user_id, bot.get());
+        assert!(flagged[0].reply_times.len() <= config.reply_window);
+
+        assert!(QteStats::clear_flag(&bot));
+        assert!(QteStats::flagged().is_empty());
+    }
+
+    #[test]
+    fn corrupt_stats_are_moved_aside_instead_of_overwritten() {
+        let dir = TestDataDir::new();
+        fs::write(data_path(QTE_STATS_LOCATION), "not qte stats").unwrap();
+
+        QteStats::record(&UserId::new(1), 3.0, &AntiMacro::default());
+
+        let kept: Vec<String> = fs::read_dir(&dir.path)
+            .unwrap()
+            .filter_map(|entry| fs::read_to_string(entry.ok()?.path()).ok())
+            .collect();
+        assert!(kept.iter().any(|data| data == "not qte stats"));
+        assert_eq!(QteStats::read().unwrap()[0].reply_times, vec![3.0]);
+    }
+}
@@ -1,17 +1,16 @@
 use std::{
     fs::{self, OpenOptions},
     io::Write,
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use serde::{Deserialize, Serialize};
-use serenity::all::UserId;
+use serenity::all::{GuildId, UserId};
 
-use crate::{data_management::monetary::MonetaryAmount, hey};
+use crate::{data_management::{data_path, monetary::MonetaryAmount}, error::ReelError, hey};
+use crate::fishing::aquarium::Aquarium;
 use crate::fishing::inventory::Inventory;
 
-const DATA_DIR: &str = "./data";
-
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct UserValues {
     // stored user values here
@@ -20,6 +19,12 @@ pub struct UserValues {
     pub caught_fish: Vec<String>,
     pub total_catches: u64,
     pub autobait: bool,
+    /// catch results only shown to the player, and kept out of the hall of fame
+    #[serde(default)]
+    pub private_catches: bool,
+    /// the fish the player has mounted, and their latest catches to choose from
+    #[serde(default)]
+    pub aquarium: Aquarium,
 }
 
 impl Default for UserValues {
@@ -30,6 +35,8 @@ impl Default for UserValues {
             caught_fish: Vec::new(),
             total_catches: 0,
             autobait: false,
+            private_catches: false,
+            aquarium: Aquarium::default(),
         }
     }
 }
@@ -37,28 +44,48 @@ impl Default for UserValues {
 #[derive(Clone)]
 pub struct UserFile {
     pub user_id: UserId,
+    /// the guild this profile belongs to, None for the player's global profile
+    pub guild: Option<GuildId>,
     pub file: UserValues,
 }
 
+/// Where a profile is stored:
+/// global profiles in `users/<id>.ron`, guild profiles in `guilds/<guild id>/users/<id>.ron`
+fn profile_path(id: &UserId, guild: Option<GuildId>) -> PathBuf {
+    match guild {
+        Some(guild) => data_path(&format!("guilds/{}/users/{}.ron", guild, id)),
+        None => data_path(&format!("users/{}.ron", id)),
+    }
+}
+
 impl UserFile {
 
     pub fn new(id: &UserId) -> Self {
         Self {
             user_id: id.clone(),
+            guild: None,
             file: UserValues::default(),
         }
     }
 
     pub fn get_path(&self) -> String {
-        format!("{}/users/{}.ron", DATA_DIR, self.user_id)
+        profile_path(&self.user_id, self.guild).to_string_lossy().into_owned()
     }
 
-    pub fn read(id: &UserId) -> Self {
+    /// Read a user's global profile, creating a fresh file for new players.
+    /// A file that exists but can't be read is an error, so it is never replaced with defaults.
+    pub fn read(id: &UserId) -> Result<Self, ReelError> {
+        Self::read_in(id, None)
+    }
+
+    /// Read a user's profile for a guild, or their global profile with None
+    pub fn read_in(id: &UserId, guild: Option<GuildId>) -> Result<Self, ReelError> {
         let default_values = UserValues::default();
 
         // create a new user file with default values
         let mut file = Self {
             user_id: id.clone(),
+            guild,
             file: default_values,
         };
 
@@ -68,72 +95,84 @@ impl UserFile {
         // check if the file exists
         if !path.exists() {
             // file doesn't exist, return default values and generate new file
-            Self::generate(id);
-            return file;
+            file.update();
+            return Ok(file);
         };
 
         // read the file
-        let Ok(data) = fs::read_to_string(path) else {
-            // invalid data, return default values
-            Self::generate(id);
-            return file;
-        };
+        let data = fs::read_to_string(path)?;
 
         file.file = ron::from_str(data.as_str())
-            .expect(format!("failed to deserialize user data with ID {}", id).as_str());
+            .map_err(|e| ReelError::FileLoadFailed(format!("user data with ID {}: {}", id, e)))?;
+        file.file.inventory.migrate();
 
-        file
+        Ok(file)
     }
 
-    fn generate(id: &UserId) {
-        let default_values = UserValues::default();
-
-        // create a new user file with default values
-        let default_file = Self {
-            user_id: id.clone(),
-            file: default_values,
-        };
+    /// check if a user has a global data file without generating one
+    pub fn exists(id: &UserId) -> bool {
+        Self::exists_in(id, None)
+    }
 
-        let raw_path = default_file.get_path();
-        let path = Path::new(raw_path.as_str());
+    /// check if a user has a profile for a guild (or a global one with None) without generating one
+    pub fn exists_in(id: &UserId, guild: Option<GuildId>) -> bool {
+        profile_path(id, guild).exists()
+    }
 
-        if path.exists() {
-            hey!("User data already exists: {}", id);
-            return;
-        };
+    /// Copy a user's global profile into a guild, so they keep their progress when
+    /// the guild switches to per-guild profiles.
+    /// Returns false if the user already has a profile in that guild and `overwrite` is off.
+    pub fn copy_to_guild(id: &UserId, guild: GuildId, overwrite: bool) -> Result<bool, ReelError> {
+        if !Self::exists(id) {
+            return Err(ReelError::FileLoadFailed(format!("no global profile for user {}", id)));
+        }
+        if Self::exists_in(id, Some(guild)) && !overwrite {
+            return Ok(false);
+        }
 
-        let Ok(mut file) = OpenOptions::new()
-            .read(false)
-            .write(true)
-            .create(true)
-            .append(false)
-            .open(path)
-        else {
-            hey!("Failed to get file for user data: {}", id);
-            return;
-        };
+        let mut profile = Self::read(id)?;
+        profile.guild = Some(guild);
+        profile.update();
+        Ok(true)
+    }
 
-        let Ok(data) = ron::to_string(&default_file.file) else {
-            hey!("Failed to serialize user data: {}", id.clone());
-            return;
+    /// list the ids of every user that has a data file
+    pub fn list_ids() -> Vec<UserId> {
+        let Ok(entries) = fs::read_dir(data_path("users")) else {
+            return Vec::new();
         };
 
-        if let Err(e) = write!(file, "{}", data) {
-            hey!("Failed to write to file for user {}: {}", id, e);
-        }
+        let mut ids = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+            .filter_map(|path| path.file_stem()?.to_str()?.parse::<u64>().ok())
+            .map(UserId::new)
+            .collect::<Vec<UserId>>();
+        ids.sort();
+        ids
     }
 
-    pub fn reload(&mut self) {
-        *self = Self::read(&self.user_id);
+    /// Read the profile from disk again, from the same guild (or the global profile) it came from
+    pub fn reload(&mut self) -> Result<(), ReelError> {
+        *self = Self::read_in(&self.user_id, self.guild)?;
+        Ok(())
     }
 
     pub fn update(&self) {
         let raw_path = self.get_path();
         let path = Path::new(raw_path.as_str());
 
-        if !path.exists() {
-            #[cfg(not(feature = "guild_relative_userdata"))]
-            Self::generate(&self.user_id);
+        // guild profile directories are made the first time a player is saved there
+        if let Some(parent) = path.parent() && let Err(e) = fs::create_dir_all(parent) {
+            hey!("Failed to create directory for user data {}: {}", &self.user_id, e);
+            return;
+        }
+
+        // serialize before the file is truncated, so a failure can't wipe the user's data
+        let Ok(data) = ron::to_string(&self.file) else {
+            hey!("Failed to serialize user data: {}", &self.user_id);
+            return;
         };
 
         let Ok(mut file) = OpenOptions::new()
@@ -148,13 +187,55 @@ impl UserFile {
             return;
         };
 
-        let Ok(data) = ron::to_string(&self.file) else {
-            hey!("Failed to serialize user data: {}", &self.user_id);
-            return;
-        };
-
         if let Err(e) = write!(file, "{}", data) {
             hey!("Failed to write to file for user {}: {}", &self.user_id, e);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestDataDir;
+
+    const USER: UserId = UserId::new(42);
+    const GUILD: GuildId = GuildId::new(7);
+
+    #[test]
+    fn guild_profiles_are_kept_apart_from_the_global_one() {
+        let _dir = TestDataDir::new();
+
+        let mut global = UserFile::read(&USER).unwrap();
+        global.file.total_catches = 12;
+        global.update();
+
+        // a guild profile starts fresh and is saved under the guild
+        let guild = UserFile::read_in(&USER, Some(GUILD)).unwrap();
+        assert_eq!(guild.file.total_catches, 0);
+        assert!(data_path("guilds/7/users/42.ron").exists());
+
+        // copying brings the global progress over, but won't replace it again without overwrite
+        assert!(UserFile::copy_to_guild(&USER, GUILD, true).unwrap());
+        assert_eq!(UserFile::read_in(&USER, Some(GUILD)).unwrap().file.total_catches, 12);
+        assert!(!UserFile::copy_to_guild(&USER, GUILD, false).unwrap());
+    }
+
+    #[test]
+    fn reloading_a_guild_profile_stays_in_the_guild() {
+        let _dir = TestDataDir::new();
+
+        let mut global = UserFile::read(&USER).unwrap();
+        global.file.total_catches = 12;
+        global.update();
+
+        let mut guild = UserFile::read_in(&USER, Some(GUILD)).unwrap();
+        guild.file.total_catches = 3;
+        guild.update();
+        guild.reload().unwrap();
+        guild.update();
+
+        assert_eq!(guild.guild, Some(GUILD));
+        assert_eq!(guild.file.total_catches, 3);
+        assert_eq!(UserFile::read_in(&USER, Some(GUILD)).unwrap().file.total_catches, 3);
+    }
+}
@@ -4,8 +4,10 @@ use serenity::all::UserId;
 use crate::data_management::monetary::MonetaryAmount;
 use crate::data_management::userfile::{UserFile, UserValues};
 use crate::fishing::bait_bucket::BaitBucket;
+use crate::fishing::gadgets;
 use crate::fishing::rod_data::RodLoadout;
-use crate::nay;
+use crate::data_management::data_path;
+use crate::{hey, nay};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct OLD_UserValues {
@@ -33,25 +35,32 @@ impl Default for OLD_UserValues {
 pub fn convert_old_userfiles() {
 
     // loop through the files in ./data/users and convert them to the new format
-    let path = std::path::Path::new("./data/users/old");
+    let path = data_path("users/old");
+
+    // nothing to convert
+    let Ok(entries) = fs::read_dir(&path) else {
+        return;
+    };
 
     // loop through all files in the directory
-    for entry in fs::read_dir(path).unwrap() {
-        let entry = entry.unwrap();
+    for entry in entries.filter_map(Result::ok) {
         let path = entry.path();
 
         if path.is_file() {
-            let user_id = path.file_stem().unwrap().to_str().unwrap().parse::<u64>().unwrap();
+            let Some(user_id) = path.file_stem().and_then(|s| s.to_str()).and_then(|s| s.parse::<u64>().ok()) else {
+                hey!("Skipping old user file with an invalid name: {}", path.display());
+                continue;
+            };
 
             let Ok(data) = fs::read_to_string(path.clone()) else {
-                // invalid data, return default values
-                return;
+                nay!("Failed to read old user file: {}", path.display());
+                continue;
             };
 
             let Ok(old_userfile) = ron::from_str::<OLD_UserValues>(&data) else {
                 // invalid data
                 nay!("Failed to parse old user file: {}", path.display());
-                return;
+                continue;
             };
 
             // convert data to new format
@@ -76,8 +85,12 @@ pub fn convert_old_userfiles() {
             file.inventory.selected_reel = 0;
             file.inventory.selected_sinker = 0;
             file.inventory.bait_bucket = old_userfile.bait_bucket;
-            file.inventory.depth_finder = loadout.has_depth_finder;
-            file.inventory.underwater_cam = loadout.has_underwater_camera;
+            if loadout.has_depth_finder {
+                file.inventory.gadgets.push(gadgets::DEPTH_FINDER.to_string());
+            }
+            if loadout.has_underwater_camera {
+                file.inventory.gadgets.push(gadgets::UNDERWATER_CAMERA.to_string());
+            }
 
             new_userfile.update();
 
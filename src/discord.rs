@@ -1,36 +1,75 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::Mutex;
 use crate::{
     commands::{
-        CommandData, command_response_ephemeral, error_command_response,
-        get_all_cmds, register_command,
+        BotCommand, CommandData, command_response_ephemeral,
+        get_all_cmds, register_command, respond_with_error,
+        cast::resume_casts,
+        cooldown::{Cooldowns, cooldown_message},
+        components::{ComponentData, ID_SEPARATOR, component_response_ephemeral, get_all_components},
     },
-    helpers::generate_error_code,
-    nay, yay,
+    error::ReelError,
+    yay,
 };
 use serenity::{
     all::{
-        ActivityData, Command, Context, EventHandler, Interaction, Message, OnlineStatus, Ready,
-        ResumedEvent,
+        ActivityData, ChannelId, Context, EventHandler, Interaction, Member, Message,
+        OnlineStatus, Permissions, Ready, ResumedEvent,
     },
     async_trait,
 };
 use serenity::all::UserId;
-use crate::data_management::guildfile::GuildSettings;
+use crate::data_management::guildfile::{EffectiveSettings, GuildSettings};
 
 pub struct Handler {
-    pub users_fishing: Arc<Mutex<HashSet<UserId>>>,
+    // users with an active cast, and the flag that cancels it
+    pub users_fishing: Arc<Mutex<HashMap<UserId, Arc<AtomicBool>>>>,
+    // ready fires again on reconnects, stored casts must only be resumed once
+    casts_resumed: AtomicBool,
+    cooldowns: Cooldowns,
 }
 
 impl Handler {
     pub fn new() -> Self {
         Self {
-            users_fishing: Arc::new(Mutex::new(HashSet::new())),
+            users_fishing: Arc::new(Mutex::new(HashMap::new())),
+            casts_resumed: AtomicBool::new(false),
+            cooldowns: Cooldowns::default(),
         }
     }
 }
 
+/// Check a command (or a component on its message) against the member's permissions and the guild's rules
+/// (fishing channels, disabled commands, role and channel restrictions).
+/// Returns the message to show the member if they can't run it.
+fn check_access(cmd: &dyn BotCommand, member: Option<&Member>, channel: ChannelId, guild: Option<&GuildSettings>) -> Result<(), String> {
+    // there are no guild rules in DMs
+    let (Some(guild), Some(member)) = (guild, member) else {
+        return Ok(());
+    };
+
+    if cmd.is_admin() || cmd.required_permissions().is_some() {
+        let required = cmd.required_permissions().unwrap_or(Permissions::ADMINISTRATOR);
+        let permissions = member.permissions.unwrap_or_default();
+        let allowed = permissions.administrator()
+            || permissions.contains(required)
+            || guild.file.is_manager(&member.roles);
+
+        if !allowed {
+            return Err("You don't have permission to use that command!".to_string());
+        }
+
+        // admin commands ignore the guild's command rules, so a server can't lock itself out
+        if cmd.is_admin() {
+            return Ok(());
+        }
+    }
+
+    guild.file.check_command(cmd.name(), &member.roles, channel)
+}
+
 #[async_trait]
 impl EventHandler for Handler {
     async fn message(&self, _: Context, msg: Message) {
@@ -64,6 +103,11 @@ impl EventHandler for Handler {
         // Log that the bot is ready
         yay!("{} is connected!", ready.user.name);
 
+        // finish any casts that were in flight when the bot last stopped
+        if !self.casts_resumed.swap(true, Ordering::SeqCst) {
+            resume_casts(&ctx, self.users_fishing.clone()).await;
+        }
+
         // set bot activity
         if debug {
             ctx.set_presence(
@@ -102,6 +146,9 @@ impl EventHandler for Handler {
                 // get the name of the command
                 let command_name = &command.data.name;
 
+                // the guild's settings, checked after the data is built so a bad file can be reported
+                let guild = command.guild_id.as_ref().map(GuildSettings::get).transpose();
+
                 // build the data
                 let cmd_data = CommandData {
                     command_name: command_name.clone(),
@@ -112,6 +159,7 @@ impl EventHandler for Handler {
                     guild_id: command.guild_id.as_ref(),
                     command_options: command.data.options(),
                     channel: command.channel_id,
+                    settings: EffectiveSettings::resolve(guild.as_ref().ok().and_then(Option::as_ref)),
                 };
 
                 // Find the command to run
@@ -125,34 +173,83 @@ impl EventHandler for Handler {
                     }
 
                     // guild permissions check
-                    if let Some(guild_id) = command.guild_id {
-                        let guildfile = GuildSettings::get(&guild_id);
-                        if !guildfile.file.fishing_channels.is_empty()
-                            && !guildfile.file.fishing_channels.contains(&command.channel_id.get()) && !cmd.is_admin() {
-                            command_response_ephemeral(&ctx, &command, "This channel is not allowed for fishing commands!").await;
+                    let guild = match guild {
+                        Ok(guild) => guild,
+                        Err(e) => {
+                            respond_with_error(&cmd_data, e.into()).await;
+                            return;
+                        }
+                    };
+                    if let Err(msg) = check_access(cmd.as_ref(), command.member.as_deref(), command.channel_id, guild.as_ref()) {
+                        command_response_ephemeral(&ctx, &command, msg).await;
+                        return;
+                    }
+
+                    // cooldowns, the config can change how long a command's cooldown is
+                    if let Some(mut cooldown) = cmd.cooldown() {
+                        if let Some(seconds) = cmd_data.settings.config.cooldowns.get(cmd.name()) {
+                            cooldown.seconds = *seconds;
+                        }
+                        if let Err(remaining) = self.cooldowns.try_use(cmd.name(), cooldown, command.user.id, command.guild_id) {
+                            command_response_ephemeral(&ctx, &command, cooldown_message(cmd.name(), cooldown.scope, remaining)).await;
                             return;
                         }
                     }
 
                     // run
                     if let Err(e) = cmd.run(&cmd_data).await {
-                        command_response_ephemeral(&ctx, &command, e).await;
+                        respond_with_error(&cmd_data, e).await;
                     }
                 } else {
                     // command not found (shouldn't happen)
-                    let error_code = format!("CMD_NOT_FOUND-{}", generate_error_code());
-                    nay!(
-                        "Unknown Command Run: {} CODE: {}",
-                        command_name,
-                        error_code.clone()
-                    );
-                    error_command_response(&ctx, &command, error_code).await;
-                    return;
+                    respond_with_error(&cmd_data, ReelError::UnknownCommand(command_name.to_string()).into()).await;
                 }
             }
 
-            Interaction::Component(_component_interaction) => {
-                // TODO: handle component interactions (buttons, select menus, etc.)
+            Interaction::Component(component) => {
+                let custom_id = component.data.custom_id.as_str();
+                let mut parts = custom_id.split(ID_SEPARATOR);
+                let prefix = parts.next().unwrap_or_default();
+
+                // components without a registered handler are owned by a collector
+                // (or are from an old version of the bot), so leave them alone
+                let components = get_all_components();
+                let Some(handler) = components.iter().find(|c| c.prefix() == prefix) else {
+                    return;
+                };
+
+                let guild = component.guild_id.as_ref().map(GuildSettings::get).transpose();
+
+                let data = ComponentData {
+                    handler: self,
+                    ctx: &ctx,
+                    interaction: &component,
+                    sender: &component.user,
+                    args: parts.collect(),
+                    settings: EffectiveSettings::resolve(guild.as_ref().ok().and_then(Option::as_ref)),
+                };
+
+                // a guild whose settings can't be read can't tell us which profile to use
+                let guild = match guild {
+                    Ok(guild) => guild,
+                    Err(e) => {
+                        data.respond_with_error(e.into()).await;
+                        return;
+                    }
+                };
+
+                // components follow the rules of their command, so e.g. a turned off /shop can't be used from an old message
+                let commands = get_all_cmds();
+                if let Some(cmd) = commands.iter().find(|c| c.name() == handler.command())
+                    && let Err(msg) = check_access(cmd.as_ref(), component.member.as_ref(), component.channel_id, guild.as_ref())
+                {
+                    component_response_ephemeral(&ctx, &component, msg).await;
+                    return;
+                }
+
+                if let Err(e) = handler.handle(&data).await {
+                    data.respond_with_error(e).await;
+                }
             }
 
             _ => {}
@@ -11,7 +11,12 @@ pub enum ReelError {
     RandomError(String),
     FileLoadFailed(String),
     HttpError(HttpError),
-    Error(String),
+    /// discord errors that didn't come from an http request (gateway, model, collector, ...)
+    Discord(Box<serenity::Error>),
+    /// data that couldn't be turned into ron or json
+    Serialize(String),
+    /// a command discord knows about that isn't registered with the bot
+    UnknownCommand(String),
 }
 
 impl Display for ReelError {
@@ -22,7 +27,9 @@ impl Display for ReelError {
             ReelError::RandomError(err) => write!(f, "Random Error: {}", err),
             ReelError::FileLoadFailed(err) => write!(f, "Failed To Load: {}", err),
             ReelError::HttpError(err) => write!(f, "Http Error: {}", err),
-            ReelError::Error(e) => write!(f, "Error: {}", e),
+            ReelError::Discord(err) => write!(f, "Discord Error: {}", err),
+            ReelError::Serialize(err) => write!(f, "Failed To Serialize: {}", err),
+            ReelError::UnknownCommand(name) => write!(f, "Unknown Command: {}", name),
         }
     }
 }
@@ -48,22 +55,93 @@ impl From<HttpError> for ReelError {
     }
 }
 
-// Helper to allow `?` on any error that can be converted to a string
-impl From<String> for ReelError {
-    fn from(e: String) -> Self {
-        ReelError::Error(e)
+// Helper to allow `?` on serenity's errors
+impl From<serenity::Error> for ReelError {
+    fn from(e: serenity::Error) -> Self {
+        match e {
+            serenity::Error::Http(e) => ReelError::HttpError(e),
+            e => ReelError::Discord(Box::new(e)),
+        }
+    }
+}
+
+impl ReelError {
+    /// prefix for the error code a player is given when this error reaches them
+    pub fn code(&self) -> &'static str {
+        match self {
+            ReelError::IOError(_) => "IO_ERR",
+            ReelError::MathError(_) => "MATH_ERR",
+            ReelError::RandomError(_) => "RNG_ERR",
+            ReelError::FileLoadFailed(_) => "DATA_LOAD",
+            ReelError::HttpError(_) => "HTTP_ERR",
+            ReelError::Discord(_) => "DISCORD_ERR",
+            ReelError::Serialize(_) => "SERIALIZE",
+            ReelError::UnknownCommand(_) => "CMD_NOT_FOUND",
+        }
+    }
+}
+
+/// Why a command (or a button press) failed.
+/// Player errors are shown to the player as they are; internal errors are logged
+/// and the player is given a themed message with an error code to report.
+#[derive(Debug)]
+pub enum CommandError {
+    /// the player asked for something they can't do (not enough money, invalid selection, ...)
+    Player(String),
+    /// something broke on our end, `code` is the error code prefix (e.g. FISH_FTG)
+    Internal { code: &'static str, error: ReelError },
+}
+
+impl CommandError {
+    pub fn internal(code: &'static str, error: impl Into<ReelError>) -> Self {
+        CommandError::Internal { code, error: error.into() }
     }
 }
 
-impl Into<String> for ReelError {
-    fn into(self) -> String {
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            ReelError::IOError(err) => format!("IO Error: {}", err),
-            ReelError::MathError(err) => format!("Math Error: {}", err),
-            ReelError::RandomError(err) => format!("Random Error: {}", err),
-            ReelError::FileLoadFailed(err) => format!("Failed To Load: {}", err),
-            ReelError::HttpError(err) => format!("Http Error: {}", err),
-            ReelError::Error(e) => format!("Error: {}", e),
+            CommandError::Player(msg) => write!(f, "{}", msg),
+            CommandError::Internal { code, error } => write!(f, "{}: {}", code, error),
         }
     }
-}
\ No newline at end of file
+}
+
+// player facing messages, e.g. from argument parsing or `return Err("Invalid selection.".into())`
+impl From<String> for CommandError {
+    fn from(msg: String) -> Self {
+        CommandError::Player(msg)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(msg: &str) -> Self {
+        CommandError::Player(msg.to_string())
+    }
+}
+
+impl From<ReelError> for CommandError {
+    fn from(error: ReelError) -> Self {
+        CommandError::Internal { code: error.code(), error }
+    }
+}
+
+impl From<serenity::Error> for CommandError {
+    fn from(error: serenity::Error) -> Self {
+        ReelError::from(error).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn player_messages_and_internal_errors_stay_separate() {
+        let player: CommandError = "Invalid selection.".into();
+        assert!(matches!(player, CommandError::Player(ref msg) if msg == "Invalid selection."));
+
+        let internal: CommandError = ReelError::FileLoadFailed("users/1.ron".to_string()).into();
+        assert!(matches!(internal, CommandError::Internal { code: "DATA_LOAD", .. }));
+    }
+}
@@ -0,0 +1,157 @@
+// the aquarium is where players show off their best catches
+// fish are mounted from the player's recent catches, and the tank earns a little money while they're away
+
+use serde::{Deserialize, Serialize};
+
+use crate::data_management::config::AquariumConfig;
+use crate::data_management::monetary::MonetaryAmount;
+use crate::fishing::fish_data::fish::Fish;
+use crate::fishing::fish_data::rarity::FishRarity;
+
+/// how many of a player's latest catches are kept to be mounted
+pub const RECENT_CATCHES: usize = 10;
+
+const HOUR_MILLIS: f32 = 3_600_000.0;
+
+/// A caught fish, as it was when it came out of the water
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trophy {
+    pub name: String,
+    pub rarity: FishRarity,
+    /// size in inches
+    pub size: f32,
+    /// weight in pounds
+    pub weight: f32,
+    /// the depth in feet it was caught at
+    pub depth: f32,
+    pub value: MonetaryAmount,
+    /// unix time in milliseconds it was caught
+    pub caught_at: i64,
+}
+
+impl Trophy {
+    pub fn from_catch(fish: &Fish, caught_at: i64) -> Self {
+        Self {
+            name: fish.fish_type.name.clone(),
+            rarity: fish.fish_type.rarity.clone(),
+            size: fish.size,
+            weight: fish.weight,
+            depth: fish.depth,
+            value: fish.value.clone(),
+            caught_at,
+        }
+    }
+
+    /// The day it was caught, e.g. 2025-06-01
+    pub fn caught_on(&self) -> String {
+        chrono::DateTime::from_timestamp_millis(self.caught_at)
+            .map_or("unknown".to_string(), |t| t.format("%Y-%m-%d").to_string())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Aquarium {
+    /// the fish on display
+    pub trophies: Vec<Trophy>,
+    /// the player's latest catches, newest first
+    pub recent: Vec<Trophy>,
+    /// unix time in milliseconds the tank's income was last paid out, None before anything was mounted
+    pub last_collected: Option<i64>,
+}
+
+impl Aquarium {
+    /// Remember a catch so it can be mounted later, forgetting the oldest past `RECENT_CATCHES`
+    pub fn record_catch(&mut self, trophy: Trophy) {
+        self.recent.insert(0, trophy);
+        self.recent.truncate(RECENT_CATCHES);
+    }
+
+    /// Move one of the recent catches into the tank
+    pub fn mount(&mut self, index: usize, capacity: usize) -> Result<&Trophy, String> {
+        if index >= self.recent.len() {
+            return Err("You don't have a recent catch with that number, check `/trophy catches`.".to_string());
+        }
+        if self.trophies.len() >= capacity {
+            return Err(format!("Your aquarium is full! It holds {} fish, release one with `/trophy release` first.", capacity));
+        }
+        self.trophies.push(self.recent.remove(index));
+        Ok(&self.trophies[self.trophies.len() - 1])
+    }
+
+    /// Take a fish out of the tank for good
+    pub fn release(&mut self, index: usize) -> Result<Trophy, String> {
+        if index >= self.trophies.len() {
+            return Err("There's no fish with that number in your aquarium.".to_string());
+        }
+        Ok(self.trophies.remove(index))
+    }
+
+    /// What the tank earns an hour. Each species on display earns a share of its most valuable fish,
+    /// so a second fish of the same species only shows off.
+    pub fn hourly_income(&self, config: &AquariumConfig) -> f32 {
+        let mut best: Vec<(&str, f32)> = Vec::new();
+        for trophy in &self.trophies {
+            let value = trophy.value.get();
+            match best.iter_mut().find(|(name, _)| *name == trophy.name) {
+                Some((_, best_value)) => *best_value = best_value.max(value),
+                None => best.push((&trophy.name, value)),
+            }
+        }
+        best.iter().map(|(_, value)| value * config.income_rate).sum()
+    }
+
+    /// Pay out what the tank earned since it was last collected, up to `max_income_hours` of it.
+    /// Call this before the fish on display change, so the old ones are paid for their time.
+    pub fn collect(&mut self, now: i64, config: &AquariumConfig) -> MonetaryAmount {
+        let hours = self
+            .last_collected
+            .map_or(0.0, |last| (now - last).max(0) as f32 / HOUR_MILLIS)
+            .min(config.max_income_hours);
+        self.last_collected = Some(now);
+        MonetaryAmount::new(self.hourly_income(config) * hours)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trophy(name: &str, value: f32) -> Trophy {
+        Trophy {
+            name: name.to_string(),
+            rarity: FishRarity::Common,
+            size: 1.0,
+            weight: 1.0,
+            depth: 10.0,
+            value: MonetaryAmount::new(value),
+            caught_at: 0,
+        }
+    }
+
+    #[test]
+    fn mounted_species_earn_while_away() {
+        let config = AquariumConfig { capacity: 3, income_rate: 0.1, max_income_hours: 24.0 };
+        let mut aquarium = Aquarium::default();
+        for _ in 0..RECENT_CATCHES + 2 {
+            aquarium.record_catch(trophy("Minnow", 10.0));
+        }
+        aquarium.record_catch(trophy("Minnow", 20.0));
+        aquarium.record_catch(trophy("Carp", 50.0));
+        assert_eq!(aquarium.recent.len(), RECENT_CATCHES);
+
+        // nothing is owed before the first collect
+        assert_eq!(aquarium.collect(0, &config).get(), 0.0);
+        for _ in 0..3 {
+            aquarium.mount(0, config.capacity).unwrap();
+        }
+        assert!(aquarium.mount(0, config.capacity).is_err());
+
+        // a second minnow doesn't earn, so an hour is a carp and the best minnow, and a day at most
+        let hour = HOUR_MILLIS as i64;
+        assert_eq!(aquarium.collect(hour, &config).get(), 7.0);
+        assert_eq!(aquarium.collect(hour * 100, &config).get(), 7.0 * 24.0);
+
+        assert_eq!(aquarium.release(0).unwrap().name, "Carp");
+        assert!(aquarium.release(2).is_err());
+    }
+}
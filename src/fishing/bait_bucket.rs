@@ -1,37 +1,157 @@
 use serde::{Deserialize, Serialize};
 use crate::fishing::rod_data::bait::Bait;
 
+/// Some number of the same bait
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaitStack {
+    pub bait: Bait,
+    pub count: u32,
+}
+
+impl BaitStack {
+    /// Whether a bait belongs in this stack, baits with the same name and description are the same bait
+    pub fn holds(&self, bait: &Bait) -> bool {
+        self.bait.name == bait.name
+            && self.bait.description == bait.description
+            && self.bait.reusable == bait.reusable
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(from = "BaitBucketData")]
 pub struct BaitBucket {
-    pub baits: Vec<Bait>,
+    pub stacks: Vec<BaitStack>,
+}
+
+/// What's stored on disk: buckets from before stacking kept one entry per bait in `baits`
+#[derive(Deserialize)]
+struct BaitBucketData {
+    #[serde(default)]
+    stacks: Vec<BaitStack>,
+    #[serde(default)]
+    baits: Vec<Bait>,
+}
+
+impl From<BaitBucketData> for BaitBucket {
+    fn from(data: BaitBucketData) -> Self {
+        // old baits become stacks of one, so indices into the bucket still point at the same bait.
+        // `Inventory::migrate` merges them when the profile is loaded
+        let mut stacks = data.stacks;
+        stacks.extend(data.baits.into_iter().map(|bait| BaitStack { bait, count: 1 }));
+        Self { stacks }
+    }
 }
 
 impl BaitBucket {
     pub fn new() -> Self {
-        Self { baits: Vec::new() }
+        Self { stacks: Vec::new() }
     }
 
     pub fn add(&mut self, bait: Bait) {
-        self.baits.push(bait);
+        self.add_many(bait, 1);
+    }
+
+    /// Add some of a bait, onto its stack if there is one. Returns the stack's index.
+    pub fn add_many(&mut self, bait: Bait, count: u32) -> usize {
+        if let Some(index) = self.position(&bait) {
+            self.stacks[index].count += count;
+            return index;
+        }
+
+        self.stacks.push(BaitStack { bait, count });
+        self.stacks.len() - 1
     }
 
-    pub fn remove_index(&mut self, index: usize) -> Option<Bait> {
-        if index < self.baits.len() {
-            Some(self.baits.remove(index))
+    /// Take one bait off a stack, removing the stack if it was the last one
+    pub fn take_one(&mut self, index: usize) -> Option<Bait> {
+        let stack = self.stacks.get_mut(index)?;
+        if stack.count > 1 {
+            stack.count -= 1;
+            return Some(stack.bait.clone());
+        }
+        self.remove_index(index).map(|stack| stack.bait)
+    }
+
+    /// Remove a whole stack
+    pub fn remove_index(&mut self, index: usize) -> Option<BaitStack> {
+        if index < self.stacks.len() {
+            Some(self.stacks.remove(index))
         } else {
             None
         }
     }
 
+    /// Merge stacks of the same bait into the first of them
+    pub fn merge(&mut self) {
+        for stack in std::mem::take(&mut self.stacks) {
+            self.add_many(stack.bait, stack.count);
+        }
+    }
+
+    /// The index of the stack a bait belongs in
+    pub fn position(&self, bait: &Bait) -> Option<usize> {
+        self.stacks.iter().position(|s| s.holds(bait))
+    }
+
     pub fn get(&self, index: usize) -> Option<&Bait> {
-        self.baits.get(index)
+        self.stacks.get(index).map(|s| &s.bait)
     }
 
+    /// How many baits are on a stack, 0 if there's no stack there
+    pub fn count(&self, index: usize) -> u32 {
+        self.stacks.get(index).map_or(0, |s| s.count)
+    }
+
+    /// The number of stacks
     pub fn len(&self) -> usize {
-        self.baits.len()
+        self.stacks.len()
+    }
+
+    /// The number of baits across every stack
+    pub fn total(&self) -> u32 {
+        self.stacks.iter().map(|s| s.count).sum()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.baits.is_empty()
+        self.stacks.is_empty()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fishing::inventory::Inventory;
+    use crate::fishing::rod_data::bait::BaitPotency;
+
+    #[test]
+    fn old_buckets_merge_into_stacks() {
+        let mut rng = rand::rng();
+        let worm = Bait::generate(BaitPotency::Low, false, &mut rng);
+        let mut cricket = Bait::generate(BaitPotency::Low, false, &mut rng);
+        cricket.name = format!("Not {}", worm.name);
+
+        // one entry per bait, with the second worm selected
+        let old = format!("(baits: {})", ron::to_string(&vec![&worm, &cricket, &worm]).unwrap());
+        let mut inventory = Inventory {
+            bait_bucket: ron::from_str(&old).unwrap(),
+            selected_bait: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(inventory.bait_bucket.len(), 3);
+
+        inventory.merge_bait_stacks();
+        assert_eq!(inventory.bait_bucket.len(), 2);
+        assert_eq!(inventory.bait_bucket.count(0), 2);
+        assert_eq!(inventory.selected_bait, Some(0));
+
+        // AutoBait keeps using the stack until it runs out
+        inventory.use_selected_bait(true);
+        assert_eq!(inventory.selected_bait, Some(0));
+        inventory.use_selected_bait(true);
+        assert_eq!(inventory.selected_bait, None);
+        assert_eq!(inventory.bait_bucket.get(0).map(|b| b.name.clone()), Some(cricket.name.clone()));
+
+        assert_eq!(inventory.bait_bucket.add_many(cricket, 4), 0);
+        assert_eq!(inventory.bait_bucket.total(), 5);
+    }
+}
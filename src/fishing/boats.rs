@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// how deep a line can go cast from the shore, without a boat
+pub const SHORE_MAX_DEPTH: f32 = 200.0;
+
+/// Boats take the angler out to deeper water. A player has at most one, a new boat replaces the old one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Boat {
+    pub name: String,
+    pub description: String,
+    pub price: f32,
+    /// the deepest a sinker can reach from this boat, in feet
+    pub max_depth: f32,
+    /// extra baits the bait bucket holds
+    pub storage: u32,
+}
+
+/// The deepest a cast can reach with or without a boat
+pub fn max_depth(boat: Option<&Boat>) -> f32 {
+    boat.map_or(SHORE_MAX_DEPTH, |b| b.max_depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cast::{ROLL_STREAM, cast_rng};
+    use crate::fishing::Attribute;
+    use crate::fishing::rod_data::RodLoadout;
+
+    #[test]
+    fn boats_let_deep_sinkers_reach_deeper() {
+        let mut rng = cast_rng(11, ROLL_STREAM);
+        let mut loadout = RodLoadout::default();
+        loadout.sinker.depth_range = Attribute { min: 800.0, max: 2500.0, average: 1600.0 };
+
+        assert_eq!(loadout.generate_depth(None, &mut rng).unwrap(), SHORE_MAX_DEPTH);
+        assert!(loadout.cut_short(SHORE_MAX_DEPTH));
+
+        loadout.boat = Some(Boat {
+            name: "Rowboat".to_string(),
+            description: String::new(),
+            price: 0.0,
+            max_depth: 1000.0,
+            storage: 0,
+        });
+        for _ in 0..20 {
+            let depth = loadout.generate_depth(None, &mut rng).unwrap();
+            assert!((800.0..=1000.0).contains(&depth), "cast to {}", depth);
+        }
+        assert!(!loadout.cut_short(900.0));
+    }
+}
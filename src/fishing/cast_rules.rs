@@ -0,0 +1,21 @@
+// Rules every cast follows, shared by /cast and the simulator so balance runs fish the same way players do.
+
+use rand::Rng;
+
+/// Casts that take at least this long (in seconds) are long casts
+pub const LONG_CAST_SECS: f32 = 90.0;
+
+/// Whether a cast of this many seconds is a long cast
+pub fn is_long_cast(cast_time: f32) -> bool {
+    cast_time >= LONG_CAST_SECS
+}
+
+/// A long cast the fish didn't bite on has a 20% chance to hook it anyway
+pub fn long_cast_hooks(rng: &mut impl Rng) -> bool {
+    rng.random_range(0..100) < 20
+}
+
+/// Single use bait has a 1 in 20 chance to survive a catch attempt
+pub fn keeps_bait(rng: &mut impl Rng) -> bool {
+    rng.random_range(0..20) == 7
+}
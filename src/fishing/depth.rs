@@ -30,6 +30,11 @@ impl Depth {
         }
     }
 
+    /// The depth category with this name, e.g. `MidWater`
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::iter().find(|d| format!("{:?}", d).eq_ignore_ascii_case(name))
+    }
+
     /// Get the depth category from a given depth in feet
     pub fn from_depth(depth: f32) -> Self {
         match depth {
@@ -43,9 +48,8 @@ impl Depth {
 
     /// Get a random depth in feet within the range of the given depth category
     /// Will give a random depth in feet within 2 decimal places of precision
-    pub fn random_depth(&self) -> f32 {
+    pub fn random_depth(&self, rng: &mut impl Rng) -> f32 {
         let (min, max) = self.get_range();
-        let mut rng = rand::rng();
         let depth = rng.random_range(min..=max);
         (depth * 100.0).round() / 100.0
     }
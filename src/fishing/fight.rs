@@ -0,0 +1,231 @@
+// the fight with a big fish once it's hooked
+// each round the fish shows its next move, the player answers with one of their own,
+// and the line's tension and the fish's stamina change with both
+
+use rand::Rng;
+
+use crate::data_management::config::FightConfig;
+use crate::fishing::fish_data::fish::Fish;
+use crate::fishing::rod_data::RodLoadout;
+
+/// the line snaps at this tension
+pub const MAX_TENSION: f32 = 100.0;
+/// a fish starts the fight with this much stamina
+pub const MAX_STAMINA: f32 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FightAction {
+    Reel,
+    EaseOff,
+    Hold,
+}
+
+impl FightAction {
+    pub const ALL: &[FightAction] = &[FightAction::Reel, FightAction::EaseOff, FightAction::Hold];
+
+    /// used in button ids
+    pub fn id(&self) -> &'static str {
+        match self {
+            FightAction::Reel => "reel",
+            FightAction::EaseOff => "ease",
+            FightAction::Hold => "hold",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|a| a.id() == id)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FightAction::Reel => "🔄 Reel",
+            FightAction::EaseOff => "🪢 Ease Off",
+            FightAction::Hold => "✊ Hold",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FishMove {
+    /// pulls hard, tension rises a lot
+    Run,
+    /// pulls a little
+    Thrash,
+    /// stops pulling, the line goes slack
+    Rest,
+}
+
+impl FishMove {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            FishMove::Run => "The fish is gearing up for a run!",
+            FishMove::Thrash => "The fish is thrashing about.",
+            FishMove::Rest => "The fish is slowing down...",
+        }
+    }
+
+    fn roll(rng: &mut impl Rng) -> Self {
+        match rng.random_range(0..10) {
+            0..4 => FishMove::Run,
+            4..7 => FishMove::Thrash,
+            _ => FishMove::Rest,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FightOutcome {
+    Landed,
+    /// the tension got too high
+    Snapped,
+    /// the line went slack
+    Escaped,
+    /// the fish was still going after the last round
+    Outlasted,
+}
+
+pub struct Fight {
+    /// 0 to MAX_TENSION
+    pub tension: f32,
+    /// the fish's stamina, it's landed at 0
+    pub stamina: f32,
+    /// rounds played so far
+    pub round: u32,
+    /// what the fish will do this round
+    pub next_move: FishMove,
+    /// how much tension the fish adds when it runs
+    pull: f32,
+    /// how much stamina reeling takes from the fish
+    reel_power: f32,
+    max_rounds: u32,
+}
+
+impl Fight {
+    /// Whether a hooked fish is big enough to fight.
+    /// Fish over the loadout's strength have a QTE instead.
+    pub fn should_fight(fish: &Fish, loadout: &RodLoadout, config: &FightConfig) -> bool {
+        let excess = loadout.qte_excess(fish);
+        excess <= 1.0
+            && (excess >= config.min_weight_ratio
+                || fish.category.fight_multiplier() >= config.min_fight_multiplier)
+    }
+
+    pub fn new(fish: &Fish, loadout: &RodLoadout, config: &FightConfig, rng: &mut impl Rng) -> Self {
+        // a fish close to the line's limit pulls harder, and stronger categories fight harder still
+        let excess = loadout.qte_excess(fish).clamp(0.0, 1.0);
+        let fight = fish.category.fight_multiplier().max(0.1).sqrt();
+
+        Self {
+            tension: MAX_TENSION / 2.0,
+            stamina: MAX_STAMINA,
+            round: 0,
+            next_move: FishMove::roll(rng),
+            pull: config.base_pull * fight * (0.5 + excess),
+            reel_power: config.base_reel_power * loadout.total_speed_multiplier() / fight,
+            max_rounds: config.max_rounds.max(1),
+        }
+    }
+
+    /// Play a round with the player's action against the fish's next move.
+    /// Returns the outcome if the fight is over.
+    pub fn step(&mut self, action: FightAction, rng: &mut impl Rng) -> Option<FightOutcome> {
+        self.round += 1;
+
+        // running wears the fish out, resting lets it get its strength back
+        let (pull, tiring) = match self.next_move {
+            FishMove::Run => (self.pull * 1.5, 8.0),
+            FishMove::Thrash => (self.pull * 0.5, 3.0),
+            FishMove::Rest => (-self.pull * 0.5, -4.0),
+        };
+        self.tension += pull;
+        self.stamina -= tiring;
+
+        match action {
+            FightAction::Reel => {
+                self.tension += 15.0;
+                self.stamina -= self.reel_power * 2.0;
+            }
+            FightAction::Hold => {
+                self.tension += 5.0;
+                self.stamina -= self.reel_power * 0.5;
+            }
+            FightAction::EaseOff => self.tension -= 20.0,
+        }
+
+        self.tension = self.tension.clamp(0.0, MAX_TENSION);
+        self.stamina = self.stamina.clamp(0.0, MAX_STAMINA);
+        self.next_move = FishMove::roll(rng);
+
+        if self.tension >= MAX_TENSION {
+            Some(FightOutcome::Snapped)
+        } else if self.tension <= 0.0 {
+            Some(FightOutcome::Escaped)
+        } else if self.stamina <= 0.0 {
+            Some(FightOutcome::Landed)
+        } else if self.round >= self.max_rounds {
+            Some(FightOutcome::Outlasted)
+        } else {
+            None
+        }
+    }
+
+    /// What a careful player would do this round: ease off before a run or when the line is tight,
+    /// and reel while the line is slack. Used by the balance simulator.
+    pub fn careful_move(&self) -> FightAction {
+        match self.next_move {
+            FishMove::Run if self.tension > 30.0 => FightAction::EaseOff,
+            _ if self.tension > 65.0 => FightAction::EaseOff,
+            _ if self.tension < 30.0 => FightAction::Reel,
+            FishMove::Rest => FightAction::Reel,
+            _ => FightAction::Hold,
+        }
+    }
+}
+
+/// a text meter like `[██████░░░░] 60%`
+pub fn meter(value: f32, max: f32) -> String {
+    let filled = ((value / max).clamp(0.0, 1.0) * 10.0).round() as usize;
+    format!("[{}{}] {:.0}%", "█".repeat(filled), "░".repeat(10 - filled), value / max * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fishing::fish_data::fish::FishCategory;
+    use crate::testing::test_fish;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn play(policy: impl Fn(&Fight) -> FightAction, seed: u64) -> FightOutcome {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut fish = test_fish(2.0, 10.0);
+        fish.category = FishCategory::Apex;
+
+        let mut fight = Fight::new(&fish, &RodLoadout::default(), &FightConfig::default(), &mut rng);
+        loop {
+            if let Some(outcome) = fight.step(policy(&fight), &mut rng) {
+                return outcome;
+            }
+        }
+    }
+
+    #[test]
+    fn reading_the_fish_wins_the_fight() {
+        let mut fish = test_fish(0.1, 10.0);
+        let config = FightConfig::default();
+        assert!(!Fight::should_fight(&fish, &RodLoadout::default(), &config));
+        fish.category = FishCategory::Apex;
+        assert!(Fight::should_fight(&fish, &RodLoadout::default(), &config));
+
+        let mut landed = 0;
+        for seed in 0..20 {
+            assert_eq!(play(|_| FightAction::Reel, seed), FightOutcome::Snapped);
+            assert_eq!(play(|_| FightAction::EaseOff, seed), FightOutcome::Escaped);
+            if play(Fight::careful_move, seed) == FightOutcome::Landed {
+                landed += 1;
+            }
+        }
+        // an apex fish on starter gear still gets away sometimes
+        assert!(landed >= 12, "only landed {} of 20", landed);
+    }
+}
@@ -2,6 +2,7 @@
 
 use std::fmt::Display;
 use crate::data_management::config::{Config, ValueCalculationType};
+use crate::data_management::data_path;
 use crate::fishing::Attribute;
 use crate::fishing::fish_data::rarity::FishRarity;
 use crate::fishing::rod_data::RodLoadout;
@@ -19,7 +20,7 @@ pub enum FishCategory {
     Schooling,
     /// Aggressive fish that are harder to catch (higher line snap chance, requires stronger lines and rods)
     Predatory,
-    /// Fish that are more likely to be caught if your sinker is near the bottom of the depth category
+    /// Fish that are more likely to be caught if your sinker is near the bottom of its range
     BottomFeeder,
     /// Trophy fish that are rare and valuable, but harder to catch
     Ornamental,
@@ -106,24 +107,30 @@ impl FishType {
         attr.average += shift;
     }
 
-    pub fn generate_fish(&self, caught_depth: f32, bait: Option<&Bait>) -> Result<Fish, ReelError> {
+    pub fn generate_fish(
+        &self,
+        caught_depth: f32,
+        bait: Option<&Bait>,
+        config: &Config,
+        rng: &mut impl Rng,
+    ) -> Result<Fish, ReelError> {
         // Clone the ranges so we can modify them temporarily for this generation
         let mut size_range = self.size_range.clone();
         let mut weight_range = self.weight_range.clone();
 
         if let Some(bait) = bait {
             // The helper function makes this much more readable
-            Self::apply_bias(&mut size_range, bait.get_size_bias());
-            Self::apply_bias(&mut weight_range, bait.get_weight_bias());
+            let effect = bait.effect(&config.bait);
+            Self::apply_bias(&mut size_range, effect.size_bias);
+            Self::apply_bias(&mut weight_range, effect.weight_bias);
         }
 
         // Generate values using the skewed ranges
         // Note: randomness is preserved by the triangular distribution
-        let size = size_range.triangular_rand()?;
-        let weight = weight_range.triangular_rand()?;
+        let size = size_range.triangular_rand(rng)?;
+        let weight = weight_range.triangular_rand(rng)?;
 
         // ... Value Calculation ...
-        let config = Config::load();
         let value = match config.fishing.fish_value_calculation {
             ValueCalculationType::Averaged => self.averaged_value(size, weight),
             ValueCalculationType::Multiplicative => self.multiplicative_value(size, weight),
@@ -160,11 +167,9 @@ pub struct Fish {
 }
 
 impl Fish {
-    pub fn try_hook(&self, loadout: &RodLoadout) -> bool {
-        let mut rng = rand::rng();
-
+    /// The chance (0..1) of this fish being hooked with the given loadout
+    pub fn hook_chance(&self, loadout: &RodLoadout, config: &Config) -> f32 {
         // base chance
-        let config = Config::load();
         let base_chance = config.fishing.base_catch_chance;
 
         // rods sensitivity
@@ -176,10 +181,12 @@ impl Fish {
         let fight_mod = self.category.fight_multiplier();
 
         // calculation
-        let final_chance = catch_chance / fight_mod;
+        catch_chance / fight_mod
+    }
 
+    pub fn try_hook(&self, loadout: &RodLoadout, config: &Config, rng: &mut impl Rng) -> bool {
         // from 0..1
-        rng.random::<f32>() < final_chance
+        rng.random::<f32>() < self.hook_chance(loadout, config)
     }
 }
 
@@ -201,23 +208,25 @@ pub struct Pond {
 
 impl Pond {
     pub fn load() -> Result<Self, ReelError> {
-        let raw_path = "./data/gamedata/fish_types.ron".to_string();
-        let path = std::path::Path::new(raw_path.as_str());
+        let raw_path = data_path("gamedata/fish_types.ron");
+        let path = raw_path.as_path();
 
         if !path.exists() {
             return Err(ReelError::FileLoadFailed("Failed to load fish data!".to_string()));
         }
 
-        let contents = std::fs::read_to_string(path).unwrap();
+        let contents = std::fs::read_to_string(path)?;
 
-        Ok(ron::from_str(contents.as_str()).unwrap())
+        ron::from_str(contents.as_str())
+            .map_err(|e| ReelError::FileLoadFailed(format!("fish_types.ron: {}", e)))
     }
 
     pub fn save(&self) -> Result<(), ReelError> {
-        let raw_path = "./data/gamedata/fish_types.ron".to_string();
-        let path = std::path::Path::new(raw_path.as_str());
+        let raw_path = data_path("gamedata/fish_types.ron");
+        let path = raw_path.as_path();
 
-        let contents = ron::to_string(self).unwrap();
+        let contents = ron::to_string(self)
+            .map_err(|e| ReelError::Serialize(format!("fish_types.ron: {}", e)))?;
 
         std::fs::write(path, contents)?;
 
@@ -250,23 +259,29 @@ impl Pond {
     pub fn generate_fish(
         &self,
         raw_depth: f32,
+        near_bottom: f32,
         bait: Option<&Bait>,
+        config: &Config,
+        rng: &mut impl Rng,
     ) -> Result<Option<Fish>, ReelError> {
         // generate a weighted rarity
-        let rarity = FishRarity::weighted_random(bait);
+        let rarity = FishRarity::weighted_random(bait, &config.bait, rng)?;
 
         let depth = Depth::from_depth(raw_depth);
         let mut available_fish = self.get_available_fish(depth.clone(), rarity);
 
-        if let Some(bait) = bait {
-            if let Some((target_name, _)) = bait.get_specific_fish_modifier() {
+        // Resolve Bait Modifiers ONCE (Optimization)
+        let effect = bait.map(|b| b.effect(&config.bait));
+
+        if let Some(effect) = &effect {
+            for target_name in effect.attracted_fish() {
                 // Find the fish in the full list
-                if let Some(target_fish) = self.fish_types.iter().find(|f| f.name == *target_name) {
+                if let Some(target_fish) = self.fish_types.iter().find(|f| f.name == target_name) {
                     // Check depth compatibility manually
                     let (min_depth, max_depth) = target_fish.depth_range;
                     let (depth_min, depth_max) = depth.get_range();
 
-                    if (min_depth <= depth_max) || (max_depth >= depth_min) {
+                    if (min_depth <= depth_max) && (max_depth >= depth_min) {
                         // If it's not already in the list (because it was too rare), add it now
                         if !available_fish.fish_types.iter().any(|f| f.name == target_fish.name) {
                             available_fish.fish_types.push(target_fish.clone());
@@ -280,31 +295,21 @@ impl Pond {
             return Ok(None);
         }
 
-        let mut rng = rand::rng();
-
-        // Resolve Bait Modifiers ONCE (Optimization)
-        // We don't want to load Config inside the loop for every single fish type
-        let category_mod = bait.and_then(|b| b.get_category_modifier());
-        let specific_mod = bait.and_then(|b| b.get_specific_fish_modifier());
-
         let weights: Vec<f32> = available_fish
             .fish_types
             .iter()
             .map(|f| {
                 let mut weight = 1.0;
 
-                // Apply Category Bias
-                if let Some((target_cat, mult)) = &category_mod {
-                    if f.category == *target_cat {
-                        weight *= *mult; // e.g., x3.0
-                    }
+                // bottom feeders bite near the bottom of the sinker's reach (`near_bottom` is 0 to 1)
+                if f.category == FishCategory::BottomFeeder {
+                    weight *= 0.5 + 1.5 * near_bottom;
                 }
 
-                // Apply Specific Fish Bias (Stacks with Category)
-                if let Some((target_name, mult)) = specific_mod {
-                    if f.name == *target_name {
-                        weight *= mult; // e.g., x5.0
-                    }
+                // Apply Category and Specific Fish Bias (they stack, and bad attractions divide)
+                if let Some(effect) = &effect {
+                    weight *= effect.category_multiplier(&f.category);
+                    weight *= effect.fish_multiplier(&f.name);
                 }
 
                 weight
@@ -314,8 +319,36 @@ impl Pond {
         let dist = WeightedIndex::new(&weights)
             .map_err(|_| ReelError::RandomError("Failed to create weighted index".into()))?;
 
-        let fish_type = &available_fish.fish_types[dist.sample(&mut rng)];
+        let fish_type = &available_fish.fish_types[dist.sample(rng)];
+
+        Ok(Some(fish_type.generate_fish(raw_depth, bait, config, rng)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cast::{ROLL_STREAM, cast_rng};
+    use crate::testing::test_fish_type;
+
+    #[test]
+    fn bottom_feeders_bite_near_the_bottom() {
+        let mut bottom_feeder = test_fish_type(FishCategory::BottomFeeder);
+        bottom_feeder.name = "Test Catfish".to_string();
+        let pond = Pond {
+            fish_types: vec![test_fish_type(FishCategory::BaitFish), bottom_feeder],
+        };
+        let config = Config::default();
+        let mut rng = cast_rng(9, ROLL_STREAM);
+
+        let mut bottom_feeders = |near_bottom| {
+            (0..500)
+                .filter_map(|_| pond.generate_fish(50.0, near_bottom, None, &config, &mut rng).unwrap())
+                .filter(|f| f.category == FishCategory::BottomFeeder)
+                .count()
+        };
 
-        Ok(Some(fish_type.generate_fish(raw_depth, bait)?))
+        let (shallow, deep) = (bottom_feeders(0.0), bottom_feeders(1.0));
+        assert!(deep > shallow * 3 / 2, "{} near the top, {} near the bottom", shallow, deep);
     }
 }
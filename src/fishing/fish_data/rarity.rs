@@ -1,8 +1,11 @@
+use rand::Rng;
 use rand_distr::{Distribution, weighted::WeightedIndex};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use std::str::FromStr;
 
+use crate::data_management::config::BaitConfig;
+use crate::error::ReelError;
 use crate::fishing::rod_data::bait::Bait;
 
 // TODO: When fishing, if there are no fish of the generated rarity or lower, then the player should
@@ -47,31 +50,29 @@ impl FishRarity {
     }
 
     /// Get a random rarity weighted properly based on bait and rarity weights.
-    pub fn weighted_random(bait: Option<&Bait>) -> Self {
-        let mut rng = rand::rng();
-
+    /// Fails if the weights can't be sampled (e.g. a bait multiplier of 0 or less in the config)
+    pub fn weighted_random(bait: Option<&Bait>, config: &BaitConfig, rng: &mut impl Rng) -> Result<Self, ReelError> {
         let mut items = Vec::new();
         let mut weights = Vec::new();
 
-        // Resolve the modifier once
-        let rarity_modifier = bait.and_then(|b| b.get_rarity_modifier());
+        // Resolve the bait's effect once
+        let effect = bait.map(|b| b.effect(config));
 
         for rarity in Self::iter() {
             let mut weight = rarity.get_weight() as f32;
 
-            // Apply Bait Bias
-            if let Some((target, multiplier)) = &rarity_modifier {
-                if *target == rarity {
-                    weight *= *multiplier; // Uses Config value (e.g., 5.0)
-                }
+            // Apply Bait Bias, every rarity attraction on the bait stacks (bad ones divide)
+            if let Some(effect) = &effect {
+                weight *= effect.rarity_multiplier(&rarity);
             }
 
             items.push(rarity);
             weights.push(weight);
         }
 
-        let dist = WeightedIndex::new(&weights).unwrap();
-        items[dist.sample(&mut rng)].clone()
+        let dist = WeightedIndex::new(&weights)
+            .map_err(|e| ReelError::RandomError(format!("Invalid rarity weights {:?}: {}", weights, e)))?;
+        Ok(items[dist.sample(rng)].clone())
     }
 
     /// Get all rarities that are less than or equal to the given rarity.
@@ -0,0 +1,176 @@
+// gadgets are permanent items from the shop's unique tab
+// a player owns a gadget by having its id in their inventory, and what it does comes from the catalog
+
+use serde::{Deserialize, Serialize};
+
+use crate::fishing::shop::Shop;
+
+const GADGETS_PATH: &str = "gamedata/gadgets.ron";
+
+/// the ids of the gadgets players had before the catalog, kept as bools in old profiles
+pub const UNDERWATER_CAMERA: &str = "underwater_camera";
+pub const DEPTH_FINDER: &str = "depth_finder";
+
+/// What owning a gadget does, each one hooks into a step of the cast
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GadgetEffect {
+    /// shows the fish that got away
+    UnderwaterCamera,
+    /// shows a hooked fish's rarity and category before the QTE or fight
+    Sonar,
+    /// shows the exact cast depth
+    DepthFinder,
+    /// lists the species that live at the cast depth
+    FishFinder,
+    /// more room in the bait bucket
+    BaitCapacity(u32),
+    /// a second chance to hook a fish that got away, 0 to 1
+    Luck(f32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Gadget {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub price: f32,
+    /// the gadget this one upgrades, which has to be owned first
+    #[serde(default)]
+    pub requires: Option<String>,
+    pub effects: Vec<GadgetEffect>,
+}
+
+/// Everything a player's gadgets add up to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GadgetEffects {
+    pub underwater_camera: bool,
+    pub sonar: bool,
+    pub depth_finder: bool,
+    pub fish_finder: bool,
+    pub bait_capacity: u32,
+    pub luck: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct GadgetCatalog {
+    pub gadgets: Vec<Gadget>,
+}
+
+impl GadgetCatalog {
+    /// Loads the catalog, falling back to the gadgets from before it existed so they keep working
+    pub fn load() -> Self {
+        let gadgets = Shop::load_static_data(GADGETS_PATH).unwrap_or_else(|| Self::default().gadgets);
+        Self { gadgets }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Gadget> {
+        self.gadgets.iter().find(|g| g.id == id)
+    }
+
+    /// A gadget's name, or its id if it's not in the catalog anymore
+    pub fn name_of<'a>(&'a self, id: &'a str) -> &'a str {
+        self.get(id).map_or(id, |g| g.name.as_str())
+    }
+
+    /// Whether a player owning `owned` can buy a gadget, not counting the price
+    pub fn check_purchase(&self, gadget: &Gadget, owned: &[String]) -> Result<(), String> {
+        if owned.contains(&gadget.id) {
+            return Err(format!("You already own a {}!", gadget.name));
+        }
+        if let Some(required) = &gadget.requires
+            && !owned.contains(required)
+        {
+            return Err(format!("You need a {} before you can buy a {}!", self.name_of(required), gadget.name));
+        }
+        Ok(())
+    }
+
+    /// The effects of every owned gadget together. Capacity and luck add up, gadgets missing from the catalog do nothing.
+    pub fn effects(&self, owned: &[String]) -> GadgetEffects {
+        let mut effects = GadgetEffects::default();
+        for effect in owned.iter().filter_map(|id| self.get(id)).flat_map(|g| &g.effects) {
+            match effect {
+                GadgetEffect::UnderwaterCamera => effects.underwater_camera = true,
+                GadgetEffect::Sonar => effects.sonar = true,
+                GadgetEffect::DepthFinder => effects.depth_finder = true,
+                GadgetEffect::FishFinder => effects.fish_finder = true,
+                GadgetEffect::BaitCapacity(amount) => effects.bait_capacity += amount,
+                GadgetEffect::Luck(chance) => effects.luck += chance,
+            }
+        }
+        effects.luck = effects.luck.clamp(0.0, 1.0);
+        effects
+    }
+}
+
+impl Default for GadgetCatalog {
+    fn default() -> Self {
+        Self {
+            gadgets: vec![
+                Gadget {
+                    id: UNDERWATER_CAMERA.to_string(),
+                    name: "Underwater Camera".to_string(),
+                    description: "Allows you to see which fish got away if your line snaps.".to_string(),
+                    price: 2500.0,
+                    requires: None,
+                    effects: vec![GadgetEffect::UnderwaterCamera],
+                },
+                Gadget {
+                    id: DEPTH_FINDER.to_string(),
+                    name: "Depth Finder".to_string(),
+                    description: "Reveals the exact depth your line reaches when casting.".to_string(),
+                    price: 5000.0,
+                    requires: None,
+                    effects: vec![GadgetEffect::DepthFinder],
+                },
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fishing::inventory::Inventory;
+
+    #[test]
+    fn upgrades_need_the_gadget_before_them() {
+        let catalog = GadgetCatalog {
+            gadgets: ron::from_str(
+                r#"[
+                    (id: "tackle_box", name: "Tackle Box", description: "", price: 10.0, effects: [BaitCapacity(50)]),
+                    (id: "tackle_chest", name: "Tackle Chest", description: "", price: 20.0, requires: Some("tackle_box"),
+                        effects: [BaitCapacity(150), Luck(0.6)]),
+                    (id: "charm", name: "Charm", description: "", price: 5.0, effects: [Luck(0.6)]),
+                ]"#,
+            )
+            .unwrap(),
+        };
+        let chest = catalog.get("tackle_chest").unwrap();
+
+        assert!(catalog.check_purchase(chest, &[]).is_err());
+        let mut owned = vec!["tackle_box".to_string()];
+        assert!(catalog.check_purchase(chest, &owned).is_ok());
+
+        // capacity and luck stack, luck tops out at a sure thing
+        owned.extend(["tackle_chest".to_string(), "charm".to_string(), "sold_out".to_string()]);
+        assert!(catalog.check_purchase(chest, &owned).is_err());
+        let effects = catalog.effects(&owned);
+        assert_eq!(effects.bait_capacity, 200);
+        assert_eq!(effects.luck, 1.0);
+        assert!(!effects.sonar);
+    }
+
+    #[test]
+    fn old_profiles_keep_their_camera() {
+        let mut inventory = Inventory { underwater_cam: true, ..Default::default() };
+        inventory.migrate();
+        inventory.migrate();
+        assert!(!ron::to_string(&inventory).unwrap().contains("underwater_cam:"));
+
+        assert_eq!(inventory.gadgets, vec![UNDERWATER_CAMERA.to_string()]);
+        assert!(inventory.get_loadout().has_underwater_camera);
+        let effects = GadgetCatalog::default().effects(&inventory.gadgets);
+        assert!(effects.underwater_camera && !effects.depth_finder);
+    }
+}
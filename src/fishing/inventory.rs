@@ -1,12 +1,56 @@
 use serde::{Deserialize, Serialize};
 use crate::fishing::bait_bucket::BaitBucket;
+use crate::fishing::boats::Boat;
+use crate::fishing::gadgets;
 use crate::fishing::rod_data::bait::Bait;
 use crate::fishing::rod_data::lines::Line;
 use crate::fishing::rod_data::reels::Reel;
 use crate::fishing::rod_data::RodLoadout;
+use crate::fishing::rod_data::durability;
 use crate::fishing::rod_data::rods::RodBase;
 use crate::fishing::rod_data::sinkers::Sinker;
 
+/// The kinds of gear a player can own more than one of
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GearKind {
+    Rod,
+    Line,
+    Reel,
+    Sinker,
+    Bait,
+}
+
+impl GearKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "rod" | "rods" => Some(Self::Rod),
+            "line" | "lines" => Some(Self::Line),
+            "reel" | "reels" => Some(Self::Reel),
+            "sinker" | "sinkers" => Some(Self::Sinker),
+            "bait" | "baits" => Some(Self::Bait),
+            _ => None,
+        }
+    }
+}
+
+/// the most loadout presets a player can save
+pub const MAX_PRESETS: usize = 10;
+/// the longest a preset's name can be
+const MAX_PRESET_NAME: usize = 32;
+
+/// A named set of gear to switch to in one go.
+/// Gear is remembered by name, so a preset still works after other gear is bought or sold.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LoadoutPreset {
+    pub name: String,
+    pub rod: String,
+    pub line: String,
+    pub reel: String,
+    pub sinker: String,
+    /// None to fish without bait
+    pub bait: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Inventory {
     pub rods: Vec<RodBase>,
@@ -21,8 +65,21 @@ pub struct Inventory {
     pub bait_bucket: BaitBucket,
     pub selected_bait: Option<usize>,
 
+    /// None while the player fishes from the shore
+    #[serde(default)]
+    pub boat: Option<Boat>,
+
+    /// the ids of the gadgets the player owns, see `GadgetCatalog`
+    #[serde(default)]
+    pub gadgets: Vec<String>,
+    /// profiles from before gadgets had these instead, they're moved into `gadgets` by `migrate`
+    #[serde(default, skip_serializing)]
     pub underwater_cam: bool,
+    #[serde(default, skip_serializing)]
     pub depth_finder: bool,
+
+    #[serde(default)]
+    pub presets: Vec<LoadoutPreset>,
 }
 
 impl Inventory {
@@ -35,16 +92,363 @@ impl Inventory {
         }
     }
 
+    /// Remove a piece of gear by index, keeping the selected gear pointing at the same item.
+    /// Baits are removed one at a time from their stack.
+    /// The last rod, line, reel or sinker can't be removed since a loadout always needs one of each.
+    /// Returns the name of the removed item.
+    pub fn remove_gear(&mut self, kind: GearKind, index: usize) -> Result<String, String> {
+        if kind == GearKind::Bait {
+            let last_of_stack = self.bait_bucket.count(index) == 1;
+            let bait = self
+                .bait_bucket
+                .take_one(index)
+                .ok_or(format!("No bait at index {}", index))?;
+            if last_of_stack {
+                self.selected_bait = match self.selected_bait {
+                    Some(selected) if selected == index => None,
+                    Some(selected) if selected > index => Some(selected - 1),
+                    other => other,
+                };
+            }
+            return Ok(bait.name);
+        }
+
+        let (len, selected) = match kind {
+            GearKind::Rod => (self.rods.len(), &mut self.selected_rod),
+            GearKind::Line => (self.lines.len(), &mut self.selected_line),
+            GearKind::Reel => (self.reels.len(), &mut self.selected_reel),
+            GearKind::Sinker => (self.sinkers.len(), &mut self.selected_sinker),
+            GearKind::Bait => unreachable!(),
+        };
+
+        if index >= len {
+            return Err(format!("No {:?} at index {}", kind, index));
+        }
+        if len == 1 {
+            return Err(format!("Can't remove the only {:?}", kind));
+        }
+
+        if *selected == index {
+            *selected = 0;
+        } else if *selected > index {
+            *selected -= 1;
+        }
+
+        let name = match kind {
+            GearKind::Rod => self.rods.remove(index).name,
+            GearKind::Line => self.lines.remove(index).name,
+            GearKind::Reel => self.reels.remove(index).name,
+            GearKind::Sinker => self.sinkers.remove(index).name,
+            GearKind::Bait => unreachable!(),
+        };
+
+        Ok(name)
+    }
+
+    /// Use up one of the selected bait.
+    /// With `keep_selected` (AutoBait) the rest of its stack stays selected, otherwise it's unequipped.
+    pub fn use_selected_bait(&mut self, keep_selected: bool) {
+        let Some(index) = self.selected_bait else { return };
+        let _ = self.remove_gear(GearKind::Bait, index);
+        if !keep_selected {
+            self.selected_bait = None;
+        }
+    }
+
+    /// Bring a profile loaded from disk up to date
+    pub fn migrate(&mut self) {
+        // descriptions are rebuilt first, stacks are told apart by them
+        for stack in &mut self.bait_bucket.stacks {
+            stack.bait.refresh_description();
+        }
+        self.merge_bait_stacks();
+
+        let legacy = [
+            (std::mem::take(&mut self.underwater_cam), gadgets::UNDERWATER_CAMERA),
+            (std::mem::take(&mut self.depth_finder), gadgets::DEPTH_FINDER),
+        ];
+        for (owned, id) in legacy {
+            if owned && !self.has_gadget(id) {
+                self.gadgets.push(id.to_string());
+            }
+        }
+    }
+
+    pub fn has_gadget(&self, id: &str) -> bool {
+        self.gadgets.iter().any(|g| g == id)
+    }
+
+    /// Merge stacks of the same bait, keeping the selected bait selected.
+    /// Buckets from before stacking had one entry per bait, and are merged like this when loaded.
+    pub fn merge_bait_stacks(&mut self) {
+        let selected = self.selected_bait.and_then(|i| self.bait_bucket.get(i).cloned());
+        self.bait_bucket.merge();
+        self.selected_bait = selected.and_then(|bait| self.bait_bucket.position(&bait));
+    }
+
+    /// The index of the equipped item of a kind, None if no bait is equipped
+    pub fn selected_index(&self, kind: GearKind) -> Option<usize> {
+        match kind {
+            GearKind::Rod => Some(self.selected_rod),
+            GearKind::Line => Some(self.selected_line),
+            GearKind::Reel => Some(self.selected_reel),
+            GearKind::Sinker => Some(self.selected_sinker),
+            GearKind::Bait => self.selected_bait,
+        }
+    }
+
+    /// The name and shop price of an item
+    pub fn item_info(&self, kind: GearKind, index: usize) -> Option<(String, f32)> {
+        match kind {
+            GearKind::Rod => self.rods.get(index).map(|i| (i.name.clone(), i.price)),
+            GearKind::Line => self.lines.get(index).map(|i| (i.name.clone(), i.price)),
+            GearKind::Reel => self.reels.get(index).map(|i| (i.name.clone(), i.price)),
+            GearKind::Sinker => self.sinkers.get(index).map(|i| (i.name.clone(), i.price)),
+            GearKind::Bait => self.bait_bucket.get(index).map(|i| (i.name.clone(), i.price)),
+        }
+    }
+
+    /// The name of an item and what it would sell back for, if it can be sold.
+    /// Equipped items can't be sold, so a loadout is never left without a piece.
+    pub fn sale_quote(&self, kind: GearKind, index: usize, sell_back_fraction: f32) -> Result<(String, f32), String> {
+        let (name, price) = self
+            .item_info(kind, index)
+            .ok_or("that item doesn't exist anymore")?;
+        // the rest of the selected bait's stack can still be sold
+        let last_equipped = kind != GearKind::Bait || self.bait_bucket.count(index) == 1;
+        if self.selected_index(kind) == Some(index) && last_equipped {
+            return Err(format!("**{}** is equipped, equip something else first", name));
+        }
+
+        // worn gear sells for less
+        let condition = self.durability(kind, index).unwrap_or(1.0);
+        let value = (price * condition * sell_back_fraction.clamp(0.0, 1.0) * 100.0).round() / 100.0;
+        Ok((name, value))
+    }
+
+    /// Remove an unequipped item for `sell_back_fraction` of its price.
+    /// Returns the item's name and what it sold for, the caller pays the player.
+    pub fn sell(&mut self, kind: GearKind, index: usize, sell_back_fraction: f32) -> Result<(String, f32), String> {
+        let quote = self.sale_quote(kind, index, sell_back_fraction)?;
+        self.remove_gear(kind, index)?;
+        Ok(quote)
+    }
+
+    /// The durability of a rod, line or reel, None for gear that doesn't wear down
+    pub fn durability(&self, kind: GearKind, index: usize) -> Option<f32> {
+        match kind {
+            GearKind::Rod => self.rods.get(index).map(|i| i.durability),
+            GearKind::Line => self.lines.get(index).map(|i| i.durability),
+            GearKind::Reel => self.reels.get(index).map(|i| i.durability),
+            GearKind::Sinker | GearKind::Bait => None,
+        }
+    }
+
+    fn durability_mut(&mut self, kind: GearKind, index: usize) -> Option<&mut f32> {
+        match kind {
+            GearKind::Rod => self.rods.get_mut(index).map(|i| &mut i.durability),
+            GearKind::Line => self.lines.get_mut(index).map(|i| &mut i.durability),
+            GearKind::Reel => self.reels.get_mut(index).map(|i| &mut i.durability),
+            GearKind::Sinker | GearKind::Bait => None,
+        }
+    }
+
+    /// Wear down the equipped rod, line and reel. Free gear (like the Willow Branch) never wears.
+    /// Returns the names of anything that just broke.
+    pub fn wear_gear(&mut self, amount: f32) -> Vec<String> {
+        let mut broken = Vec::new();
+        for kind in [GearKind::Rod, GearKind::Line, GearKind::Reel] {
+            let Some(index) = self.selected_index(kind) else { continue };
+            let Some((name, price)) = self.item_info(kind, index) else { continue };
+            if price <= 0.0 {
+                continue;
+            }
+
+            if let Some(durability) = self.durability_mut(kind, index)
+                && !durability::is_broken(*durability)
+            {
+                *durability = (*durability - amount).max(0.0);
+                if durability::is_broken(*durability) {
+                    broken.push(name);
+                }
+            }
+        }
+        broken
+    }
+
+    /// Every rod, line and reel that isn't at full durability
+    pub fn damaged_gear(&self) -> Vec<(GearKind, usize)> {
+        let mut damaged = Vec::new();
+        for (kind, len) in [
+            (GearKind::Rod, self.rods.len()),
+            (GearKind::Line, self.lines.len()),
+            (GearKind::Reel, self.reels.len()),
+        ] {
+            for index in 0..len {
+                if self.durability(kind, index).is_some_and(|d| d < 1.0) {
+                    damaged.push((kind, index));
+                }
+            }
+        }
+        damaged
+    }
+
+    /// What it costs to bring an item back to full durability
+    pub fn repair_cost(&self, kind: GearKind, index: usize, repair_cost: f32) -> Option<f32> {
+        let (_, price) = self.item_info(kind, index)?;
+        let missing = 1.0 - self.durability(kind, index)?;
+        Some((price * missing * repair_cost.max(0.0) * 100.0).round() / 100.0)
+    }
+
+    /// Bring an item back to full durability, the caller charges for it
+    pub fn repair(&mut self, kind: GearKind, index: usize) {
+        if let Some(durability) = self.durability_mut(kind, index) {
+            *durability = durability::full();
+        }
+    }
+
+    /// Save the equipped gear as a preset, replacing any preset with the same name.
+    /// Returns true if a preset was replaced.
+    pub fn save_preset(&mut self, name: &str) -> Result<bool, String> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_PRESET_NAME {
+            return Err(format!("Preset names must be 1 to {} characters long.", MAX_PRESET_NAME));
+        }
+
+        let preset = LoadoutPreset {
+            name: name.to_string(),
+            rod: self.rods[self.selected_rod].name.clone(),
+            line: self.lines[self.selected_line].name.clone(),
+            reel: self.reels[self.selected_reel].name.clone(),
+            sinker: self.sinkers[self.selected_sinker].name.clone(),
+            bait: self.get_selected_bait().map(|b| b.name),
+        };
+
+        if let Some(existing) = self.presets.iter_mut().find(|p| p.name.eq_ignore_ascii_case(name)) {
+            *existing = preset;
+            return Ok(true);
+        }
+        if self.presets.len() >= MAX_PRESETS {
+            return Err(format!("You can only have {} presets, delete one first.", MAX_PRESETS));
+        }
+        self.presets.push(preset);
+        Ok(false)
+    }
+
+    pub fn find_preset(&self, name: &str) -> Option<&LoadoutPreset> {
+        self.presets.iter().find(|p| p.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Returns false if there was no preset with that name
+    pub fn delete_preset(&mut self, name: &str) -> bool {
+        let before = self.presets.len();
+        self.presets.retain(|p| !p.name.eq_ignore_ascii_case(name.trim()));
+        self.presets.len() != before
+    }
+
+    /// The pieces of a preset the player doesn't own anymore
+    pub fn missing_from_preset(&self, preset: &LoadoutPreset) -> Vec<String> {
+        let mut missing = Vec::new();
+        if !self.rods.iter().any(|r| r.name == preset.rod) {
+            missing.push(preset.rod.clone());
+        }
+        if !self.lines.iter().any(|l| l.name == preset.line) {
+            missing.push(preset.line.clone());
+        }
+        if !self.reels.iter().any(|r| r.name == preset.reel) {
+            missing.push(preset.reel.clone());
+        }
+        if !self.sinkers.iter().any(|s| s.name == preset.sinker) {
+            missing.push(preset.sinker.clone());
+        }
+        if let Some(bait) = &preset.bait
+            && !self.bait_bucket.stacks.iter().any(|s| &s.bait.name == bait)
+        {
+            missing.push(bait.clone());
+        }
+        missing
+    }
+
+    /// Equip every piece of a preset the player still owns, leaving the rest as they are.
+    /// Returns the pieces that are missing.
+    pub fn load_preset(&mut self, name: &str) -> Result<Vec<String>, String> {
+        let preset = self
+            .find_preset(name)
+            .cloned()
+            .ok_or(format!("You don't have a preset called `{}`. See `/loadout list`.", name.trim()))?;
+
+        if let Some(index) = self.rods.iter().position(|r| r.name == preset.rod) {
+            self.selected_rod = index;
+        }
+        if let Some(index) = self.lines.iter().position(|l| l.name == preset.line) {
+            self.selected_line = index;
+        }
+        if let Some(index) = self.reels.iter().position(|r| r.name == preset.reel) {
+            self.selected_reel = index;
+        }
+        if let Some(index) = self.sinkers.iter().position(|s| s.name == preset.sinker) {
+            self.selected_sinker = index;
+        }
+        match &preset.bait {
+            None => self.selected_bait = None,
+            Some(bait) => {
+                if let Some(index) = self.bait_bucket.stacks.iter().position(|s| &s.bait.name == bait) {
+                    self.selected_bait = Some(index);
+                }
+            }
+        }
+
+        Ok(self.missing_from_preset(&preset))
+    }
+
+    /// List problems with the inventory that would break `get_loadout`
+    pub fn find_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let checks = [
+            ("rod", self.rods.len(), self.selected_rod),
+            ("line", self.lines.len(), self.selected_line),
+            ("reel", self.reels.len(), self.selected_reel),
+            ("sinker", self.sinkers.len(), self.selected_sinker),
+        ];
+        for (name, len, selected) in checks {
+            if len == 0 {
+                problems.push(format!("has no {}s", name));
+            } else if selected >= len {
+                problems.push(format!("selected {} {} is out of range ({} owned)", name, selected, len));
+            }
+        }
+
+        if let Some(selected) = self.selected_bait
+            && selected >= self.bait_bucket.len()
+        {
+            problems.push(format!(
+                "selected bait {} is out of range ({} owned)",
+                selected,
+                self.bait_bucket.len()
+            ));
+        }
+
+        problems
+    }
+
+    /// The equipped gear, with the default loadout's pieces standing in for broken ones
     pub fn get_loadout(&self) -> RodLoadout {
+        let fallback = RodLoadout::default();
+        let rod = &self.rods[self.selected_rod];
+        let line = &self.lines[self.selected_line];
+        let reel = &self.reels[self.selected_reel];
+
         RodLoadout {
-            rod: self.rods[self.selected_rod].clone(),
-            line: self.lines[self.selected_line].clone(),
-            reel: self.reels[self.selected_reel].clone(),
+            rod: if durability::is_broken(rod.durability) { fallback.rod } else { rod.clone() },
+            line: if durability::is_broken(line.durability) { fallback.line } else { line.clone() },
+            reel: if durability::is_broken(reel.durability) { fallback.reel } else { reel.clone() },
             sinker: self.sinkers[self.selected_sinker].clone(),
             bait: self.get_selected_bait(),
 
-            has_underwater_camera: self.underwater_cam,
-            has_depth_finder: self.depth_finder,
+            has_underwater_camera: self.has_gadget(gadgets::UNDERWATER_CAMERA),
+            has_depth_finder: self.has_gadget(gadgets::DEPTH_FINDER),
+            boat: self.boat.clone(),
         }
     }
 }
@@ -65,8 +469,109 @@ impl Default for Inventory {
             bait_bucket: BaitBucket::new(),
             selected_bait: None,
 
+            boat: None,
+            gadgets: Vec::new(),
             underwater_cam: false,
             depth_finder: false,
+
+            presets: Vec::new(),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_bait(name: &str) -> Bait {
+        Bait {
+            name: name.to_string(),
+            description: String::new(),
+            price: 10.0,
+            reusable: false,
+            attraction: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn selling_keeps_the_equipped_gear_selected() {
+        let mut inventory = Inventory::default();
+        let mut spare = inventory.rods[0].clone();
+        spare.name = "Spare Rod".to_string();
+        spare.price = 50.0;
+        inventory.rods.insert(0, spare);
+        inventory.selected_rod = 1;
+
+        assert!(inventory.sell(GearKind::Rod, 1, 0.4).is_err());
+        assert_eq!(inventory.sell(GearKind::Rod, 0, 0.4), Ok(("Spare Rod".to_string(), 20.0)));
+        assert_eq!(inventory.selected_rod, 0);
+        // the last rod is always the equipped one
+        assert!(inventory.sell(GearKind::Rod, 0, 0.4).is_err());
+
+        for name in ["Worm", "Cricket", "Minnow"] {
+            inventory.bait_bucket.add(test_bait(name));
         }
+        inventory.selected_bait = Some(2);
+        assert!(inventory.sell(GearKind::Bait, 2, 0.4).is_err());
+        assert_eq!(inventory.sell(GearKind::Bait, 0, 0.0), Ok(("Worm".to_string(), 0.0)));
+        assert_eq!(inventory.get_loadout().bait.map(|b| b.name), Some("Minnow".to_string()));
+        assert!(inventory.find_problems().is_empty());
+    }
+
+    #[test]
+    fn worn_gear_is_weaker_and_broken_gear_falls_back() {
+        let mut inventory = Inventory::default();
+        let mut line = inventory.lines[0].clone();
+        line.name = "Braided Line".to_string();
+        line.price = 100.0;
+        line.strength = 20;
+        inventory.lines.push(line);
+        inventory.selected_line = 1;
+        let new_strength = inventory.get_loadout().total_strength();
+
+        assert!(inventory.wear_gear(0.75).is_empty());
+        assert!(inventory.get_loadout().total_strength() < new_strength);
+        // the free starter rod never wears
+        assert_eq!(inventory.rods[0].durability, 1.0);
+        assert_eq!(inventory.damaged_gear(), vec![(GearKind::Line, 1)]);
+        assert_eq!(inventory.repair_cost(GearKind::Line, 1, 0.5), Some(37.5));
+
+        assert_eq!(inventory.wear_gear(0.5), vec!["Braided Line".to_string()]);
+        assert_eq!(inventory.get_loadout().line.name, RodLoadout::default().line.name);
+
+        inventory.repair(GearKind::Line, 1);
+        assert_eq!(inventory.get_loadout().total_strength(), new_strength);
+        assert!(inventory.damaged_gear().is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn presets_survive_gear_being_sold() {
+        let mut inventory = Inventory::default();
+        let mut deep = inventory.sinkers[0].clone();
+        deep.name = "Lead Weight".to_string();
+        inventory.sinkers.push(deep);
+        inventory.bait_bucket.add(test_bait("Worm"));
+        inventory.selected_sinker = 1;
+        inventory.selected_bait = Some(0);
+        assert_eq!(inventory.save_preset("Abyss run"), Ok(false));
+
+        inventory.selected_sinker = 0;
+        inventory.selected_bait = None;
+        assert_eq!(inventory.save_preset("Shallows"), Ok(false));
+        assert_eq!(inventory.save_preset(" shallows "), Ok(true));
+        assert_eq!(inventory.presets.len(), 2);
+
+        assert_eq!(inventory.load_preset("ABYSS RUN"), Ok(Vec::new()));
+        assert_eq!((inventory.selected_sinker, inventory.selected_bait), (1, Some(0)));
+
+        // the sold sinker is reported missing, and the rest still loads
+        inventory.selected_sinker = 0;
+        inventory.sell(GearKind::Sinker, 1, 0.4).unwrap();
+        inventory.selected_bait = None;
+        assert_eq!(inventory.load_preset("Abyss run"), Ok(vec!["Lead Weight".to_string()]));
+        assert_eq!(inventory.selected_bait, Some(0));
+        assert!(inventory.find_problems().is_empty());
+
+        assert!(inventory.delete_preset("abyss run"));
+        assert!(inventory.load_preset("Abyss run").is_err());
+    }
+}
@@ -1,5 +1,6 @@
 // this folder is where all fishing related code goes (except for commands)
 
+use rand::Rng;
 use rand_distr::{Distribution, Triangular};
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +12,13 @@ pub mod rod_data;
 pub mod shop;
 pub mod bait_bucket;
 pub mod inventory;
+pub mod simulation;
+pub mod qte;
+pub mod fight;
+pub mod gadgets;
+pub mod boats;
+pub mod aquarium;
+pub mod cast_rules;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Attribute {
@@ -20,11 +28,9 @@ pub struct Attribute {
 }
 
 impl Attribute {
-    pub fn triangular_rand(&self) -> Result<f32, ReelError> {
-        let mut rng = rand::rng();
-
+    pub fn triangular_rand(&self, rng: &mut impl Rng) -> Result<f32, ReelError> {
         let dist: Triangular<f32> = Triangular::new(self.min, self.max, self.average)?;
 
-        Ok(dist.sample(&mut rng))
+        Ok(dist.sample(rng))
     }
 }
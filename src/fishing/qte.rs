@@ -0,0 +1,548 @@
+// quick time events, started when a fish is heavier than the loadout can handle
+// each kind of QTE is a `Qte`, the cast only shows its stages and checks the player's replies
+
+use rand::Rng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::data_management::config::Config;
+use crate::fishing::fish_data::fish::{Fish, FishCategory};
+
+const DIGIT_WORDS: &[&str] = &[
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+const QTE_EMOJI: &[&str] = &["🐟", "🦀", "🐙", "🐚", "🦐", "🐡", "🦑", "🐠"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QteFormat {
+    /// `3 8 1 9 2`
+    Digits,
+    /// `three eight one nine two`
+    Words,
+    /// `23 + 48`
+    Math,
+    /// a legend of emoji to digits, then the emoji to type in order
+    EmojiOrder,
+}
+
+impl QteFormat {
+    pub const ALL: &[QteFormat] = &[
+        QteFormat::Digits,
+        QteFormat::Words,
+        QteFormat::Math,
+        QteFormat::EmojiOrder,
+    ];
+}
+
+/// The code a player has to type back during a QTE.
+/// The format is picked at random so a macro can't just copy the digits out of the embed
+pub struct QteChallenge {
+    pub format: QteFormat,
+    /// what the player is asked to do, e.g. "Type the code below"
+    pub instructions: &'static str,
+    /// what is shown in the embed
+    pub prompt: String,
+    /// what the player has to type (without spaces)
+    pub answer: String,
+}
+
+impl QteChallenge {
+    /// Roll a challenge in one of the given formats (digits if none are given)
+    pub fn generate(formats: &[QteFormat], rng: &mut impl Rng) -> Self {
+        let format = *formats.choose(rng).unwrap_or(&QteFormat::Digits);
+        let digits: Vec<usize> = (0..5).map(|_| rng.random_range(0..10)).collect();
+        let answer: String = digits.iter().map(|d| d.to_string()).collect();
+
+        match format {
+            QteFormat::Digits => Self {
+                format,
+                instructions: "Type the code below",
+                // spaces between characters prevent copy + paste
+                prompt: answer.chars().map(String::from).collect::<Vec<_>>().join(" "),
+                answer,
+            },
+            QteFormat::Words => Self {
+                format,
+                instructions: "Type the code below as numbers",
+                prompt: digits.iter().map(|d| DIGIT_WORDS[*d]).collect::<Vec<_>>().join(" "),
+                answer,
+            },
+            QteFormat::Math => {
+                let (a, b) = (rng.random_range(10..100), rng.random_range(10..100));
+                let (prompt, result) = if rng.random_bool(0.5) {
+                    (format!("{} + {}", a, b), a + b)
+                } else {
+                    (format!("{} - {}", a.max(b), a.min(b)), a.max(b) - a.min(b))
+                };
+                Self {
+                    format,
+                    instructions: "Type the answer to the sum below",
+                    prompt,
+                    answer: result.to_string(),
+                }
+            }
+            QteFormat::EmojiOrder => {
+                // each emoji stands for a different digit, listed in a shuffled order
+                let mut emoji = QTE_EMOJI.to_vec();
+                emoji.shuffle(rng);
+                let mut values: Vec<usize> = (0..10).collect();
+                values.shuffle(rng);
+
+                let count = 4;
+                let legend = emoji[..count]
+                    .iter()
+                    .zip(&values)
+                    .map(|(e, v)| format!("{}={}", e, v))
+                    .collect::<Vec<_>>()
+                    .join("  ");
+
+                let order: Vec<usize> = (0..count).map(|_| rng.random_range(0..count)).collect();
+                Self {
+                    format,
+                    instructions: "Type the numbers of the fish below, in order",
+                    prompt: format!(
+                        "{}\n{}",
+                        legend,
+                        order.iter().map(|i| emoji[*i]).collect::<Vec<_>>().join(" ")
+                    ),
+                    answer: order.iter().map(|i| values[*i].to_string()).collect(),
+                }
+            }
+        }
+    }
+
+    /// check a reply, ignoring spaces and case
+    pub fn is_answer(&self, reply: &str) -> bool {
+        reply.replace(" ", "").eq_ignore_ascii_case(&self.answer)
+    }
+}
+
+/// How the player answers a stage
+pub enum QteInput {
+    /// by typing in the channel
+    Text,
+    /// by pressing one of these buttons
+    Buttons(Vec<QteButton>),
+}
+
+pub struct QteButton {
+    /// checked by `Qte::is_answer` when the button is pressed
+    pub id: String,
+    pub label: String,
+}
+
+/// One step of a QTE, most QTEs only have one
+pub struct QteStage {
+    /// what the player has to do, shown in the embed
+    pub instructions: String,
+    /// the field shown under the instructions, if any (name, value)
+    pub prompt: Option<(&'static str, String)>,
+    pub input: QteInput,
+    /// seconds the player has to answer once the stage is live
+    pub seconds: f32,
+    /// Seconds the stage waits before it goes live (the buttons turn green).
+    /// Answering before then fails the QTE.
+    pub live_after: Option<f32>,
+}
+
+/// How a QTE was lost
+pub enum QteMiss {
+    /// the wrong answer, as the player gave it
+    Wrong(String),
+    /// a button was pressed before the stage went live
+    Early,
+    Timeout,
+}
+
+/// A kind of quick time event
+pub trait Qte: Send + Sync {
+    /// the embed title while the QTE is running
+    fn title(&self) -> &'static str;
+
+    fn stages(&self) -> &[QteStage];
+
+    /// check a reply to a stage, typed text or the id of the pressed button
+    fn is_answer(&self, stage: usize, reply: &str) -> bool;
+
+    /// the (title, description) shown when the player lands the fish
+    fn success(&self) -> (&'static str, String);
+
+    /// the (title, description) shown when the line snaps
+    fn failure(&self, miss: &QteMiss) -> (&'static str, String);
+
+    /// whether reply times are kept to spot macros.
+    /// Button presses are naturally quick and steady, so only typed QTEs are timed.
+    fn tracks_reply_times(&self) -> bool {
+        false
+    }
+}
+
+/// Pick the QTE for a fish that is too heavy for the loadout.
+/// `excess` is how many times the loadout's strength the fish weighs,
+/// and `seconds` the time limit the loadout gives for it.
+pub fn choose_qte(fish: &Fish, excess: f32, seconds: f32, config: &Config, rng: &mut impl Rng) -> Box<dyn Qte> {
+    let min_seconds = config.fishing.min_qte_time;
+    match fish.category {
+        FishCategory::Apex | FishCategory::Mythological => {
+            Box::new(ReelTensionQte::new(fish.category.clone(), excess, seconds, min_seconds, rng))
+        }
+        FishCategory::Predatory | FishCategory::Abyssal => Box::new(ReactionQte::new(seconds, min_seconds, rng)),
+        FishCategory::Ornamental => Box::new(FishNameQte::new(&fish.fish_type.name, seconds)),
+        FishCategory::Schooling | FishCategory::BottomFeeder => {
+            Box::new(ButtonPickQte::new(excess, seconds, min_seconds, rng))
+        }
+        FishCategory::BaitFish | FishCategory::Forager => {
+            Box::new(TypeCodeQte::new(&config.anti_macro.qte_formats, seconds, rng))
+        }
+    }
+}
+
+/// Type back a code, in one of the anti macro formats
+pub struct TypeCodeQte {
+    challenge: QteChallenge,
+    stages: Vec<QteStage>,
+}
+
+impl TypeCodeQte {
+    pub fn new(formats: &[QteFormat], seconds: f32, rng: &mut impl Rng) -> Self {
+        let challenge = QteChallenge::generate(formats, rng);
+        let stages = vec![QteStage {
+            instructions: format!("The fish is too heavy! {} to save the line!", challenge.instructions),
+            prompt: Some(("⌨️ Type The code!", code_block(&challenge.prompt))),
+            input: QteInput::Text,
+            seconds,
+            live_after: None,
+        }];
+        Self { challenge, stages }
+    }
+}
+
+impl Qte for TypeCodeQte {
+    fn title(&self) -> &'static str {
+        "⚠️ LINE TENSION CRITICAL! ⚠️"
+    }
+
+    fn stages(&self) -> &[QteStage] {
+        &self.stages
+    }
+
+    fn is_answer(&self, _stage: usize, reply: &str) -> bool {
+        self.challenge.is_answer(reply)
+    }
+
+    fn success(&self) -> (&'static str, String) {
+        ("✅ Line Stabilized!", "You managed to reel it in safely.".to_string())
+    }
+
+    fn failure(&self, miss: &QteMiss) -> (&'static str, String) {
+        match miss {
+            QteMiss::Wrong(reply) => ("💥 SNAP!", format!("You typed the wrong code (`{}`).", reply.replace(" ", ""))),
+            _ => ("💥 SNAP!", "You weren't fast enough and your line snapped!".to_string()),
+        }
+    }
+
+    fn tracks_reply_times(&self) -> bool {
+        true
+    }
+}
+
+/// Type the name of the fish on the line, for trophy fish worth knowing
+pub struct FishNameQte {
+    name: String,
+    stages: Vec<QteStage>,
+}
+
+impl FishNameQte {
+    pub fn new(name: &str, seconds: f32) -> Self {
+        // names take longer to type than codes
+        let seconds = seconds * 1.5;
+        let stages = vec![QteStage {
+            instructions: "A trophy fish is fighting the line! Type its name to keep your grip!".to_string(),
+            // spaces between the letters prevent copy + paste
+            prompt: Some(("🏷️ The fish", code_block(&name.chars().map(String::from).collect::<Vec<_>>().join(" ")))),
+            input: QteInput::Text,
+            seconds,
+            live_after: None,
+        }];
+        Self { name: name.to_string(), stages }
+    }
+}
+
+impl Qte for FishNameQte {
+    fn title(&self) -> &'static str {
+        "🏆 A TROPHY ON THE LINE! 🏆"
+    }
+
+    fn stages(&self) -> &[QteStage] {
+        &self.stages
+    }
+
+    fn is_answer(&self, _stage: usize, reply: &str) -> bool {
+        reply.replace(" ", "").eq_ignore_ascii_case(&self.name.replace(" ", ""))
+    }
+
+    fn success(&self) -> (&'static str, String) {
+        ("✅ Got A Grip!", format!("You know a **{}** when you see one.", self.name))
+    }
+
+    fn failure(&self, miss: &QteMiss) -> (&'static str, String) {
+        match miss {
+            QteMiss::Wrong(reply) => ("💥 SNAP!", format!("`{}` isn't what's on your line!", reply)),
+            _ => ("💥 SNAP!", "The trophy shook itself free while you were typing!".to_string()),
+        }
+    }
+
+    fn tracks_reply_times(&self) -> bool {
+        true
+    }
+}
+
+const PICK_TARGETS: &[&str] = &["🐟", "🦀", "🐙", "🐚", "🦐", "🐡", "🦑", "🐠"];
+
+/// Press the button showing the fish among a few others
+pub struct ButtonPickQte {
+    target: String,
+    stages: Vec<QteStage>,
+}
+
+impl ButtonPickQte {
+    pub fn new(excess: f32, seconds: f32, min_seconds: f32, rng: &mut impl Rng) -> Self {
+        // the heavier the fish, the more buttons to pick from (discord fits 5 in a row)
+        let count = (2 + excess.floor() as usize).clamp(3, 5);
+        let mut choices = PICK_TARGETS.to_vec();
+        choices.shuffle(rng);
+        choices.truncate(count);
+        let target = choices[rng.random_range(0..count)].to_string();
+
+        let stages = vec![QteStage {
+            instructions: format!("The school is scattering! Grab the {} before it's gone!", target),
+            prompt: None,
+            input: QteInput::Buttons(
+                choices.iter().map(|e| QteButton { id: e.to_string(), label: e.to_string() }).collect(),
+            ),
+            // pressing a button is quicker than typing
+            seconds: (seconds * 0.75).max(min_seconds),
+            live_after: None,
+        }];
+        Self { target, stages }
+    }
+}
+
+impl Qte for ButtonPickQte {
+    fn title(&self) -> &'static str {
+        "🎯 QUICK, GRAB IT! 🎯"
+    }
+
+    fn stages(&self) -> &[QteStage] {
+        &self.stages
+    }
+
+    fn is_answer(&self, _stage: usize, reply: &str) -> bool {
+        reply == self.target
+    }
+
+    fn success(&self) -> (&'static str, String) {
+        ("✅ Got It!", format!("You snatched the {} out of the school.", self.target))
+    }
+
+    fn failure(&self, miss: &QteMiss) -> (&'static str, String) {
+        match miss {
+            QteMiss::Wrong(reply) => ("💥 Wrong Fish!", format!("You grabbed a {} and the {} got away!", reply, self.target)),
+            _ => ("💥 Too Slow!", "The school swam off with your fish!".to_string()),
+        }
+    }
+}
+
+/// Press the button as soon as it turns green, pressing early loses the fish
+pub struct ReactionQte {
+    stages: Vec<QteStage>,
+}
+
+impl ReactionQte {
+    pub fn new(seconds: f32, min_seconds: f32, rng: &mut impl Rng) -> Self {
+        let stages = vec![QteStage {
+            instructions: "Something big is circling your line... Wait for the button to turn green, then strike!".to_string(),
+            prompt: None,
+            input: QteInput::Buttons(vec![QteButton { id: "strike".to_string(), label: "🎣 Strike!".to_string() }]),
+            // a reaction is quicker still, but never less than the config allows
+            seconds: (seconds * 0.5).max(min_seconds),
+            live_after: Some(rng.random_range(1.5..4.0)),
+        }];
+        Self { stages }
+    }
+}
+
+impl Qte for ReactionQte {
+    fn title(&self) -> &'static str {
+        "🦈 STEADY... 🦈"
+    }
+
+    fn stages(&self) -> &[QteStage] {
+        &self.stages
+    }
+
+    fn is_answer(&self, _stage: usize, reply: &str) -> bool {
+        reply == "strike"
+    }
+
+    fn success(&self) -> (&'static str, String) {
+        ("⚡ Perfect Strike!", "You set the hook the moment it bit.".to_string())
+    }
+
+    fn failure(&self, miss: &QteMiss) -> (&'static str, String) {
+        match miss {
+            QteMiss::Early => ("💥 Too Early!", "You struck before it bit and spooked the fish!".to_string()),
+            _ => ("💥 Too Slow!", "The fish stole your bait and snapped the line!".to_string()),
+        }
+    }
+}
+
+/// the moves in a reel tension fight: (id, button, what the line is doing when it's the right move)
+const TENSION_MOVES: &[(&str, &str, &str)] = &[
+    ("reel", "🔄 Reel In", "The line's gone slack!"),
+    ("let_out", "🪢 Let Out Line", "The fish is running, the line is about to snap!"),
+    ("hold", "✊ Hold Steady", "The fish is thrashing at the surface!"),
+];
+
+/// A fight in several stages, each answered with the right move for the line's tension
+pub struct ReelTensionQte {
+    category: FishCategory,
+    answers: Vec<&'static str>,
+    stages: Vec<QteStage>,
+}
+
+impl ReelTensionQte {
+    pub fn new(category: FishCategory, excess: f32, seconds: f32, min_seconds: f32, rng: &mut impl Rng) -> Self {
+        let base_stages = if category == FishCategory::Mythological { 4 } else { 3 };
+        // a fish far too heavy for the loadout fights for longer
+        let count = base_stages + (excess.log2().floor().max(0.0) as usize).min(2);
+        let seconds = (seconds * 0.6).max(min_seconds);
+
+        let mut answers = Vec::new();
+        let mut stages = Vec::new();
+        for _ in 0..count {
+            let (answer, _, situation) = *TENSION_MOVES.choose(rng).expect("there are tension moves");
+            let mut buttons: Vec<QteButton> = TENSION_MOVES
+                .iter()
+                .map(|(id, label, _)| QteButton { id: id.to_string(), label: label.to_string() })
+                .collect();
+            // the buttons move around so the same spot can't be pressed every time
+            buttons.shuffle(rng);
+
+            answers.push(answer);
+            stages.push(QteStage {
+                instructions: format!("{} Make the right move!", situation),
+                prompt: None,
+                input: QteInput::Buttons(buttons),
+                seconds,
+                live_after: None,
+            });
+        }
+
+        Self { category, answers, stages }
+    }
+}
+
+impl Qte for ReelTensionQte {
+    fn title(&self) -> &'static str {
+        match self.category {
+            FishCategory::Mythological => "🌊 THE FIGHT OF A LIFETIME! 🌊",
+            _ => "🦈 BOSS FIGHT! 🦈",
+        }
+    }
+
+    fn stages(&self) -> &[QteStage] {
+        &self.stages
+    }
+
+    fn is_answer(&self, stage: usize, reply: &str) -> bool {
+        self.answers.get(stage).is_some_and(|answer| *answer == reply)
+    }
+
+    fn success(&self) -> (&'static str, String) {
+        ("✅ It's Exhausted!", format!("After {} rounds, the fish finally gives in.", self.stages.len()))
+    }
+
+    fn failure(&self, miss: &QteMiss) -> (&'static str, String) {
+        match miss {
+            QteMiss::Wrong(_) => ("💥 SNAP!", "Wrong move! The line couldn't take it.".to_string()),
+            _ => ("💥 SNAP!", "You hesitated and the fish tore the line apart!".to_string()),
+        }
+    }
+}
+
+/// wrap every line in backticks so the prompt shows as code
+fn code_block(text: &str) -> String {
+    text.lines().map(|l| format!("`{}`", l)).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::test_fish;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn every_format_can_be_answered_from_its_prompt() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..20 {
+            let words = QteChallenge::generate(&[QteFormat::Words], &mut rng);
+            let typed: String = words
+                .prompt
+                .split(' ')
+                .map(|w| DIGIT_WORDS.iter().position(|d| *d == w).unwrap().to_string())
+                .collect();
+            assert!(words.is_answer(&typed));
+
+            let math = QteChallenge::generate(&[QteFormat::Math], &mut rng);
+            let parts: Vec<&str> = math.prompt.split(' ').collect();
+            let (a, b): (i32, i32) = (parts[0].parse().unwrap(), parts[2].parse().unwrap());
+            let result = if parts[1] == "+" { a + b } else { a - b };
+            assert!(math.is_answer(&result.to_string()));
+
+            let emoji = QteChallenge::generate(&[QteFormat::EmojiOrder], &mut rng);
+            let (legend, order) = emoji.prompt.split_once('\n').unwrap();
+            let typed: String = order
+                .split(' ')
+                .map(|e| legend.split("  ").find_map(|pair| pair.strip_prefix(&format!("{}=", e))).unwrap())
+                .collect();
+            assert!(emoji.is_answer(&typed));
+        }
+    }
+
+    #[test]
+    fn qtes_are_picked_by_category_and_can_be_won() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let config = Config::default();
+        let mut fish = test_fish(50.0, 10.0);
+
+        fish.category = FishCategory::Mythological;
+        let fight = choose_qte(&fish, 5.0, 4.0, &config, &mut rng);
+        // mythological fish fight for 4 stages, plus more for being far too heavy
+        assert_eq!(fight.stages().len(), 6);
+        for (i, stage) in fight.stages().iter().enumerate() {
+            let QteInput::Buttons(buttons) = &stage.input else { panic!("tension stages use buttons") };
+            let right: Vec<_> = buttons.iter().filter(|b| fight.is_answer(i, &b.id)).collect();
+            assert_eq!(right.len(), 1);
+        }
+
+        fish.category = FishCategory::Predatory;
+        let reaction = choose_qte(&fish, 2.0, 4.0, &config, &mut rng);
+        assert!(reaction.stages()[0].live_after.is_some());
+        assert!(reaction.is_answer(0, "strike"));
+
+        fish.category = FishCategory::Ornamental;
+        let name = choose_qte(&fish, 2.0, 4.0, &config, &mut rng);
+        assert!(name.is_answer(0, "test minnow"));
+        assert!(!name.is_answer(0, "Test Minow"));
+
+        fish.category = FishCategory::Schooling;
+        let pick = choose_qte(&fish, 2.0, 4.0, &config, &mut rng);
+        let (_, prompt) = pick.success();
+        let QteInput::Buttons(buttons) = &pick.stages()[0].input else { panic!("picking uses buttons") };
+        assert_eq!(buttons.len(), 4);
+        assert!(buttons.iter().any(|b| pick.is_answer(0, &b.id) && prompt.contains(&b.label)));
+    }
+}
@@ -1,8 +1,8 @@
 use rand::prelude::IndexedRandom;
-use rand::Rng;
+use rand::{Rng, RngCore};
 use serde::{Deserialize, Serialize};
 
-use crate::{data_management::config::Config, fishing::fish_data::{fish::FishCategory, rarity::FishRarity}, nay};
+use crate::{data_management::{config::BaitConfig, data_path}, fishing::fish_data::{fish::FishCategory, rarity::FishRarity}, nay};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// Affects how much the bait affects the weights
@@ -13,27 +13,23 @@ pub enum BaitBias {
 }
 
 impl BaitBias {
-    pub fn get_multiplier(&self) -> f32 {
-        let config = Config::load();
-
+    pub fn get_multiplier(&self, config: &BaitConfig) -> f32 {
         match self {
-            BaitBias::Low => config.bait.low_bait_weight,
-            BaitBias::Medium => config.bait.medium_bait_weight,
-            BaitBias::High => config.bait.high_bait_weight,
+            BaitBias::Low => config.low_bait_weight,
+            BaitBias::Medium => config.medium_bait_weight,
+            BaitBias::High => config.high_bait_weight,
         }
     }
 
     /// Returns a normalized value between 0.0 and 1.0 based on the highest configured weight.
-    pub fn get_normalized_strength(&self) -> f32 {
-        let config = Config::load();
-
+    pub fn get_normalized_strength(&self, config: &BaitConfig) -> f32 {
         let val = match self {
-            BaitBias::Low => config.bait.low_bait_weight,
-            BaitBias::Medium => config.bait.medium_bait_weight,
-            BaitBias::High => config.bait.high_bait_weight,
+            BaitBias::Low => config.low_bait_weight,
+            BaitBias::Medium => config.medium_bait_weight,
+            BaitBias::High => config.high_bait_weight,
         };
 
-        let max = config.bait.high_bait_weight.max(1.0);
+        let max = config.high_bait_weight.max(1.0);
         (val / max).clamp(0.0, 1.0)
     }
 }
@@ -56,6 +52,20 @@ pub enum BaitAttraction {
     Category(FishCategory, BaitBias, AttractionQuality),
 }
 
+impl BaitAttraction {
+    pub fn quality(&self) -> AttractionQuality {
+        match self {
+            BaitAttraction::Heavy { quality, .. }
+            | BaitAttraction::Light { quality, .. }
+            | BaitAttraction::Large { quality, .. }
+            | BaitAttraction::Small { quality, .. }
+            | BaitAttraction::SpecificFish { quality, .. }
+            | BaitAttraction::Rarity(_, _, quality)
+            | BaitAttraction::Category(_, _, quality) => *quality,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Bait {
     pub name: String,
@@ -68,49 +78,129 @@ pub struct Bait {
 }
 
 impl Bait {
-    pub fn get_specific_fish_modifier(&self) -> Option<(&String, f32)> {
-        self.attraction.iter().find_map(|attr| match attr {
-            BaitAttraction::SpecificFish { name, bias, .. } => Some((name, bias.get_multiplier())),
-            _ => None,
-        })
+    /// The combined effect of every attraction on the bait
+    pub fn effect(&self, config: &BaitConfig) -> BaitEffect {
+        let mut effect = BaitEffect::default();
+
+        for attr in &self.attraction {
+            match attr {
+                BaitAttraction::Heavy { bias, quality } => effect.weight_bias += quality.sign() * bias.get_normalized_strength(config),
+                BaitAttraction::Light { bias, quality } => effect.weight_bias -= quality.sign() * bias.get_normalized_strength(config),
+                BaitAttraction::Large { bias, quality } => effect.size_bias += quality.sign() * bias.get_normalized_strength(config),
+                BaitAttraction::Small { bias, quality } => effect.size_bias -= quality.sign() * bias.get_normalized_strength(config),
+                BaitAttraction::SpecificFish { name, bias, quality } => {
+                    stack(&mut effect.fish, name.clone(), quality.apply(bias.get_multiplier(config)))
+                }
+                BaitAttraction::Rarity(rarity, bias, quality) => {
+                    stack(&mut effect.rarities, rarity.clone(), quality.apply(bias.get_multiplier(config)))
+                }
+                BaitAttraction::Category(category, bias, quality) => {
+                    stack(&mut effect.categories, category.clone(), quality.apply(bias.get_multiplier(config)))
+                }
+            }
+        }
+
+        effect.size_bias = effect.size_bias.clamp(-1.0, 1.0);
+        effect.weight_bias = effect.weight_bias.clamp(-1.0, 1.0);
+        effect
     }
 
-    pub fn get_rarity_modifier(&self) -> Option<(FishRarity, f32)> {
-        self.attraction.iter().find_map(|attr| match attr {
-            BaitAttraction::Rarity(r, bias, _) => Some((r.clone(), bias.get_multiplier())),
-            _ => None,
-        })
+    /// Rebuild the description of a bait with Bad attractions.
+    /// Baits from before Bad attractions repelled were described as attracting what they now keep away,
+    /// baits without any are left alone so hand-written descriptions are kept.
+    pub fn refresh_description(&mut self) {
+        if self.attraction.iter().any(|a| a.quality() == AttractionQuality::Bad) {
+            self.description = Self::generate_description(&self.attraction, self.reusable);
+        }
     }
+}
 
-    pub fn get_category_modifier(&self) -> Option<(FishCategory, f32)> {
-        self.attraction.iter().find_map(|attr| match attr {
-            BaitAttraction::Category(c, bias, _) => Some((c.clone(), bias.get_multiplier())),
-            _ => None,
-        })
+impl AttractionQuality {
+    /// which way the attraction pushes sizes and weights
+    fn sign(&self) -> f32 {
+        match self {
+            AttractionQuality::Good => 1.0,
+            AttractionQuality::Bad => -1.0,
+        }
     }
 
-    pub fn get_weight_bias(&self) -> f32 {
-        let mut total_bias = 0.0;
-        for attr in &self.attraction {
-            match attr {
-                BaitAttraction::Heavy { bias, .. } => total_bias += bias.get_normalized_strength(),
-                BaitAttraction::Light { bias, .. } => total_bias -= bias.get_normalized_strength(),
-                _ => {}
-            }
+    /// Good attractions multiply a target's weight, Bad ones divide it
+    fn apply(&self, multiplier: f32) -> f32 {
+        match self {
+            AttractionQuality::Good => multiplier,
+            AttractionQuality::Bad => 1.0 / multiplier,
         }
-        total_bias.clamp(-1.0, 1.0)
     }
+}
 
-    pub fn get_size_bias(&self) -> f32 {
-        let mut total_bias = 0.0;
-        for attr in &self.attraction {
-            match attr {
-                BaitAttraction::Large { bias, .. } => total_bias += bias.get_normalized_strength(),
-                BaitAttraction::Small { bias, .. } => total_bias -= bias.get_normalized_strength(),
-                _ => {}
-            }
+/// multiply a target's multiplier in, so attractions on the same target stack
+fn stack<T: PartialEq>(multipliers: &mut Vec<(T, f32)>, target: T, multiplier: f32) {
+    match multipliers.iter_mut().find(|(t, _)| *t == target) {
+        Some((_, existing)) => *existing *= multiplier,
+        None => multipliers.push((target, multiplier)),
+    }
+}
+
+/// What a bait does once every attraction on it is added up.
+/// Multipliers above 1.0 draw a target in and below 1.0 keep it away.
+#[derive(Debug, Clone, Default)]
+pub struct BaitEffect {
+    pub rarities: Vec<(FishRarity, f32)>,
+    pub categories: Vec<(FishCategory, f32)>,
+    pub fish: Vec<(String, f32)>,
+    /// -1.0 (smaller) to 1.0 (larger)
+    pub size_bias: f32,
+    /// -1.0 (lighter) to 1.0 (heavier)
+    pub weight_bias: f32,
+}
+
+impl BaitEffect {
+    pub fn rarity_multiplier(&self, rarity: &FishRarity) -> f32 {
+        self.rarities.iter().filter(|(r, _)| r == rarity).map(|(_, m)| m).product()
+    }
+
+    pub fn category_multiplier(&self, category: &FishCategory) -> f32 {
+        self.categories.iter().filter(|(c, _)| c == category).map(|(_, m)| m).product()
+    }
+
+    pub fn fish_multiplier(&self, name: &str) -> f32 {
+        self.fish.iter().filter(|(n, _)| n == name).map(|(_, m)| m).product()
+    }
+
+    /// names of the fish the bait draws in, even when their rarity wasn't rolled
+    pub fn attracted_fish(&self) -> impl Iterator<Item = &str> {
+        self.fish.iter().filter(|(_, m)| *m > 1.0).map(|(n, _)| n.as_str())
+    }
+
+    /// The net effect for players, e.g. "+150% Predatory, -40% Rare, +30% size"
+    pub fn summary(&self) -> String {
+        let percent = |multiplier: f32| format!("{:+.0}%", (multiplier - 1.0) * 100.0);
+        let mut parts: Vec<String> = Vec::new();
+
+        for (name, multiplier) in &self.fish {
+            parts.push(format!("{} {}", percent(*multiplier), name));
+        }
+        for (category, multiplier) in &self.categories {
+            parts.push(format!("{} {:?}", percent(*multiplier), category));
+        }
+        for (rarity, multiplier) in &self.rarities {
+            parts.push(format!("{} {}", percent(*multiplier), rarity));
+        }
+        // attractions that cancel each other out aren't worth showing
+        parts.retain(|p| !p.starts_with("+0%") && !p.starts_with("-0%"));
+
+        if self.size_bias.abs() >= 0.005 {
+            parts.push(format!("{:+.0}% size", self.size_bias * 100.0));
+        }
+        if self.weight_bias.abs() >= 0.005 {
+            parts.push(format!("{:+.0}% weight", self.weight_bias * 100.0));
+        }
+
+        if parts.is_empty() {
+            "No effect".to_string()
+        } else {
+            parts.join(", ")
         }
-        total_bias.clamp(-1.0, 1.0)
     }
 }
 
@@ -128,7 +218,7 @@ pub struct BaitData {
 
 impl BaitData {
     pub fn load() -> Self {
-        let path = "./data/gamedata/bait.ron";
+        let path = data_path("gamedata/bait.ron");
         match std::fs::read_to_string(path) {
             Ok(content) => ron::from_str(&content).unwrap_or_else(|e| {
                 nay!("Failed to parse bait.ron: {}", e);
@@ -144,9 +234,7 @@ impl BaitData {
 
 impl Bait {
     /// Generates a random bait or lure based on the provided potency.
-    pub fn generate(potency: BaitPotency, force_lure: bool) -> Self {
-        let mut rng = rand::rng();
-
+    pub fn generate(potency: BaitPotency, force_lure: bool, rng: &mut impl Rng) -> Self {
         // 10% chance to be a Lure (Infinite use, higher cost)
         // Lures are more common in higher potencies
         let is_lure = if force_lure {
@@ -162,15 +250,14 @@ impl Bait {
         let mut attractions = Vec::new();
 
         // Helper closure to add attractions while avoiding logic conflicts (e.g., Heavy + Light)
-        let mut add_attraction = |target_bias: BaitBias| {
-            let mut local_rng = rand::rng();
+        let mut add_attraction = |target_bias: BaitBias, rng: &mut dyn RngCore| {
             let mut attempts = 0;
             // Try up to 5 times to generate a non-conflicting attraction
             while attempts < 5 {
                 // Determine Quality based on Potency
                 // Low Potency -> High chance of Bad traits
                 // High Potency -> High chance of Good traits
-                let quality_roll = local_rng.random_range(0..100);
+                let quality_roll = rng.random_range(0..100);
                 let good_threshold = match potency {
                     BaitPotency::Low => 30, // 30% Chance for Good
                     BaitPotency::Medium => 60, // 60% Chance for Good
@@ -183,7 +270,7 @@ impl Bait {
                     AttractionQuality::Bad
                 };
 
-                let candidate = Self::generate_random_attraction(&mut local_rng, target_bias.clone(), quality);
+                let candidate = Self::generate_random_attraction(rng, target_bias.clone(), quality);
 
                 let is_conflicting = attractions.iter().any(|existing| {
                     match (existing, &candidate) {
@@ -216,26 +303,26 @@ impl Bait {
             BaitPotency::Low => {
                 let count = rng.random_range(1..=2);
                 for _ in 0..count {
-                    add_attraction(BaitBias::Low);
+                    add_attraction(BaitBias::Low, rng);
                 }
             },
             BaitPotency::Medium => {
-                add_attraction(BaitBias::Medium);
+                add_attraction(BaitBias::Medium, rng);
                 let count = rng.random_range(1..=2);
                 for _ in 0..count {
-                    add_attraction(BaitBias::Low);
+                    add_attraction(BaitBias::Low, rng);
                 }
             },
             BaitPotency::High => {
                 let high_count = rng.random_range(1..=2);
                 for _ in 0..high_count {
-                    add_attraction(BaitBias::High);
+                    add_attraction(BaitBias::High, rng);
                 }
-                add_attraction(BaitBias::Medium);
+                add_attraction(BaitBias::Medium, rng);
 
                 let low_count = rng.random_range(1..=2);
                 for _ in 0..low_count {
-                    add_attraction(BaitBias::Low);
+                    add_attraction(BaitBias::Low, rng);
                 }
             },
         }
@@ -251,7 +338,7 @@ impl Bait {
         } else {
             // Organic Bait Logic
             let data = BaitData::load();
-            let base = data.base_names.choose(&mut rng).cloned().unwrap_or_else(|| "Worm".to_string());
+            let base = data.base_names.choose(rng).cloned().unwrap_or_else(|| "Worm".to_string());
             name = Self::generate_name(&base, &attractions);
             price_multiplier = 1.0;
         }
@@ -276,7 +363,7 @@ impl Bait {
         }
     }
 
-    fn generate_random_attraction(rng: &mut impl Rng, bias: BaitBias, quality: AttractionQuality) -> BaitAttraction {
+    fn generate_random_attraction(rng: &mut (impl Rng + ?Sized), bias: BaitBias, quality: AttractionQuality) -> BaitAttraction {
         let roll = rng.random_range(0..100);
 
         match quality {
@@ -308,20 +395,21 @@ impl Bait {
                 }
             },
             AttractionQuality::Bad => {
+                // bad traits keep away the fish players want
                 match roll {
-                    0..=29 => BaitAttraction::Small { bias, quality },
-                    30..=59 => BaitAttraction::Light { bias, quality },
+                    0..=29 => BaitAttraction::Large { bias, quality },
+                    30..=59 => BaitAttraction::Heavy { bias, quality },
                     60..=84 => {
                         let categories = [
-                            FishCategory::BaitFish, FishCategory::Schooling,
-                            FishCategory::BottomFeeder, FishCategory::Forager
+                            FishCategory::Predatory, FishCategory::Apex,
+                            FishCategory::Abyssal, FishCategory::Ornamental
                         ];
                         let cat = categories.choose(rng).unwrap().clone();
                         BaitAttraction::Category(cat, bias, quality)
                     },
                     _ => {
                         let rarities = [
-                            FishRarity::Common, FishRarity::Uncommon
+                            FishRarity::Rare, FishRarity::Elusive, FishRarity::Legendary
                         ];
                         let rar = rarities.choose(rng).unwrap().clone();
                         BaitAttraction::Rarity(rar, bias, quality)
@@ -334,6 +422,10 @@ impl Bait {
     fn generate_name(base: &str, attractions: &[BaitAttraction]) -> String {
         let mut prefix = "";
 
+        // the name comes from what the bait draws in, not what it keeps away
+        let attractions: Vec<&BaitAttraction> = attractions.iter().filter(|a| a.quality() == AttractionQuality::Good).collect();
+        let attractions = attractions.as_slice();
+
         if let Some(cat_attr) = attractions.iter().find(|a| matches!(a, BaitAttraction::Category(_, _, _))) {
             if let BaitAttraction::Category(cat, _, _) = cat_attr {
                 prefix = match cat {
@@ -388,10 +480,13 @@ impl Bait {
                 BaitAttraction::SpecificFish { name, bias, .. } => (bias, format!("{}", name)),
             };
 
-            let verb = match bias {
-                BaitBias::Low => "lightly attracts",
-                BaitBias::Medium => "attracts",
-                BaitBias::High => "greatly attracts",
+            let verb = match (bias, a.quality()) {
+                (BaitBias::Low, AttractionQuality::Good) => "lightly attracts",
+                (BaitBias::Medium, AttractionQuality::Good) => "attracts",
+                (BaitBias::High, AttractionQuality::Good) => "greatly attracts",
+                (BaitBias::Low, AttractionQuality::Bad) => "slightly repels",
+                (BaitBias::Medium, AttractionQuality::Bad) => "repels",
+                (BaitBias::High, AttractionQuality::Bad) => "strongly repels",
             };
 
             format!("{} {}", verb, text)
@@ -400,4 +495,51 @@ impl Bait {
         let type_str = if is_lure { "A reusable lure" } else { "A bait" };
         format!("{} that {}.", type_str, attr_desc.join(", "))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_management::config::Config;
+
+    #[test]
+    fn bad_attractions_repel_and_every_attraction_stacks() {
+        let config = Config::default().bait;
+        let bait = Bait {
+            name: "Test Worm".to_string(),
+            description: String::new(),
+            price: 1.0,
+            reusable: false,
+            attraction: vec![
+                BaitAttraction::Category(FishCategory::Predatory, BaitBias::Low, AttractionQuality::Good),
+                BaitAttraction::Category(FishCategory::Predatory, BaitBias::Low, AttractionQuality::Good),
+                BaitAttraction::Rarity(FishRarity::Rare, BaitBias::Medium, AttractionQuality::Bad),
+                BaitAttraction::Large { bias: BaitBias::High, quality: AttractionQuality::Bad },
+            ],
+        };
+
+        let effect = bait.effect(&config);
+        let low = config.low_bait_weight;
+        assert_eq!(effect.category_multiplier(&FishCategory::Predatory), low * low);
+        assert_eq!(effect.category_multiplier(&FishCategory::Apex), 1.0);
+        assert_eq!(effect.rarity_multiplier(&FishRarity::Rare), 1.0 / config.medium_bait_weight);
+        assert_eq!(effect.size_bias, -1.0);
+
+        // 1.5 * 1.5 = +125%, 1 / 3.5 = -71%
+        assert_eq!(effect.summary(), "+125% Predatory, -71% Rare, -100% size");
+    }
+
+    #[test]
+    fn old_bad_baits_are_described_as_repelling() {
+        let mut bait = Bait {
+            name: "Small Worm".to_string(),
+            description: "A bait that lightly attracts smaller fish.".to_string(),
+            price: 1.0,
+            reusable: false,
+            attraction: vec![BaitAttraction::Small { bias: BaitBias::Low, quality: AttractionQuality::Bad }],
+        };
+
+        bait.refresh_description();
+        assert_eq!(bait.description, "A bait that slightly repels smaller fish.");
+    }
+}
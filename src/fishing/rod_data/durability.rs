@@ -0,0 +1,34 @@
+// rods, lines and reels wear down as they're used
+// durability goes from 1.0 (new) to 0.0 (broken), and worn gear performs worse
+
+/// gear below this durability is worn and its stats start dropping
+pub const WORN_BELOW: f32 = 0.5;
+/// what worn gear's stats are multiplied by just before it breaks
+pub const BROKEN_STAT_MULTIPLIER: f32 = 0.7;
+
+/// new gear, and gear from before durability existed
+pub fn full() -> f32 {
+    1.0
+}
+
+/// The multiplier for a piece of gear's stats, 1.0 until it's worn then dropping to BROKEN_STAT_MULTIPLIER
+pub fn stat_multiplier(durability: f32) -> f32 {
+    let worn = (durability / WORN_BELOW).clamp(0.0, 1.0);
+    BROKEN_STAT_MULTIPLIER + (1.0 - BROKEN_STAT_MULTIPLIER) * worn
+}
+
+pub fn is_broken(durability: f32) -> bool {
+    durability <= 0.0
+}
+
+/// like `85%`, `🔧 Worn 30%` or `💥 Broken`
+pub fn display(durability: f32) -> String {
+    let percent = (durability * 100.0).round();
+    if is_broken(durability) {
+        "💥 Broken".to_string()
+    } else if durability < WORN_BELOW {
+        format!("🔧 Worn {:.0}%", percent)
+    } else {
+        format!("{:.0}%", percent)
+    }
+}
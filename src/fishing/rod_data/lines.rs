@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::fishing::rod_data::durability;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Line {
     pub name: String,
@@ -7,4 +9,7 @@ pub struct Line {
     pub price: f32,
     /// Maximum weight (in lbs) that the line can support before snapping.
     pub strength: u32,
+    /// 1.0 when new, wears down with use and the gear breaks at 0.0
+    #[serde(default = "durability::full")]
+    pub durability: f32,
 }
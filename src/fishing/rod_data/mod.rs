@@ -1,19 +1,29 @@
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    data_management::config::Config,
+    data_management::config::{Config, DurabilityConfig},
+    error::ReelError,
     fishing::{
         Attribute,
+        boats::{self, Boat},
+        fish_data::fish::Fish,
         rod_data::{bait::Bait, lines::Line, reels::Reel, rods::RodBase, sinkers::Sinker},
     },
 };
 
 pub mod bait;
+pub mod durability;
 pub mod lines;
 pub mod reels;
 pub mod rods;
 pub mod sinkers;
 
+/// fish this many times over the loadout's strength wear it down no more than heavier ones
+const MAX_WEAR_EXCESS: f32 = 4.0;
+/// aimed casts this close to the target, as a share of the sinker's spread, are precise
+const PRECISE_CAST: f32 = 0.2;
+
 // 6 modules:
 // - Rod: The base
 // - Line: Determines weight limit
@@ -40,15 +50,77 @@ pub struct RodLoadout {
     pub has_depth_finder: bool,
     /// purchasable item to view the fish you miss
     pub has_underwater_camera: bool,
+    /// None when fishing from the shore, which limits how deep the sinker can go
+    #[serde(default)]
+    pub boat: Option<Boat>,
 }
 
 impl RodLoadout {
+    /// A depth in the sinker's range, near `target` if the player aimed, and no deeper than the boat can reach
+    pub fn generate_depth(&self, target: Option<f32>, rng: &mut impl Rng) -> Result<f32, ReelError> {
+        let depth = match target {
+            Some(target) => self.sinker.aim_depth(target.min(self.max_depth()), rng)?,
+            None => self.sinker.generate_depth(rng)?,
+        };
+        Ok(depth.min(self.max_depth()))
+    }
+
+    /// The shallowest and deepest the loadout can cast to
+    pub fn depth_reach(&self) -> (f32, f32) {
+        let deepest = self.sinker.depth_range.max.min(self.max_depth());
+        (self.sinker.depth_range.min.min(deepest), deepest)
+    }
+
+    /// How far down the loadout's reach a depth is, 0 at the top to 1 at the bottom
+    pub fn near_bottom(&self, depth: f32) -> f32 {
+        let (shallowest, deepest) = self.depth_reach();
+        if deepest <= shallowest {
+            return 1.0;
+        }
+        ((depth - shallowest) / (deepest - shallowest)).clamp(0.0, 1.0)
+    }
+
+    /// Whether an aimed cast landed close enough to its target to count as precise
+    pub fn is_precise(&self, target: f32, depth: f32) -> bool {
+        let (shallowest, deepest) = self.depth_reach();
+        (depth - target.clamp(shallowest, deepest)).abs() <= self.sinker.spread() * PRECISE_CAST
+    }
+
+    pub fn max_depth(&self) -> f32 {
+        boats::max_depth(self.boat.as_ref())
+    }
+
+    /// Whether a cast's depth was cut short by the boat (or the shore), when the sinker would have gone deeper
+    pub fn cut_short(&self, depth: f32) -> bool {
+        self.sinker.depth_range.max > self.max_depth() && depth >= self.max_depth()
+    }
+
+    /// worn rods and lines hold less
     pub fn total_strength(&self) -> f32 {
-        self.line.strength as f32 * self.rod.strength_bonus - self.sinker.weight
+        let line = self.line.strength as f32 * durability::stat_multiplier(self.line.durability);
+        let rod = self.rod.strength_bonus * durability::stat_multiplier(self.rod.durability);
+        line * rod - self.sinker.weight
     }
 
+    /// worn rods and reels are slower
     pub fn total_speed_multiplier(&self) -> f32 {
-        self.reel.speed_multiplier * self.rod.efficiency_multiplier
+        let reel = self.reel.speed_multiplier * durability::stat_multiplier(self.reel.durability);
+        let rod = self.rod.efficiency_multiplier * durability::stat_multiplier(self.rod.durability);
+        reel * rod
+    }
+
+    /// How much a cast wears down the gear: a little every cast,
+    /// more for fish close to (or over) the loadout's strength, and a lot if the line snapped
+    pub fn cast_wear(&self, fish: Option<&Fish>, snapped: bool, config: &DurabilityConfig) -> f32 {
+        let mut wear = config.cast_wear;
+        if let Some(fish) = fish {
+            // an infinite excess (a sinker too heavy for the line) is treated as a snap
+            wear += config.heavy_fish_wear * self.qte_excess(fish).min(MAX_WEAR_EXCESS);
+        }
+        if snapped {
+            wear += config.snap_wear;
+        }
+        wear
     }
 
     pub fn catch_chance_multiplier(&self) -> f32 {
@@ -56,8 +128,7 @@ impl RodLoadout {
     }
 
     /// Generate a catch time in seconds, bound by the config
-    pub fn generate_catch_time(&self) -> f32 {
-        let config = Config::load();
+    pub fn generate_catch_time(&self, config: &Config) -> f32 {
         let base_time = config.fishing.base_cast_wait;
 
         let multiplier = self.total_speed_multiplier();
@@ -75,9 +146,44 @@ impl RodLoadout {
         standard_time
     }
 
-    pub fn get_catch_chance_display(&self) -> String {
+    /// The full time in seconds from cast to catch, including the time added by the fish's weight
+    pub fn calculate_cast_time(&self, fish: Option<&Fish>, config: &Config) -> f32 {
+        let mut catch_time = self.generate_catch_time(config);
+        if let Some(f) = fish {
+            let weight_catch_time = (f.weight - f.fish_type.weight_range.average) * config.fishing.fish_weight_time_multiplier;
+            catch_time += weight_catch_time;
+        }
+        catch_time.max(config.fishing.min_cast_wait).min(config.fishing.max_cast_wait)
+    }
+
+    /// The total load on the line when reeling in the given fish (fish weight + sinker weight)
+    pub fn weight_load(&self, fish: &Fish) -> f32 {
+        fish.weight + self.sinker.weight
+    }
+
+    /// How many times more than the loadout's strength the fish weighs (above 1.0 means a QTE)
+    pub fn qte_excess(&self, fish: &Fish) -> f32 {
+        let strength = self.total_strength();
+        if strength <= 0.0 {
+            // a sinker heavier than the line can hold can't take any fish
+            return f32::INFINITY;
+        }
+        self.weight_load(fish) / strength
+    }
+
+    /// Time in seconds the player gets to complete a QTE for the given fish.
+    /// Returns None if the fish is within the loadout's strength and no QTE is needed.
+    pub fn qte_time_limit(&self, fish: &Fish, config: &Config) -> Option<f32> {
+        let ratio = self.qte_excess(fish);
+        if ratio <= 1.0 {
+            return None;
+        }
+
+        Some((config.fishing.base_qte_time / ratio).max(config.fishing.min_qte_time))
+    }
+
+    pub fn get_catch_chance_display(&self, config: &Config) -> String {
         let multiplier = self.catch_chance_multiplier();
-        let config = Config::load();
         let base = config.fishing.base_catch_chance;
 
         let final_chance = base + multiplier;
@@ -97,7 +203,11 @@ impl RodLoadout {
         let min = self.sinker.depth_range.min;
         let max = self.sinker.depth_range.max;
         let average = self.sinker.depth_range.average;
-        format!("{:.2}ft - {:.2}ft (Average: {:.2}ft)", min, max, average)
+        let mut display = format!("{:.2}ft - {:.2}ft (Average: {:.2}ft)", min, max, average);
+        if max > self.max_depth() {
+            display.push_str(&format!("\n⛵ Capped at {:.0}ft", self.max_depth()));
+        }
+        display
     }
 
     pub fn get_total_strength_display(&self) -> String {
@@ -109,9 +219,8 @@ impl RodLoadout {
         format!("{:.2}lbs", total_strength)
     }
 
-    pub fn get_speed_multiplier_display(&self) -> String {
+    pub fn get_speed_multiplier_display(&self, config: &Config) -> String {
         let multiplier = self.total_speed_multiplier();
-        let config = Config::load();
         let base_speed = config.fishing.base_cast_wait;
         let final_speed = base_speed / multiplier;
         // format!(
@@ -135,18 +244,21 @@ impl Default for RodLoadout {
                 sensitivity: -0.1,
                 strength_bonus: 0.8,
                 efficiency_multiplier: 0.8,
+                durability: durability::full(),
             },
             line: Line {
                 name: "Old Thread".to_string(),
                 description: "Cotton thread borrowed from a sewing kit. Snaps if a fish looks at it wrong.".to_string(),
                 price: 0.0,
                 strength: 5,
+                durability: durability::full(),
             },
             reel: Reel {
                 name: "Rusty Can".to_string(),
                 description: "Line wrapped around a rusty tin can. It takes ages to pull anything in.".to_string(),
                 price: 0.0,
                 speed_multiplier: 0.7,
+                durability: durability::full(),
             },
             sinker: Sinker {
                 name: "River Stone".to_string(),
@@ -162,6 +274,7 @@ impl Default for RodLoadout {
             bait: None,
             has_depth_finder: false,
             has_underwater_camera: false,
+            boat: None,
         }
     }
 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::fishing::rod_data::durability;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reel {
     pub name: String,
@@ -9,4 +11,7 @@ pub struct Reel {
     /// 1.0 means normal speed, 2.0 means twice as fast, 0.5 means half as fast.
     /// note: weight also affects speed
     pub speed_multiplier: f32,
+    /// 1.0 when new, wears down with use and the gear breaks at 0.0
+    #[serde(default = "durability::full")]
+    pub durability: f32,
 }
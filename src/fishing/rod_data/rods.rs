@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::fishing::rod_data::durability;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RodBase {
     pub name: String,
@@ -15,4 +17,7 @@ pub struct RodBase {
     /// Multiplies Reel speed
     /// 1.0 is no bonus, 1.5 is 50% faster, etc.
     pub efficiency_multiplier: f32,
+    /// 1.0 when new, wears down with use and the gear breaks at 0.0
+    #[serde(default = "durability::full")]
+    pub durability: f32,
 }
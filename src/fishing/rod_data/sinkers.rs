@@ -1,3 +1,4 @@
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -5,6 +6,9 @@ use crate::{
     fishing::{Attribute, depth::Depth},
 };
 
+/// how far from an aimed depth a sinker can land, as a share of its depth range
+const AIM_SPREAD: f32 = 0.25;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sinker {
     pub name: String,
@@ -18,9 +22,34 @@ pub struct Sinker {
 }
 
 impl Sinker {
-    pub fn generate_depth(&self) -> Result<f32, ReelError> {
+    pub fn generate_depth(&self, rng: &mut impl Rng) -> Result<f32, ReelError> {
         // generate a random depth within the sinker's depth range
-        self.depth_range.triangular_rand()
+        self.depth_range.triangular_rand(rng)
+    }
+
+    /// How far from an aimed depth the sinker can land, sinkers with a narrow range are more accurate
+    pub fn spread(&self) -> f32 {
+        (self.depth_range.max - self.depth_range.min).abs() * AIM_SPREAD
+    }
+
+    /// A depth in the sinker's range near `target`, most likely right on it
+    pub fn aim_depth(&self, target: f32, rng: &mut impl Rng) -> Result<f32, ReelError> {
+        // a range written the wrong way round in the shop file would make `clamp` panic
+        let (min, max) = (
+            self.depth_range.min.min(self.depth_range.max),
+            self.depth_range.min.max(self.depth_range.max),
+        );
+        let target = target.clamp(min, max);
+        let spread = Attribute {
+            min: (target - self.spread()).max(min),
+            max: (target + self.spread()).min(max),
+            average: target,
+        };
+
+        if spread.max <= spread.min {
+            return Ok(target);
+        }
+        spread.triangular_rand(rng)
     }
 
     /// All depth categories encompassed by the sinker's depth range
@@ -35,3 +64,30 @@ impl Sinker {
         categories
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cast::{ROLL_STREAM, cast_rng};
+
+    fn sinker(min: f32, max: f32) -> Sinker {
+        Sinker {
+            name: "Test Sinker".to_string(),
+            description: String::new(),
+            price: 0.0,
+            weight: 0.0,
+            depth_range: Attribute { min, max, average: (min + max) / 2.0 },
+        }
+    }
+
+    #[test]
+    fn aimed_casts_stay_in_the_sinkers_range() {
+        let mut rng = cast_rng(5, ROLL_STREAM);
+        for sinker in [sinker(100.0, 400.0), sinker(400.0, 100.0)] {
+            for target in [0.0, 250.0, 1000.0] {
+                let depth = sinker.aim_depth(target, &mut rng).unwrap();
+                assert!((100.0..=400.0).contains(&depth), "aimed for {} and cast to {}", target, depth);
+            }
+        }
+    }
+}
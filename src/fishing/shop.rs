@@ -1,8 +1,10 @@
 use chrono::{Local, NaiveDate};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::hash::{DefaultHasher, Hash, Hasher};
 
+use crate::fishing::boats::Boat;
+use crate::fishing::gadgets::GadgetCatalog;
 use crate::fishing::rod_data::{
     bait::{Bait, BaitPotency},
     lines::Line,
@@ -10,13 +12,15 @@ use crate::fishing::rod_data::{
     rods::RodBase,
     sinkers::Sinker,
 };
+use crate::data_management::data_path;
 use crate::nay;
 
-const SHOP_STATE_PATH: &str = "./data/gamedata/shop.ron";
-const RODS_PATH: &str = "./data/gamedata/rods.ron";
-const LINES_PATH: &str = "./data/gamedata/lines.ron";
-const REELS_PATH: &str = "./data/gamedata/reels.ron";
-const SINKERS_PATH: &str = "./data/gamedata/sinkers.ron";
+const SHOP_STATE_PATH: &str = "gamedata/shop.ron";
+const RODS_PATH: &str = "gamedata/rods.ron";
+const LINES_PATH: &str = "gamedata/lines.ron";
+const REELS_PATH: &str = "gamedata/reels.ron";
+const SINKERS_PATH: &str = "gamedata/sinkers.ron";
+const BOATS_PATH: &str = "gamedata/boats.ron";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 /// Represents the dynamic state of the shop that changes daily.
@@ -34,6 +38,20 @@ impl Default for ShopState {
     }
 }
 
+impl ShopState {
+    /// Identifies this stock of baits, so buttons from a shop opened before a restock can tell it changed.
+    /// Covers the date and the baits themselves, since the admin tool can restock more than once a day.
+    pub fn stock_id(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.last_refresh.hash(&mut hasher);
+        for bait in &self.daily_baits {
+            bait.name.hash(&mut hasher);
+            bait.price.to_bits().hash(&mut hasher);
+        }
+        format!("{:x}", hasher.finish())
+    }
+}
+
 /// The main Shop structure holding both dynamic and static inventory.
 pub struct Shop {
     pub state: ShopState,
@@ -41,6 +59,8 @@ pub struct Shop {
     pub lines: Vec<Line>,
     pub reels: Vec<Reel>,
     pub sinkers: Vec<Sinker>,
+    pub boats: Vec<Boat>,
+    pub gadgets: GadgetCatalog,
 }
 
 impl Shop {
@@ -54,42 +74,58 @@ impl Shop {
             state = Self::refresh_stock(today);
         }
 
-        // Load static catalog
+        Self {
+            state,
+            ..Self::load_catalog()
+        }
+    }
+
+    /// Loads only the static catalog, without touching (or restocking) the daily state.
+    pub fn load_catalog() -> Self {
         let rods = Self::load_static_data(RODS_PATH).unwrap_or_default();
         let lines = Self::load_static_data(LINES_PATH).unwrap_or_default();
         let reels = Self::load_static_data(REELS_PATH).unwrap_or_default();
         let sinkers = Self::load_static_data(SINKERS_PATH).unwrap_or_default();
+        let boats = Self::load_static_data(BOATS_PATH).unwrap_or_default();
 
         Self {
-            state,
+            state: ShopState::default(),
             rods,
             lines,
             reels,
             sinkers,
+            boats,
+            gadgets: GadgetCatalog::load(),
         }
     }
 
+    /// Forces a new daily stock regardless of when the shop was last refreshed.
+    pub fn restock(&mut self) {
+        self.state = Self::refresh_stock(Local::now().date_naive());
+    }
+
     /// Generates new baits, updates the date, and saves to file.
     fn refresh_stock(date: NaiveDate) -> ShopState {
+        let mut rng = rand::rng();
         let mut baits = Vec::new();
 
         // Generate Daily Stock:
         // 4 Low Potency (Common/Cheap)
-        baits.push(Bait::generate(BaitPotency::Low, false));
-        baits.push(Bait::generate(BaitPotency::Low, false));
-        baits.push(Bait::generate(BaitPotency::Low, false));
-        baits.push(Bait::generate(BaitPotency::Low, false));
+        baits.push(Bait::generate(BaitPotency::Low, false, &mut rng));
+        baits.push(Bait::generate(BaitPotency::Low, false, &mut rng));
+        baits.push(Bait::generate(BaitPotency::Low, false, &mut rng));
+        baits.push(Bait::generate(BaitPotency::Low, false, &mut rng));
 
         // 2 Medium Potency (Decent)
-        baits.push(Bait::generate(BaitPotency::Medium, false));
-        baits.push(Bait::generate(BaitPotency::Medium, false));
-        baits.push(Bait::generate(BaitPotency::Medium, false));
+        baits.push(Bait::generate(BaitPotency::Medium, false, &mut rng));
+        baits.push(Bait::generate(BaitPotency::Medium, false, &mut rng));
+        baits.push(Bait::generate(BaitPotency::Medium, false, &mut rng));
 
         // 1 High Potency (Rare/Expensive)
-        baits.push(Bait::generate(BaitPotency::High, false));
+        baits.push(Bait::generate(BaitPotency::High, false, &mut rng));
 
         // forced lure
-        baits.push(Bait::generate(BaitPotency::High, true));
+        baits.push(Bait::generate(BaitPotency::High, true, &mut rng));
 
         let state = ShopState {
             last_refresh: date,
@@ -100,9 +136,10 @@ impl Shop {
         state
     }
 
-    /// internal helper to load the state file
-    fn load_state() -> ShopState {
-        let path = Path::new(SHOP_STATE_PATH);
+    /// helper to load the state file
+    pub fn load_state() -> ShopState {
+        let raw_path = data_path(SHOP_STATE_PATH);
+        let path = raw_path.as_path();
         if !path.exists() {
             return ShopState::default();
         }
@@ -119,9 +156,10 @@ impl Shop {
         }
     }
 
-    /// internal helper to save the state file
-    fn save_state(state: &ShopState) {
-        let path = Path::new(SHOP_STATE_PATH);
+    /// helper to save the state file
+    pub fn save_state(state: &ShopState) {
+        let raw_path = data_path(SHOP_STATE_PATH);
+        let path = raw_path.as_path();
         // Ensure directory exists
         if let Some(parent) = path.parent() {
             let _ = fs::create_dir_all(parent);
@@ -138,8 +176,9 @@ impl Shop {
     }
 
     /// generic helper to load static RON lists (rods, lines, etc.)
-    fn load_static_data<T: for<'a> Deserialize<'a>>(path_str: &str) -> Option<Vec<T>> {
-        let path = Path::new(path_str);
+    pub(crate) fn load_static_data<T: for<'a> Deserialize<'a>>(path_str: &str) -> Option<Vec<T>> {
+        let raw_path = data_path(path_str);
+        let path = raw_path.as_path();
         if !path.exists() {
             nay!("Static data file missing: {}", path_str);
             return None;
@@ -159,4 +198,23 @@ impl Shop {
             }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_restock_changes_the_stock_id() {
+        let mut rng = rand::rng();
+        let today = Local::now().date_naive();
+        let stock = |baits| ShopState { last_refresh: today, daily_baits: baits };
+
+        let first = stock(vec![Bait::generate(BaitPotency::Low, false, &mut rng)]);
+        let mut restocked = first.clone();
+        restocked.daily_baits[0].price += 1.0;
+
+        assert_eq!(first.stock_id(), first.clone().stock_id());
+        assert_ne!(first.stock_id(), restocked.stock_id());
+        assert_ne!(first.stock_id(), stock(Vec::new()).stock_id());
+    }
+}
@@ -0,0 +1,395 @@
+// Headless Monte-Carlo simulation of the cast -> hook -> QTE or fight -> catch loop.
+// Used to balance the economy without having to fish by hand in discord.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+
+use crate::data_management::config::Config;
+use crate::data_management::userfile::UserValues;
+use crate::error::ReelError;
+use crate::fishing::cast_rules::{is_long_cast, keeps_bait, long_cast_hooks};
+use crate::fishing::fight::{Fight, FightOutcome};
+use crate::fishing::fish_data::fish::Pond;
+use crate::fishing::fish_data::rarity::FishRarity;
+use crate::fishing::rod_data::RodLoadout;
+use crate::fishing::shop::Shop;
+
+/// Knobs for how the simulated player behaves
+#[derive(Debug, Clone)]
+pub struct SimulationSettings {
+    /// how many casts to simulate
+    pub casts: u32,
+    /// cast to this exact depth instead of rolling the sinker's depth
+    pub fixed_depth: Option<f32>,
+    /// chance (0..1) the player completes a QTE they had enough time for
+    pub qte_success_rate: f32,
+    /// seconds the player needs to read and type a QTE code
+    pub qte_reaction_time: f32,
+    /// seed for the run, so a report can be reproduced exactly
+    pub seed: Option<u64>,
+}
+
+impl Default for SimulationSettings {
+    fn default() -> Self {
+        Self {
+            casts: 10_000,
+            fixed_depth: None,
+            qte_success_rate: 0.8,
+            qte_reaction_time: 4.0,
+            seed: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadoutSummary {
+    pub rod: String,
+    pub reel: String,
+    pub line: String,
+    pub sinker: String,
+    pub boat: Option<String>,
+    pub bait: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RarityStat {
+    pub rarity: String,
+    pub count: u32,
+    /// percent of all caught fish
+    pub percent: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TierStat {
+    pub category: String,
+    pub name: String,
+    pub price: f32,
+    /// hours of fishing needed to afford the item from a fresh profile, None if it's never affordable
+    pub hours_to_afford: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationReport {
+    pub loadout: LoadoutSummary,
+    pub casts: u32,
+    /// casts where no fish lived at the cast depth/rarity
+    pub empty_casts: u32,
+    /// fish that didn't take the hook, or got away in a fight
+    pub escaped: u32,
+    pub qtes: u32,
+    /// hooked fish that were fought (the simulated player always makes the careful move)
+    pub fights: u32,
+    pub snapped: u32,
+    pub caught: u32,
+    pub catch_rate: f32,
+    pub snap_rate: f32,
+    pub total_hours: f64,
+    pub gross_earnings: f64,
+    pub bait_spent: f64,
+    pub earnings_per_hour: f64,
+    pub rarity_distribution: Vec<RarityStat>,
+    pub shop_tiers: Vec<TierStat>,
+}
+
+/// A seeded rng, or a random one if no seed is given
+pub fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(&mut rand::rng()),
+    }
+}
+
+/// Run the simulation for the given loadout.
+/// The loadout's bait is treated as an endless supply that is re-bought every time it is used up.
+pub fn simulate(
+    loadout: &RodLoadout,
+    pond: &Pond,
+    shop: &Shop,
+    config: &Config,
+    settings: &SimulationSettings,
+) -> Result<SimulationReport, ReelError> {
+    let mut rng = seeded_rng(settings.seed);
+    let bait = loadout.bait.as_ref();
+
+    let mut empty_casts = 0;
+    let mut escaped = 0;
+    let mut qtes = 0;
+    let mut fights = 0;
+    let mut snapped = 0;
+    let mut caught = 0;
+    let mut total_secs = 0.0_f64;
+    let mut gross_earnings = 0.0_f64;
+    let mut bait_spent = 0.0_f64;
+    let mut rarity_counts: Vec<(FishRarity, u32)> = FishRarity::iter().map(|r| (r, 0)).collect();
+
+    for _ in 0..settings.casts {
+        let depth = match settings.fixed_depth {
+            Some(depth) => depth,
+            None => loadout.generate_depth(None, &mut rng)?,
+        };
+
+        let fish = pond.generate_fish(depth, loadout.near_bottom(depth), bait, config, &mut rng)?;
+        let cast_time = loadout.calculate_cast_time(fish.as_ref(), config);
+        total_secs += cast_time as f64;
+
+        // bait is used up on every catch attempt
+        let mut bait_lost = bait.is_some_and(|b| !b.reusable) && !keeps_bait(&mut rng);
+
+        let Some(fish) = fish else {
+            empty_casts += 1;
+            if bait_lost {
+                bait_spent += bait.map_or(0.0, |b| b.price as f64);
+            }
+            continue;
+        };
+
+        let mut hooked = fish.try_hook(loadout, config, &mut rng);
+        if is_long_cast(cast_time) && !hooked {
+            hooked = long_cast_hooks(&mut rng);
+        }
+
+        if !hooked {
+            escaped += 1;
+        } else if let Some(time_limit) = loadout.qte_time_limit(&fish, config) {
+            qtes += 1;
+            let success = time_limit >= settings.qte_reaction_time
+                && rng.random::<f32>() < settings.qte_success_rate;
+            if success {
+                caught += 1;
+                gross_earnings += fish.value.get() as f64;
+                bump_rarity(&mut rarity_counts, &fish.fish_type.rarity);
+            } else {
+                snapped += 1;
+                // a snapped line always takes the bait (lures included) with it
+                bait_lost = bait.is_some();
+            }
+        } else if Fight::should_fight(&fish, loadout, &config.fight) {
+            fights += 1;
+            let mut fight = Fight::new(&fish, loadout, &config.fight, &mut rng);
+            let outcome = loop {
+                if let Some(outcome) = fight.step(fight.careful_move(), &mut rng) {
+                    break outcome;
+                }
+            };
+            match outcome {
+                FightOutcome::Landed => {
+                    caught += 1;
+                    gross_earnings += fish.value.get() as f64;
+                    bump_rarity(&mut rarity_counts, &fish.fish_type.rarity);
+                }
+                FightOutcome::Snapped => {
+                    snapped += 1;
+                    bait_lost = bait.is_some();
+                }
+                FightOutcome::Escaped | FightOutcome::Outlasted => escaped += 1,
+            }
+        } else {
+            caught += 1;
+            gross_earnings += fish.value.get() as f64;
+            bump_rarity(&mut rarity_counts, &fish.fish_type.rarity);
+        }
+
+        if bait_lost {
+            bait_spent += bait.map_or(0.0, |b| b.price as f64);
+        }
+    }
+
+    let casts = settings.casts.max(1);
+    let total_hours = total_secs / 3600.0;
+    let earnings_per_hour = if total_hours > 0.0 {
+        (gross_earnings - bait_spent) / total_hours
+    } else {
+        0.0
+    };
+
+    let rarity_distribution = rarity_counts
+        .into_iter()
+        .map(|(rarity, count)| RarityStat {
+            rarity: rarity.to_string(),
+            count,
+            percent: if caught > 0 { count as f32 / caught as f32 * 100.0 } else { 0.0 },
+        })
+        .collect();
+
+    Ok(SimulationReport {
+        loadout: LoadoutSummary {
+            rod: loadout.rod.name.clone(),
+            reel: loadout.reel.name.clone(),
+            line: loadout.line.name.clone(),
+            sinker: loadout.sinker.name.clone(),
+            boat: loadout.boat.as_ref().map(|b| b.name.clone()),
+            bait: loadout.bait.as_ref().map(|b| b.name.clone()),
+        },
+        casts: settings.casts,
+        empty_casts,
+        escaped,
+        qtes,
+        fights,
+        snapped,
+        caught,
+        catch_rate: caught as f32 / casts as f32,
+        snap_rate: snapped as f32 / casts as f32,
+        total_hours,
+        gross_earnings,
+        bait_spent,
+        earnings_per_hour,
+        rarity_distribution,
+        shop_tiers: shop_tiers(shop, earnings_per_hour),
+    })
+}
+
+fn bump_rarity(counts: &mut [(FishRarity, u32)], rarity: &FishRarity) {
+    if let Some((_, count)) = counts.iter_mut().find(|(r, _)| r == rarity) {
+        *count += 1;
+    }
+}
+
+/// How long it takes to afford every item in the shop at the given earning rate
+fn shop_tiers(shop: &Shop, earnings_per_hour: f64) -> Vec<TierStat> {
+    let starting_balance = UserValues::default().balance.get() as f64;
+
+    let mut items: Vec<(&str, &str, f32)> = Vec::new();
+    items.extend(shop.rods.iter().map(|i| ("Rods", i.name.as_str(), i.price)));
+    items.extend(shop.reels.iter().map(|i| ("Reels", i.name.as_str(), i.price)));
+    items.extend(shop.lines.iter().map(|i| ("Lines", i.name.as_str(), i.price)));
+    items.extend(shop.sinkers.iter().map(|i| ("Sinkers", i.name.as_str(), i.price)));
+    items.extend(shop.boats.iter().map(|i| ("Boats", i.name.as_str(), i.price)));
+
+    items
+        .into_iter()
+        .map(|(category, name, price)| {
+            let needed = (price as f64 - starting_balance).max(0.0);
+            let hours_to_afford = if needed == 0.0 {
+                Some(0.0)
+            } else if earnings_per_hour > 0.0 {
+                Some(needed / earnings_per_hour)
+            } else {
+                None
+            };
+
+            TierStat {
+                category: category.to_string(),
+                name: name.to_string(),
+                price,
+                hours_to_afford,
+            }
+        })
+        .collect()
+}
+
+impl SimulationReport {
+    /// Human readable table for the terminal
+    pub fn to_table(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("=== Loadout ===\n");
+        out.push_str(&format!("Rod:    {}\n", self.loadout.rod));
+        out.push_str(&format!("Reel:   {}\n", self.loadout.reel));
+        out.push_str(&format!("Line:   {}\n", self.loadout.line));
+        out.push_str(&format!("Sinker: {}\n", self.loadout.sinker));
+        out.push_str(&format!("Boat:   {}\n", self.loadout.boat.as_deref().unwrap_or("None")));
+        out.push_str(&format!("Bait:   {}\n\n", self.loadout.bait.as_deref().unwrap_or("None")));
+
+        out.push_str("=== Results ===\n");
+        out.push_str(&format!("{:<20}{:>14}\n", "Casts", self.casts));
+        out.push_str(&format!("{:<20}{:>14}\n", "Nothing biting", self.empty_casts));
+        out.push_str(&format!("{:<20}{:>14}\n", "Got away", self.escaped));
+        out.push_str(&format!("{:<20}{:>14}\n", "QTEs", self.qtes));
+        out.push_str(&format!("{:<20}{:>14}\n", "Fights", self.fights));
+        out.push_str(&format!("{:<20}{:>14}\n", "Snapped", self.snapped));
+        out.push_str(&format!("{:<20}{:>14}\n", "Caught", self.caught));
+        out.push_str(&format!("{:<20}{:>13.2}%\n", "Catch rate", self.catch_rate * 100.0));
+        out.push_str(&format!("{:<20}{:>13.2}%\n", "Snap rate", self.snap_rate * 100.0));
+        out.push_str(&format!("{:<20}{:>14.2}\n", "Hours fished", self.total_hours));
+        out.push_str(&format!("{:<20}{:>14.2}\n", "Gross earnings $", self.gross_earnings));
+        out.push_str(&format!("{:<20}{:>14.2}\n", "Bait spent $", self.bait_spent));
+        out.push_str(&format!("{:<20}{:>14.2}\n\n", "Net $/hour", self.earnings_per_hour));
+
+        out.push_str("=== Rarity Distribution ===\n");
+        for stat in &self.rarity_distribution {
+            out.push_str(&format!("{:<20}{:>8}{:>9.2}%\n", stat.rarity, stat.count, stat.percent));
+        }
+
+        out.push_str("\n=== Time To Afford ===\n");
+        for tier in &self.shop_tiers {
+            let hours = match tier.hours_to_afford {
+                Some(h) => format!("{:.2}h", h),
+                None => "never".to_string(),
+            };
+            out.push_str(&format!(
+                "{:<10}{:<28}{:>12.2}{:>12}\n",
+                tier.category, tier.name, tier.price, hours
+            ));
+        }
+
+        out
+    }
+
+    /// Flat `section,key,value` rows so runs can be diffed or pasted into a spreadsheet
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("section,key,value\n");
+
+        let summary: [(&str, String); 13] = [
+            ("casts", self.casts.to_string()),
+            ("empty_casts", self.empty_casts.to_string()),
+            ("escaped", self.escaped.to_string()),
+            ("qtes", self.qtes.to_string()),
+            ("fights", self.fights.to_string()),
+            ("snapped", self.snapped.to_string()),
+            ("caught", self.caught.to_string()),
+            ("catch_rate", format!("{:.4}", self.catch_rate)),
+            ("snap_rate", format!("{:.4}", self.snap_rate)),
+            ("total_hours", format!("{:.4}", self.total_hours)),
+            ("gross_earnings", format!("{:.2}", self.gross_earnings)),
+            ("bait_spent", format!("{:.2}", self.bait_spent)),
+            ("earnings_per_hour", format!("{:.2}", self.earnings_per_hour)),
+        ];
+        for (key, value) in summary {
+            csv_row(&mut out, "summary", key, &value);
+        }
+
+        for stat in &self.rarity_distribution {
+            csv_row(&mut out, "rarity", &stat.rarity.to_string(), &stat.count.to_string());
+        }
+
+        for tier in &self.shop_tiers {
+            let hours = tier.hours_to_afford.map(|h| format!("{:.4}", h)).unwrap_or_default();
+            csv_row(&mut out, "hours_to_afford", &format!("{}/{}", tier.category, tier.name), &hours);
+        }
+
+        out
+    }
+
+    pub fn to_json(&self) -> Result<String, ReelError> {
+        serde_json::to_string_pretty(self).map_err(|e| ReelError::Serialize(format!("simulation report: {}", e)))
+    }
+}
+
+/// Add a `section,key,value` row, quoting fields with commas, quotes or line breaks (e.g. item names)
+fn csv_row(out: &mut String, section: &str, key: &str, value: &str) {
+    let fields: Vec<String> = [section, key, value]
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    out.push_str(&fields.join(","));
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_with_commas_and_quotes_are_quoted() {
+        let mut out = String::new();
+        csv_row(&mut out, "hours_to_afford", "Rod/The \"Big\" One, Mk II", "1.5000");
+        assert_eq!(out, "hours_to_afford,\"Rod/The \"\"Big\"\" One, Mk II\",1.5000\n");
+    }
+}
@@ -31,6 +31,7 @@ impl ConfigEditor {
                     ui.text_edit_singleline(&mut self.config.general.motd);
                 });
                 ui.checkbox(&mut self.config.general.log_cast_data, "Log Cast Data");
+                ui.checkbox(&mut self.config.general.headless, "Headless (hides this window on next start)");
             });
 
             // Fishing
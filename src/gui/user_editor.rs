@@ -73,7 +73,7 @@ impl UserEditor {
     pub fn refresh_users(&mut self) {
         // Scan data/users directory
         self.user_ids.clear();
-        if let Ok(entries) = std::fs::read_dir("./data/users") {
+        if let Ok(entries) = std::fs::read_dir(crate::data_management::data_path("users")) {
             for entry in entries.filter_map(Result::ok) {
                 let path = entry.path();
                 if path.extension().map_or(false, |ext| ext == "ron") {
@@ -139,7 +139,13 @@ impl UserEditor {
 
                                 if ui.selectable_label(self.selected_user_id == Some(*id), name).clicked() {
                                     self.selected_user_id = Some(*id);
-                                    self.selected_user_file = Some(UserFile::read(id));
+                                    match UserFile::read(id) {
+                                        Ok(user_file) => self.selected_user_file = Some(user_file),
+                                        Err(e) => {
+                                            self.selected_user_file = None;
+                                            self.status_message = Some((format!("Failed to load user: {}", e), std::time::Instant::now()));
+                                        }
+                                    }
                                     self.editing_item = None; 
                                 }
                             });
@@ -206,7 +212,7 @@ impl UserEditor {
             
             ui.collapsing("Bait Bucket", |ui| {
                 if ui.button("+ Add New Custom Bait").clicked() {
-                    let default_bait = Bait::generate(BaitPotency::Low, false);
+                    let default_bait = Bait::generate(BaitPotency::Low, false, &mut rand::rng());
                     self.editing_item = Some(EditingItem::NewBait(default_bait));
                 }
                 
@@ -215,10 +221,11 @@ impl UserEditor {
                 let mut index_to_edit = None;
                 let mut index_to_delete = None;
 
-                for (i, bait) in user_file.file.inventory.bait_bucket.baits.iter().enumerate() {
+                for (i, stack) in user_file.file.inventory.bait_bucket.stacks.iter_mut().enumerate() {
                     ui.push_id(format!("bait_row_{}", i), |ui| {
                         ui.horizontal(|ui| {
-                            ui.label(format!("{}. {}", i+1, bait.name));
+                            ui.label(format!("{}. {}", i+1, stack.bait.name));
+                            ui.add(egui::DragValue::new(&mut stack.count).range(1..=999).prefix("x"));
                             if ui.button("Edit").clicked() {
                                 index_to_edit = Some(i);
                             }
@@ -236,6 +243,11 @@ impl UserEditor {
                 }
                 if let Some(i) = index_to_delete {
                      user_file.file.inventory.bait_bucket.remove_index(i);
+                     user_file.file.inventory.selected_bait = match user_file.file.inventory.selected_bait {
+                         Some(selected) if selected == i => None,
+                         Some(selected) if selected > i => Some(selected - 1),
+                         other => other,
+                     };
                 }
             });
 
@@ -249,6 +261,7 @@ impl UserEditor {
                             sensitivity: 1.0,
                             strength_bonus: 1.0,
                             efficiency_multiplier: 1.0,
+                            durability: 1.0,
                         };
                         self.editing_item = Some(EditingItem::NewRod(new_rod));
                     }
@@ -296,6 +309,7 @@ impl UserEditor {
                              description: "".to_string(),
                              price: 0.0,
                              speed_multiplier: 1.0,
+                             durability: 1.0,
                          };
                          self.editing_item = Some(EditingItem::NewReel(new_reel));
                      }
@@ -343,6 +357,7 @@ impl UserEditor {
                              description: "".to_string(),
                              price: 0.0,
                              strength: 10,
+                             durability: 1.0,
                          };
                          self.editing_item = Some(EditingItem::NewLine(new_line));
                      }
@@ -459,7 +474,7 @@ impl UserEditor {
                             self.status_message = Some(("Created new bait!".to_string(), std::time::Instant::now()));
                         },
                         EditingItem::ExistingBait { index, bait } => {
-                             if let Some(existing) = user_file.file.inventory.bait_bucket.baits.get_mut(*index) {
+                             if let Some(existing) = user_file.file.inventory.bait_bucket.stacks.get_mut(*index).map(|s| &mut s.bait) {
                                  *existing = bait.clone();
                                  self.status_message = Some(("Updated bait!".to_string(), std::time::Instant::now()));
                              }
@@ -479,6 +494,7 @@ impl UserEditor {
                  ui.horizontal(|ui| { ui.label("Sensitivity"); ui.add(egui::DragValue::new(&mut val.sensitivity).speed(0.01)); });
                  ui.horizontal(|ui| { ui.label("Strength Bonus"); ui.add(egui::DragValue::new(&mut val.strength_bonus).speed(0.01)); });
                  ui.horizontal(|ui| { ui.label("Efficiency"); ui.add(egui::DragValue::new(&mut val.efficiency_multiplier).speed(0.01)); });
+                 ui.horizontal(|ui| { ui.label("Durability"); ui.add(egui::Slider::new(&mut val.durability, 0.0..=1.0)); });
                  
                  ui.separator();
                  if ui.button("Save Rod").clicked() {
@@ -506,6 +522,7 @@ impl UserEditor {
                  ui.horizontal(|ui| { ui.label("Desc"); ui.text_edit_singleline(&mut val.description); });
                  ui.horizontal(|ui| { ui.label("Price"); ui.add(egui::DragValue::new(&mut val.price)); });
                  ui.horizontal(|ui| { ui.label("Speed Mult"); ui.add(egui::DragValue::new(&mut val.speed_multiplier).speed(0.01)); });
+                 ui.horizontal(|ui| { ui.label("Durability"); ui.add(egui::Slider::new(&mut val.durability, 0.0..=1.0)); });
 
                  ui.separator();
                  if ui.button("Save Reel").clicked() {
@@ -533,6 +550,7 @@ impl UserEditor {
                  ui.horizontal(|ui| { ui.label("Desc"); ui.text_edit_singleline(&mut val.description); });
                  ui.horizontal(|ui| { ui.label("Price"); ui.add(egui::DragValue::new(&mut val.price)); });
                  ui.horizontal(|ui| { ui.label("Strength (lbs)"); ui.add(egui::DragValue::new(&mut val.strength)); });
+                 ui.horizontal(|ui| { ui.label("Durability"); ui.add(egui::Slider::new(&mut val.durability, 0.0..=1.0)); });
 
                  ui.separator();
                  if ui.button("Save Line").clicked() {
@@ -1,71 +1,447 @@
 use core::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU8, Ordering};
 
 use better_term::{Color, flush_styles};
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::data_management::config::Logging;
+use crate::data_management::data_path;
+
+const LOG_DIR: &str = "logs";
+
+/// How important a log line is. say!/yay!/wow! are Info, hey! is Warn and nay! is Error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LogLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    fn from_u8(level: u8) -> Self {
+        match level {
+            0 => LogLevel::Info,
+            1 => LogLevel::Warn,
+            _ => LogLevel::Error,
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogLevel::Info => write!(f, "INFO"),
+            LogLevel::Warn => write!(f, "WARN"),
+            LogLevel::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+/// A structured field on a log line, e.g. `("user", &user_id)`
+pub type LogField<'a> = (&'static str, &'a dyn fmt::Display);
+
+// anything below this level is dropped
+static MIN_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+// None until `init` turns file logging on (so the CLI tools and tests never write logs)
+static LOG_FILE: Mutex<Option<LogFile>> = Mutex::new(None);
+
+struct LogFile {
+    dir: PathBuf,
+    retention_days: u32,
+    // the day the open file is for, a new file is started when this changes
+    date: NaiveDate,
+    file: Option<File>,
+}
+
+impl LogFile {
+    fn write_line(&mut self, line: &str) {
+        let today = Local::now().date_naive();
+        if self.file.is_none() || self.date != today {
+            self.date = today;
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.dir.join(format!("{}.log", today)))
+                .ok();
+            remove_old_logs(&self.dir, today, self.retention_days);
+        }
+
+        if let Some(file) = &mut self.file {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+/// Apply the logging section of the config and start writing to ./data/logs/YYYY-MM-DD.log
+pub fn init(settings: &Logging) {
+    MIN_LEVEL.store(settings.level as u8, Ordering::Relaxed);
+
+    let mut log_file = LOG_FILE.lock().unwrap_or_else(|e| e.into_inner());
+    if !settings.file_logging {
+        *log_file = None;
+        return;
+    }
+
+    let dir = logs_dir();
+    if let Err(e) = fs::create_dir_all(&dir) {
+        // can't log this through the file, so only print it
+        println!("Failed to create the log directory {}: {}", dir.display(), e);
+        return;
+    }
+
+    *log_file = Some(LogFile {
+        dir,
+        retention_days: settings.retention_days,
+        date: Local::now().date_naive(),
+        file: None,
+    });
+}
+
+pub fn logs_dir() -> PathBuf {
+    data_path(LOG_DIR)
+}
+
+/// Delete log files older than `retention_days` (0 keeps them forever)
+fn remove_old_logs(dir: &Path, today: NaiveDate, retention_days: u32) {
+    if retention_days == 0 {
+        return;
+    }
+
+    for (date, path) in log_files(dir) {
+        if (today - date).num_days() > retention_days as i64 {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Every daily log file in `dir`, newest first
+fn log_files(dir: &Path) -> Vec<(NaiveDate, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<(NaiveDate, PathBuf)> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|p| {
+            let date = p.file_stem()?.to_str()?.parse::<NaiveDate>().ok()?;
+            Some((date, p))
+        })
+        .collect();
+
+    files.sort_by_key(|(date, _)| std::cmp::Reverse(*date));
+    files
+}
+
+/// `{user=1 code=ABC} ` or nothing if there are no fields.
+/// Values can't contain spaces or braces so lines can be parsed back.
+fn format_fields(fields: &[LogField]) -> String {
+    if fields.is_empty() {
+        return String::new();
+    }
+
+    let fields = fields
+        .iter()
+        .map(|(key, value)| {
+            let value: String = value
+                .to_string()
+                .chars()
+                .map(|c| if c.is_whitespace() || c == '{' || c == '}' { '_' } else { c })
+                .collect();
+            format!("{}={}", key, value)
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    format!("{{{}}} ", fields)
+}
+
+fn raw_log(level: LogLevel, prefix: String, msg_color: Color, fields: &[LogField], args: fmt::Arguments) {
+    let fields = format_fields(fields);
 
-fn raw_log(prefix: String, msg_color: Color, args: fmt::Arguments) {
     let msg = format!(
-        "{b}[{}{b}] {}{}",
+        "{b}[{}{b}] {}{}{}",
         prefix,
+        fields,
         msg_color,
         args,
         b = Color::BrightBlack
     );
     println!("{}", msg);
-    
+
     // Push to GUI console
-    let clean_msg = format!("[{}] {}", prefix, args);
-    crate::gui::logging::push_log(clean_msg, msg_color);
+    #[cfg(feature = "gui")]
+    {
+        let clean_msg = format!("[{}] {}{}", prefix, fields, args);
+        crate::gui::logging::push_log(clean_msg, msg_color);
+    }
 
     flush_styles();
+
+    let mut log_file = LOG_FILE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(log_file) = log_file.as_mut() {
+        let line = format!(
+            "{} {} {}{}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            level,
+            fields,
+            args.to_string().replace('\n', "\\n")
+        );
+        log_file.write_line(&line);
+    }
+}
+
+fn enabled(level: LogLevel) -> bool {
+    level >= LogLevel::from_u8(MIN_LEVEL.load(Ordering::Relaxed))
 }
 
-pub fn _wow(args: fmt::Arguments) {
+pub fn _wow(fields: &[LogField], args: fmt::Arguments) {
+    if !enabled(LogLevel::Info) {
+        return;
+    }
     println!();
     raw_log(
+        LogLevel::Info,
         format!("{}$", Color::BrightPurple),
         Color::BrightPurple,
+        fields,
         args,
     );
     println!();
 }
 
+/// All the log macros take optional structured fields before the message:
+/// `nay!({user: id, code: error_code}, "Something went wrong: {}", e)`
 #[macro_export]
 macro_rules! wow {
-    ($($arg:tt)*) => { $crate::logging::_wow(format_args!($($arg)*)) }
+    ({ $($key:ident : $value:expr),* $(,)? }, $($arg:tt)*) => {
+        $crate::logging::_wow(&[$((stringify!($key), &$value as &dyn ::std::fmt::Display)),*], format_args!($($arg)*))
+    };
+    ($($arg:tt)*) => { $crate::logging::_wow(&[], format_args!($($arg)*)) }
 }
 
-pub fn _say(args: fmt::Arguments) {
-    raw_log(format!("{}#", Color::White), Color::BrightWhite, args);
+pub fn _say(fields: &[LogField], args: fmt::Arguments) {
+    if enabled(LogLevel::Info) {
+        raw_log(LogLevel::Info, format!("{}#", Color::White), Color::BrightWhite, fields, args);
+    }
 }
 
 #[macro_export]
 macro_rules! say {
-    ($($arg:tt)*) => { $crate::logging::_say(format_args!($($arg)*)) }
+    ({ $($key:ident : $value:expr),* $(,)? }, $($arg:tt)*) => {
+        $crate::logging::_say(&[$((stringify!($key), &$value as &dyn ::std::fmt::Display)),*], format_args!($($arg)*))
+    };
+    ($($arg:tt)*) => { $crate::logging::_say(&[], format_args!($($arg)*)) }
 }
 
-pub fn _yay(args: fmt::Arguments) {
-    raw_log(format!("{}✔", Color::BrightGreen), Color::BrightGreen, args);
+pub fn _yay(fields: &[LogField], args: fmt::Arguments) {
+    if enabled(LogLevel::Info) {
+        raw_log(LogLevel::Info, format!("{}✔", Color::BrightGreen), Color::BrightGreen, fields, args);
+    }
 }
 
 #[macro_export]
 macro_rules! yay {
-    ($($arg:tt)*) => { $crate::logging::_yay(format_args!($($arg)*)) }
+    ({ $($key:ident : $value:expr),* $(,)? }, $($arg:tt)*) => {
+        $crate::logging::_yay(&[$((stringify!($key), &$value as &dyn ::std::fmt::Display)),*], format_args!($($arg)*))
+    };
+    ($($arg:tt)*) => { $crate::logging::_yay(&[], format_args!($($arg)*)) }
 }
 
-pub fn _hey(args: fmt::Arguments) {
-    raw_log(format!("{}!", Color::Yellow), Color::BrightYellow, args);
+pub fn _hey(fields: &[LogField], args: fmt::Arguments) {
+    if enabled(LogLevel::Warn) {
+        raw_log(LogLevel::Warn, format!("{}!", Color::Yellow), Color::BrightYellow, fields, args);
+    }
 }
 
 #[macro_export]
 macro_rules! hey {
-    ($($arg:tt)*) => { $crate::logging::_hey(format_args!($($arg)*)) }
+    ({ $($key:ident : $value:expr),* $(,)? }, $($arg:tt)*) => {
+        $crate::logging::_hey(&[$((stringify!($key), &$value as &dyn ::std::fmt::Display)),*], format_args!($($arg)*))
+    };
+    ($($arg:tt)*) => { $crate::logging::_hey(&[], format_args!($($arg)*)) }
 }
 
-pub fn _nay(args: fmt::Arguments) {
-    raw_log(format!("{}✘", Color::Red), Color::BrightRed, args);
+pub fn _nay(fields: &[LogField], args: fmt::Arguments) {
+    if enabled(LogLevel::Error) {
+        raw_log(LogLevel::Error, format!("{}✘", Color::Red), Color::BrightRed, fields, args);
+    }
 }
 
 #[macro_export]
 macro_rules! nay {
-    ($($arg:tt)*) => { $crate::logging::_nay(format_args!($($arg)*)) }
+    ({ $($key:ident : $value:expr),* $(,)? }, $($arg:tt)*) => {
+        $crate::logging::_nay(&[$((stringify!($key), &$value as &dyn ::std::fmt::Display)),*], format_args!($($arg)*))
+    };
+    ($($arg:tt)*) => { $crate::logging::_nay(&[], format_args!($($arg)*)) }
+}
+
+/// One line read back from a log file
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    pub timestamp: String,
+    pub level: String,
+    pub fields: Vec<(String, String)>,
+    pub message: String,
+}
+
+impl LogLine {
+    pub fn parse(line: &str) -> Option<Self> {
+        let (date, rest) = line.split_once(' ')?;
+        let (time, rest) = rest.split_once(' ')?;
+        let (level, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+
+        let (fields, message) = match rest.strip_prefix('{').and_then(|r| r.split_once("} ")) {
+            Some((fields, message)) => (
+                fields
+                    .split_whitespace()
+                    .filter_map(|f| f.split_once('='))
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                message,
+            ),
+            None => (Vec::new(), rest),
+        };
+
+        Some(Self {
+            timestamp: format!("{} {}", date, time),
+            level: level.to_string(),
+            fields,
+            message: message.to_string(),
+        })
+    }
+
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+}
+
+impl fmt::Display for LogLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ", self.timestamp, self.level)?;
+        if !self.fields.is_empty() {
+            let fields = self
+                .fields
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<String>>()
+                .join(" ");
+            write!(f, "{{{}}} ", fields)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// A logged error code and what the same user was doing just before it
+#[derive(Debug, Clone)]
+pub struct ErrorCodeMatch {
+    pub line: LogLine,
+    pub context: Vec<LogLine>,
+}
+
+/// Search the logs in `dir` (newest first) for an error code players reported.
+/// Each match comes with up to `context` earlier lines from the same user.
+/// With a `guild`, only lines logged for that guild are searched, so one server's admins can't read another's.
+pub fn find_error_code(dir: &Path, code: &str, guild: Option<&str>, context: usize) -> Vec<ErrorCodeMatch> {
+    let mut matches = Vec::new();
+
+    for (_, path) in log_files(dir) {
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let lines: Vec<LogLine> = contents
+            .lines()
+            .filter_map(LogLine::parse)
+            .filter(|l| guild.is_none() || l.field("guild") == guild)
+            .collect();
+
+        for (i, line) in lines.iter().enumerate() {
+            if line.field("code") != Some(code) && !line.message.contains(code) {
+                continue;
+            }
+
+            let user = line.field("user");
+            let mut before: Vec<LogLine> = lines[..i]
+                .iter()
+                .rev()
+                .filter(|l| user.is_none() || l.field("user") == user)
+                .take(context)
+                .cloned()
+                .collect();
+            before.reverse();
+
+            matches.push(ErrorCodeMatch { line: line.clone(), context: before });
+        }
+    }
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestDataDir;
+
+    #[test]
+    fn fields_round_trip_through_a_log_line() {
+        let user = 42u64;
+        let line = format!(
+            "2026-01-02 03:04:05 ERROR {}Fish Fail To Generate",
+            format_fields(&[("user", &user), ("command", &"cast two")])
+        );
+
+        let parsed = LogLine::parse(&line).unwrap();
+        assert_eq!(parsed.level, "ERROR");
+        assert_eq!(parsed.field("user"), Some("42"));
+        assert_eq!(parsed.field("command"), Some("cast_two"));
+        assert_eq!(parsed.message, "Fish Fail To Generate");
+        assert_eq!(parsed.to_string(), line);
+    }
+
+    #[test]
+    fn finds_error_codes_with_context_from_the_same_user() {
+        let dir = TestDataDir::new();
+        let logs = dir.path.join(LOG_DIR);
+        fs::create_dir_all(&logs).unwrap();
+        fs::write(
+            logs.join("2026-01-02.log"),
+            [
+                "2026-01-02 10:00:00 INFO {user=1} cast their line",
+                "2026-01-02 10:00:01 INFO {user=2} cast their line",
+                "2026-01-02 10:00:02 ERROR {user=1 code=FISH_FTG-abc12} Fish Fail To Generate",
+                "2026-01-02 10:00:03 INFO no fields here",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let found = find_error_code(&logs, "FISH_FTG-abc12", None, 5);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].line.message, "Fish Fail To Generate");
+        assert_eq!(found[0].context.len(), 1);
+        assert_eq!(found[0].context[0].field("user"), Some("1"));
+
+        assert!(find_error_code(&logs, "FISH_FTG-zzzzz", None, 5).is_empty());
+    }
+
+    #[test]
+    fn old_logs_are_removed() {
+        let dir = TestDataDir::new();
+        let logs = dir.path.join(LOG_DIR);
+        fs::create_dir_all(&logs).unwrap();
+        for day in ["2026-01-01", "2026-01-10", "2026-01-14"] {
+            fs::write(logs.join(format!("{}.log", day)), "").unwrap();
+        }
+
+        remove_old_logs(&logs, NaiveDate::from_ymd_opt(2026, 1, 15).unwrap(), 7);
+
+        let kept: Vec<NaiveDate> = log_files(&logs).into_iter().map(|(d, _)| d).collect();
+        assert_eq!(kept, vec![NaiveDate::from_ymd_opt(2026, 1, 14).unwrap(), NaiveDate::from_ymd_opt(2026, 1, 10).unwrap()]);
+    }
 }
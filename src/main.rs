@@ -6,6 +6,7 @@ use crate::{data_management::config::Config, fishing::fish_data::fish::FishType}
 use serenity::{Client, all::GatewayIntents};
 use crate::data_management::version_uf_converter::convert_old_userfiles;
 
+mod cli;
 mod commands;
 pub mod data_management;
 mod discord;
@@ -13,13 +14,43 @@ pub mod error;
 pub mod fishing;
 pub mod helpers;
 pub mod logging;
+pub mod messenger;
+#[cfg(test)]
+mod testing;
+#[cfg(feature = "gui")]
 pub mod gui;
 
 #[tokio::main]
 async fn main() {
     // Initialize global log buffer
+    #[cfg(feature = "gui")]
     crate::gui::logging::GLOBAL_LOG_BUFFER.set(crate::gui::logging::LogBuffer::new(1000)).ok();
 
+    // handle command line tools before starting the bot
+    let bot_args = match cli::parse(env::args().skip(1)) {
+        Ok(cli::CliCommand::RunBot(args)) => args,
+        Ok(cli::CliCommand::Simulate(args)) => {
+            cli::simulate::run_simulation(*args);
+            return;
+        }
+        Ok(cli::CliCommand::Admin(command)) => {
+            if let Err(e) = cli::admin::run(command) {
+                nay!("{}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Ok(cli::CliCommand::Help) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Err(e) => {
+            nay!("{}", e);
+            println!("{}", cli::USAGE);
+            return;
+        }
+    };
+
     yay!("Angler Bot is starting up!");
 
     // Create the data directory if it doesn't exist
@@ -67,6 +98,9 @@ async fn main() {
         default_config.save();
     }
 
+    // start writing the logs to ./data/logs now that the config exists
+    logging::init(&Config::load().logging);
+
     // create ./data/gamedata/ if it doesn't exist
     let Ok(exists) = std::fs::exists("./data/gamedata") else {
         nay!("Failed to check if gamedata directory exists");
@@ -114,7 +148,10 @@ async fn main() {
     convert_old_userfiles();
 
     // get the env variables
-    dotenv::dotenv().expect("Failed to load .env file");
+    // a missing .env is fine as long as DISCORD_TOKEN is set some other way
+    if let Err(e) = dotenv::dotenv() {
+        hey!("Failed to load .env file: {}", e);
+    }
 
     let Ok(token) = env::var("DISCORD_TOKEN") else {
         nay!("DISCORD_TOKEN not found in environment");
@@ -126,7 +163,7 @@ async fn main() {
         | GatewayIntents::DIRECT_MESSAGES
         | GatewayIntents::MESSAGE_CONTENT;
 
-    let Ok(mut client) = Client::builder(token, intents)
+    let Ok(client) = Client::builder(token, intents)
         .event_handler(Handler::new())
         .await
     else {
@@ -134,7 +171,74 @@ async fn main() {
         return;
     };
 
+    let config = Config::load();
+    let headless = bot_args.headless || config.general.headless || cfg!(not(feature = "gui"));
+
+    if headless {
+        run_headless(client).await;
+    } else {
+        #[cfg(feature = "gui")]
+        run_with_gui(client);
+    }
+}
+
+/// Run the bot without the admin window, until it stops or the process is told to shut down
+async fn run_headless(mut client: Client) {
+    say!("Running in headless mode. Press Ctrl+C to stop.");
+
+    let shard_manager = client.shard_manager.clone();
+
+    tokio::select! {
+        result = client.start() => {
+            if let Err(e) = result {
+                nay!("Client error: {}", e);
+            }
+        }
+        _ = shutdown_signal() => {
+            hey!("Shutdown signal received, disconnecting...");
+            shard_manager.shutdown_all().await;
+        }
+    }
+
+    yay!("Angler Bot has shut down.");
+}
+
+/// Resolves on SIGINT (Ctrl+C) or, on unix, SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            nay!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                nay!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+/// Run the bot in the background and block on the egui admin window
+#[cfg(feature = "gui")]
+fn run_with_gui(mut client: Client) {
     let http = client.http.clone();
+    let shard_manager = client.shard_manager.clone();
 
     // Spawn the client in a background task
     tokio::spawn(async move {
@@ -150,4 +254,9 @@ async fn main() {
         options,
         Box::new(|cc| Ok(Box::new(crate::gui::app::AnglerApp::new(cc, Some(http))))),
     );
+
+    // window closed, disconnect cleanly before exiting
+    tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current().block_on(shard_manager.shutdown_all());
+    });
 }
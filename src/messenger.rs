@@ -0,0 +1,116 @@
+// everything the fishing flow sends to discord goes through a Messenger,
+// so the flow can run against an in-memory fake in tests
+
+use std::time::Duration;
+
+use serenity::{
+    all::{
+        ChannelId, ComponentInteractionCollector, Context, CreateInteractionResponse,
+        CreateInteractionResponseFollowup, CreateMessage, EditInteractionResponse, EditMessage,
+        InteractionId, MessageId, UserId,
+    },
+    async_trait,
+    builder::Builder,
+};
+
+use crate::error::ReelError;
+
+#[async_trait]
+pub trait Messenger: Send + Sync {
+    async fn send_message(&self, channel: ChannelId, message: CreateMessage) -> Result<MessageId, ReelError>;
+
+    async fn edit_message(&self, channel: ChannelId, message: MessageId, edit: EditMessage) -> Result<(), ReelError>;
+
+    /// Wait for the next message `user` sends in `channel`.
+    /// Returns None if they don't reply before the timeout.
+    async fn await_reply(&self, channel: ChannelId, user: UserId, timeout: Duration) -> Option<String>;
+
+    /// Answer an interaction, using its id and token
+    async fn create_response(&self, interaction: InteractionId, token: &str, response: CreateInteractionResponse) -> Result<(), ReelError>;
+
+    /// Edit the original response of an interaction, using the interaction's token
+    async fn edit_response(&self, token: &str, edit: EditInteractionResponse) -> Result<(), ReelError>;
+
+    /// Send a follow up to an interaction, using the interaction's token
+    async fn send_followup(&self, token: &str, followup: CreateInteractionResponseFollowup) -> Result<MessageId, ReelError>;
+
+    /// Edit a follow up sent with `send_followup`
+    async fn edit_followup(&self, token: &str, message: MessageId, edit: CreateInteractionResponseFollowup) -> Result<(), ReelError>;
+
+    /// Send a message to `user`'s DMs. Returns the DM channel with the message, so it can be edited
+    async fn send_dm(&self, user: UserId, message: CreateMessage) -> Result<(ChannelId, MessageId), ReelError>;
+
+    /// Wait for `user` to press a button on `message`, and return the button's custom_id.
+    /// Returns None if they don't press one before the timeout.
+    async fn await_button(&self, message: MessageId, user: UserId, timeout: Duration) -> Option<String>;
+}
+
+/// The real messenger, backed by the serenity client
+pub struct SerenityMessenger {
+    pub ctx: Context,
+}
+
+impl SerenityMessenger {
+    pub fn new(ctx: Context) -> Self {
+        Self { ctx }
+    }
+}
+
+#[async_trait]
+impl Messenger for SerenityMessenger {
+    async fn send_message(&self, channel: ChannelId, message: CreateMessage) -> Result<MessageId, ReelError> {
+        Ok(channel.send_message(&self.ctx.http, message).await?.id)
+    }
+
+    async fn edit_message(&self, channel: ChannelId, message: MessageId, edit: EditMessage) -> Result<(), ReelError> {
+        channel.edit_message(&self.ctx.http, message, edit).await?;
+        Ok(())
+    }
+
+    async fn await_reply(&self, channel: ChannelId, user: UserId, timeout: Duration) -> Option<String> {
+        channel
+            .await_reply(&self.ctx)
+            .author_id(user)
+            .timeout(timeout)
+            .await
+            .map(|msg| msg.content)
+    }
+
+    async fn create_response(&self, interaction: InteractionId, token: &str, response: CreateInteractionResponse) -> Result<(), ReelError> {
+        response.execute(&self.ctx, (interaction, token)).await?;
+        Ok(())
+    }
+
+    async fn edit_response(&self, token: &str, edit: EditInteractionResponse) -> Result<(), ReelError> {
+        edit.execute(&self.ctx, token).await?;
+        Ok(())
+    }
+
+    async fn send_followup(&self, token: &str, followup: CreateInteractionResponseFollowup) -> Result<MessageId, ReelError> {
+        Ok(followup.execute(&self.ctx, (None, token)).await?.id)
+    }
+
+    async fn edit_followup(&self, token: &str, message: MessageId, edit: CreateInteractionResponseFollowup) -> Result<(), ReelError> {
+        edit.execute(&self.ctx, (Some(message), token)).await?;
+        Ok(())
+    }
+
+    async fn send_dm(&self, user: UserId, message: CreateMessage) -> Result<(ChannelId, MessageId), ReelError> {
+        let channel = user.create_dm_channel(&self.ctx.http).await?;
+        Ok((channel.id, channel.id.send_message(&self.ctx.http, message).await?.id))
+    }
+
+    async fn await_button(&self, message: MessageId, user: UserId, timeout: Duration) -> Option<String> {
+        let interaction = ComponentInteractionCollector::new(&self.ctx)
+            .message_id(message)
+            .author_id(user)
+            .timeout(timeout)
+            .await?;
+
+        // the press has to be answered or discord shows it as failed
+        if let Err(e) = interaction.create_response(&self.ctx.http, CreateInteractionResponse::Acknowledge).await {
+            crate::hey!("Failed to acknowledge a button press: {}", e);
+        }
+        Some(interaction.data.custom_id)
+    }
+}
@@ -0,0 +1,213 @@
+// helpers for running the bot's logic in tests without discord or the real ./data folder
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use serde_json::Value;
+use serenity::all::{ChannelId, CreateInteractionResponse, CreateInteractionResponseFollowup, CreateMessage, EditInteractionResponse, EditMessage, InteractionId, MessageId, UserId};
+use serenity::async_trait;
+
+use crate::data_management::config::Config;
+use crate::data_management::monetary::MonetaryAmount;
+use crate::data_management::{data_path, set_test_data_dir};
+use crate::error::ReelError;
+use crate::fishing::Attribute;
+use crate::fishing::fish_data::fish::{Fish, FishCategory, FishType, Pond};
+use crate::fishing::fish_data::rarity::FishRarity;
+use crate::messenger::Messenger;
+
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// A fresh data directory for one test. `data_path` points at it until it is dropped.
+pub struct TestDataDir {
+    pub path: PathBuf,
+}
+
+impl TestDataDir {
+    pub fn new() -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "angler_test_{}_{}",
+            std::process::id(),
+            NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        set_test_data_dir(Some(path.clone()));
+
+        for dir in ["users", "guilds", "gamedata"] {
+            std::fs::create_dir_all(data_path(dir)).expect("failed to create test data directory");
+        }
+
+        Self { path }
+    }
+
+    /// write a config for the test to use
+    pub fn with_config(self, config: &Config) -> Self {
+        config.save();
+        self
+    }
+}
+
+impl Drop for TestDataDir {
+    fn drop(&mut self) {
+        set_test_data_dir(None);
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// What the fake "player" does when the bot waits for a reply or a button press
+pub enum FakeReply {
+    Text(String),
+    /// press the button with this custom_id
+    Button(String),
+    /// build a reply from the last message the bot sent (e.g. to type a QTE code)
+    FromLastMessage(Box<dyn Fn(&Value) -> String + Send>),
+    Timeout,
+}
+
+/// A Messenger that records everything instead of talking to discord
+#[derive(Default)]
+pub struct FakeMessenger {
+    pub sent: Mutex<Vec<(ChannelId, Value)>>,
+    /// responses to interactions, by token
+    pub responses: Mutex<Vec<(String, Value)>>,
+    pub edits: Mutex<Vec<(String, Value)>>,
+    pub followups: Mutex<Vec<(String, Value)>>,
+    /// edits of sent messages and follow ups
+    pub message_edits: Mutex<Vec<(MessageId, Value)>>,
+    pub dms: Mutex<Vec<(UserId, Value)>>,
+    /// the channels the bot waited for a typed reply in
+    pub reply_channels: Mutex<Vec<ChannelId>>,
+    /// makes follow ups fail, like they do once an interaction's token has expired
+    pub fail_followups: AtomicBool,
+    replies: Mutex<VecDeque<FakeReply>>,
+    last_id: AtomicU64,
+}
+
+impl FakeMessenger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// queue up the player's next reply (replies are used in order, then time out)
+    pub fn reply(&self, reply: FakeReply) {
+        self.replies.lock().unwrap().push_back(reply);
+    }
+
+    /// titles of the first embed of every message sent, in order
+    pub fn embed_titles(&self) -> Vec<String> {
+        self.sent
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(_, msg)| msg["embeds"][0]["title"].as_str().map(str::to_string))
+            .collect()
+    }
+
+    fn next_id(&self) -> MessageId {
+        MessageId::new(self.last_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn next_reply(&self) -> Option<String> {
+        match self.replies.lock().unwrap().pop_front()? {
+            FakeReply::Text(text) | FakeReply::Button(text) => Some(text),
+            FakeReply::FromLastMessage(build) => {
+                let sent = self.sent.lock().unwrap();
+                let (_, last) = sent.last()?;
+                Some(build(last))
+            }
+            FakeReply::Timeout => None,
+        }
+    }
+}
+
+#[async_trait]
+impl Messenger for FakeMessenger {
+    async fn send_message(&self, channel: ChannelId, message: CreateMessage) -> Result<MessageId, ReelError> {
+        let value = serde_json::to_value(&message).map_err(|e| ReelError::Serialize(e.to_string()))?;
+        self.sent.lock().unwrap().push((channel, value));
+        Ok(self.next_id())
+    }
+
+    async fn edit_message(&self, _channel: ChannelId, message: MessageId, edit: EditMessage) -> Result<(), ReelError> {
+        let value = serde_json::to_value(&edit).map_err(|e| ReelError::Serialize(e.to_string()))?;
+        self.message_edits.lock().unwrap().push((message, value));
+        Ok(())
+    }
+
+    async fn await_reply(&self, channel: ChannelId, _user: UserId, _timeout: Duration) -> Option<String> {
+        self.reply_channels.lock().unwrap().push(channel);
+        self.next_reply()
+    }
+
+    async fn await_button(&self, _message: MessageId, _user: UserId, _timeout: Duration) -> Option<String> {
+        self.next_reply()
+    }
+
+    async fn create_response(&self, _interaction: InteractionId, token: &str, response: CreateInteractionResponse) -> Result<(), ReelError> {
+        let value = serde_json::to_value(&response).map_err(|e| ReelError::Serialize(e.to_string()))?;
+        self.responses.lock().unwrap().push((token.to_string(), value));
+        Ok(())
+    }
+
+    async fn edit_response(&self, token: &str, edit: EditInteractionResponse) -> Result<(), ReelError> {
+        let value = serde_json::to_value(&edit).map_err(|e| ReelError::Serialize(e.to_string()))?;
+        self.edits.lock().unwrap().push((token.to_string(), value));
+        Ok(())
+    }
+
+    async fn send_followup(&self, token: &str, followup: CreateInteractionResponseFollowup) -> Result<MessageId, ReelError> {
+        if self.fail_followups.load(Ordering::Relaxed) {
+            return Err(ReelError::Discord(Box::new(serenity::Error::Other("Unknown Webhook"))));
+        }
+        let value = serde_json::to_value(&followup).map_err(|e| ReelError::Serialize(e.to_string()))?;
+        self.followups.lock().unwrap().push((token.to_string(), value));
+        Ok(self.next_id())
+    }
+
+    async fn edit_followup(&self, _token: &str, message: MessageId, edit: CreateInteractionResponseFollowup) -> Result<(), ReelError> {
+        let value = serde_json::to_value(&edit).map_err(|e| ReelError::Serialize(e.to_string()))?;
+        self.message_edits.lock().unwrap().push((message, value));
+        Ok(())
+    }
+
+    async fn send_dm(&self, user: UserId, message: CreateMessage) -> Result<(ChannelId, MessageId), ReelError> {
+        let value = serde_json::to_value(&message).map_err(|e| ReelError::Serialize(e.to_string()))?;
+        self.dms.lock().unwrap().push((user, value));
+        Ok((ChannelId::new(user.get()), self.next_id()))
+    }
+}
+
+/// A common fish that lives at every depth
+pub fn test_fish_type(category: FishCategory) -> FishType {
+    FishType {
+        name: "Test Minnow".to_string(),
+        rarity: FishRarity::Common,
+        category,
+        size_range: Attribute { min: 2.0, max: 4.0, average: 3.0 },
+        weight_range: Attribute { min: 0.5, max: 1.5, average: 1.0 },
+        depth_range: (0.0, 100_000.0),
+        base_value: 10.0,
+    }
+}
+
+pub fn test_pond() -> Pond {
+    Pond {
+        fish_types: vec![test_fish_type(FishCategory::BaitFish)],
+    }
+}
+
+/// A fish with an exact weight and value
+pub fn test_fish(weight: f32, value: f32) -> Fish {
+    let fish_type = test_fish_type(FishCategory::BaitFish);
+    Fish {
+        category: fish_type.category.clone(),
+        fish_type,
+        size: 3.0,
+        weight,
+        depth: 10.0,
+        value: MonetaryAmount::new(value),
+    }
+}